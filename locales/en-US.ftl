@@ -0,0 +1,86 @@
+# Strings in the default locale. Commands take their English names and
+# descriptions from the code, so only replies need to be here.
+#
+# Translations go in a file named after the Discord locale, such as `es-ES.ftl`.
+# Commands with a `key` are translated by a message with that ID, whose `name` and
+# `description` attributes are the command's, and each option by a message with
+# the ID `<key>-<option>`:
+#
+#     role-add =
+#         .name = añadir
+#         .description = Añade un rol.
+#     role-add-role =
+#         .name = rol
+#         .description = El rol a añadir.
+
+## Replies from the core
+
+command-unavailable = This command isn't available here.
+component-unavailable = This isn't available here.
+component-expired = This has expired. Use the command again to get a new one.
+done = Done.
+
+error-title = Something went wrong
+error-description = This has been logged. If it keeps happening, give staff the error ID `{ $id }`.
+
+denied-staff = Only staff can use this command.
+denied-owner = Only the bot owner can use this command.
+denied-channels = This command can only be used in { $channels }.
+feature-disabled = `{ $name }` is turned off here.
+
+cooldown =
+    { $secs ->
+        [one] Slow down! Try again in { $secs } second.
+       *[other] Slow down! Try again in { $secs } seconds.
+    }
+
+fields-omitted =
+    { $count ->
+        [one] { $count } more isn't shown.
+       *[other] { $count } more aren't shown.
+    }
+
+## /help
+
+help-title = Commands
+help-footer = Page { $page } of { $pages }
+help-empty = There are no commands you can use here.
+help-unknown = There's no command called `{ $name }` that you can use here.
+help-previous = Previous
+help-next = Next
+help-context-menu =
+    { $kind ->
+        [user] Right-click a user, then pick it under Apps.
+       *[message] Right-click a message, then pick it under Apps.
+    }
+help-no-options = No options.
+help-examples = Examples
+help-requires = Requires
+help-staff = The staff role
+help-owner = Owning the bot
+help-channels = Using it in { $channels }
+help-exported =
+    { $count ->
+        [one] Documented { $count } command.
+       *[other] Documented { $count } commands.
+    }
+
+## /config
+
+config-title = Settings
+config-empty = There are no settings to change here.
+config-default = { $value } (default)
+config-set = Set `{ $name }` to { $value }.
+config-reset = Reset `{ $name }` to { $value }.
+
+## /features
+
+features-title = Features
+features-on = on
+features-off = off
+features-enabled = Turned `{ $name }` on.
+features-disabled = Turned `{ $name }` off.
+
+## /activity
+
+activity-more = …and { $count } more.
@@ -0,0 +1,78 @@
+## Replies from the core
+
+command-unavailable = Este comando no está disponible aquí.
+component-unavailable = Esto no está disponible aquí.
+component-expired = Esto ha caducado. Vuelve a usar el comando para obtener uno nuevo.
+done = Hecho.
+
+error-title = Algo ha salido mal
+error-description = Se ha registrado el error. Si sigue pasando, da al staff el ID de error `{ $id }`.
+
+denied-staff = Solo el staff puede usar este comando.
+denied-owner = Solo el dueño del bot puede usar este comando.
+denied-channels = Este comando solo se puede usar en { $channels }.
+feature-disabled = `{ $name }` está desactivado aquí.
+
+cooldown =
+    { $secs ->
+        [one] ¡Más despacio! Vuelve a intentarlo en { $secs } segundo.
+       *[other] ¡Más despacio! Vuelve a intentarlo en { $secs } segundos.
+    }
+
+fields-omitted =
+    { $count ->
+        [one] No se muestra { $count } más.
+       *[other] No se muestran { $count } más.
+    }
+
+## /help
+
+help =
+    .name = ayuda
+    .description = Muestra los comandos que puedes usar aquí, o explica uno de ellos.
+help-command =
+    .name = comando
+    .description = El comando a explicar.
+
+help-title = Comandos
+help-footer = Página { $page } de { $pages }
+help-empty = No hay comandos que puedas usar aquí.
+help-unknown = No hay ningún comando llamado `{ $name }` que puedas usar aquí.
+help-previous = Anterior
+help-next = Siguiente
+help-context-menu =
+    { $kind ->
+        [user] Haz clic derecho en un usuario y elígelo en Aplicaciones.
+       *[message] Haz clic derecho en un mensaje y elígelo en Aplicaciones.
+    }
+help-no-options = Sin opciones.
+help-examples = Ejemplos
+help-requires = Requiere
+help-staff = El rol de staff
+help-owner = Ser dueño del bot
+help-channels = Usarlo en { $channels }
+help-exported =
+    { $count ->
+        [one] Se ha documentado { $count } comando.
+       *[other] Se han documentado { $count } comandos.
+    }
+
+## /config
+
+config-title = Ajustes
+config-empty = No hay ajustes que cambiar aquí.
+config-default = { $value } (predeterminado)
+config-set = Se ha cambiado `{ $name }` a { $value }.
+config-reset = Se ha restablecido `{ $name }` a { $value }.
+
+## /features
+
+features-title = Funciones
+features-on = activada
+features-off = desactivada
+features-enabled = Se ha activado `{ $name }`.
+features-disabled = Se ha desactivado `{ $name }`.
+
+## /activity
+
+activity-more = …y { $count } más.
@@ -0,0 +1,78 @@
+## Replies from the core
+
+command-unavailable = Este comando não está disponível aqui.
+component-unavailable = Isto não está disponível aqui.
+component-expired = Isto expirou. Use o comando de novo para obter um novo.
+done = Pronto.
+
+error-title = Algo deu errado
+error-description = Isso foi registrado. Se continuar acontecendo, passe para a staff o ID de erro `{ $id }`.
+
+denied-staff = Só a staff pode usar este comando.
+denied-owner = Só o dono do bot pode usar este comando.
+denied-channels = Este comando só pode ser usado em { $channels }.
+feature-disabled = `{ $name }` está desativado aqui.
+
+cooldown =
+    { $secs ->
+        [one] Calma! Tente de novo em { $secs } segundo.
+       *[other] Calma! Tente de novo em { $secs } segundos.
+    }
+
+fields-omitted =
+    { $count ->
+        [one] Mais { $count } não é mostrado.
+       *[other] Mais { $count } não são mostrados.
+    }
+
+## /help
+
+help =
+    .name = ajuda
+    .description = Mostra os comandos que você pode usar aqui, ou explica um deles.
+help-command =
+    .name = comando
+    .description = O comando a explicar.
+
+help-title = Comandos
+help-footer = Página { $page } de { $pages }
+help-empty = Não há comandos que você possa usar aqui.
+help-unknown = Não há nenhum comando chamado `{ $name }` que você possa usar aqui.
+help-previous = Anterior
+help-next = Próxima
+help-context-menu =
+    { $kind ->
+        [user] Clique com o botão direito em um usuário e escolha em Apps.
+       *[message] Clique com o botão direito em uma mensagem e escolha em Apps.
+    }
+help-no-options = Sem opções.
+help-examples = Exemplos
+help-requires = Requer
+help-staff = O cargo de staff
+help-owner = Ser dono do bot
+help-channels = Usá-lo em { $channels }
+help-exported =
+    { $count ->
+        [one] { $count } comando documentado.
+       *[other] { $count } comandos documentados.
+    }
+
+## /config
+
+config-title = Configurações
+config-empty = Não há configurações para mudar aqui.
+config-default = { $value } (padrão)
+config-set = `{ $name }` foi alterado para { $value }.
+config-reset = `{ $name }` foi redefinido para { $value }.
+
+## /features
+
+features-title = Recursos
+features-on = ativado
+features-off = desativado
+features-enabled = `{ $name }` foi ativado.
+features-disabled = `{ $name }` foi desativado.
+
+## /activity
+
+activity-more = …e mais { $count }.
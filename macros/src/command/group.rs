@@ -0,0 +1,182 @@
+use super::{doc_comment, kebab_case, validate, CommandInput};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Result};
+
+/// Derives `Command`, `FromInteraction` and `Handler` for an enum, where each
+/// variant wraps a subcommand or a subcommand group.
+///
+/// Subcommand names and nesting depth are only known once the wrapped types
+/// implement `Command`, so those are checked by const assertions in the output
+/// rather than by the macro itself.
+pub fn derive(input: CommandInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let name = input.name.clone().unwrap_or_else(|| kebab_case(ident));
+    let desc = input
+        .desc
+        .clone()
+        .unwrap_or_else(|| doc_comment(&input.attrs));
+
+    validate::name(&name).map_err(|e| Error::new_spanned(ident, e))?;
+    validate::description(&desc).map_err(|e| Error::new_spanned(ident, e))?;
+
+    let items = input.items()?;
+    let variants = input.data.take_enum().expect("darling only allows enums");
+
+    if variants.is_empty() {
+        return Err(Error::new_spanned(
+            ident,
+            "commands with subcommands need at least one",
+        ));
+    }
+
+    if variants.len() > validate::MAX_OPTIONS {
+        return Err(Error::new_spanned(
+            ident,
+            format!(
+                "commands can have at most {} subcommands",
+                validate::MAX_OPTIONS
+            ),
+        ));
+    }
+
+    let idents = variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let types = variants
+        .iter()
+        .map(|v| &v.fields.fields[0].ty)
+        .collect::<Vec<_>>();
+
+    let mut unique = Vec::new();
+
+    for (i, a) in types.iter().enumerate() {
+        for b in &types[i + 1..] {
+            unique.push(quote! {
+                assert!(
+                    !crate::core::command::__str_eq(
+                        <#a as crate::core::command::Command>::NAME,
+                        <#b as crate::core::command::Command>::NAME,
+                    ),
+                    "two subcommands have the same name",
+                );
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl crate::core::command::FromInteraction for #ident {
+            fn from_options(
+                options: &[::serenity::model::application::interaction::application_command::CommandDataOption],
+            ) -> ::std::result::Result<Self, crate::core::command::ArgumentError> {
+                let subcommand = options
+                    .first()
+                    .ok_or(crate::core::command::ArgumentError::MissingSubcommand)?;
+
+                #(
+                    if subcommand.name == <#types as crate::core::command::Command>::NAME {
+                        return <#types as crate::core::command::FromInteraction>::from_options(
+                            &subcommand.options,
+                        )
+                        .map(Self::#idents);
+                    }
+                )*
+
+                Err(crate::core::command::ArgumentError::UnknownSubcommand {
+                    name: subcommand.name.clone(),
+                })
+            }
+        }
+
+        impl crate::core::command::Command for #ident {
+            const NAME: &'static str = #name;
+            const DESC: &'static str = #desc;
+
+            #items
+
+            const DEPTH: u8 = {
+                let mut depth = 0;
+
+                #(
+                    if <#types as crate::core::command::Command>::DEPTH > depth {
+                        depth = <#types as crate::core::command::Command>::DEPTH;
+                    }
+                )*
+
+                depth + 1
+            };
+
+            fn options() -> ::std::vec::Vec<crate::core::command::OptionSchema> {
+                vec![#(<#types as crate::core::command::Command>::as_option()),*]
+            }
+
+            fn examples() -> ::std::vec::Vec<&'static str> {
+                let mut examples = Self::EXAMPLES.to_vec();
+                #(examples.extend(<#types as crate::core::command::Command>::examples());)*
+                examples
+            }
+
+            fn autocomplete<'a>(
+                ctx: &'a crate::core::command::AutocompleteContext,
+                options: &'a [::serenity::model::application::interaction::application_command::CommandDataOption],
+            ) -> Option<crate::core::command::AutocompleteFuture<'a>> {
+                let subcommand = options.first()?;
+
+                #(
+                    if subcommand.name == <#types as crate::core::command::Command>::NAME {
+                        return <#types as crate::core::command::Command>::autocomplete(
+                            ctx,
+                            &subcommand.options,
+                        );
+                    }
+                )*
+
+                None
+            }
+        }
+
+        #[::serenity::async_trait]
+        impl crate::core::command::Handler for #ident {
+            async fn handle(
+                self,
+                ctx: &crate::core::command::CommandContext,
+            ) -> crate::core::command::CommandResult {
+                match self {
+                    #(
+                        Self::#idents(command) => {
+                            crate::core::command::check(
+                                ctx,
+                                <#types as crate::core::command::Command>::REQUIRES,
+                            )
+                            .await?;
+
+                            crate::core::command::throttle(
+                                ctx,
+                                ::std::any::TypeId::of::<#types>(),
+                                <#types as crate::core::command::Command>::COOLDOWN,
+                            )
+                            .await?;
+
+                            command.handle(ctx).await
+                        }
+                    )*
+                }
+            }
+        }
+
+        const _: () = {
+            assert!(
+                <#ident as crate::core::command::Command>::DEPTH
+                    <= crate::core::command::MAX_DEPTH,
+                "subcommand groups can only contain subcommands",
+            );
+
+            #(
+                assert!(
+                    <#types as crate::core::command::Command>::DEFAULT_PERMISSIONS.is_none(),
+                    "subcommands can't have `default_permissions`, set them on the top-level command",
+                );
+            )*
+
+            #(#unique)*
+        };
+    })
+}
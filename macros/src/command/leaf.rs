@@ -0,0 +1,343 @@
+use super::{call_site, doc_comment, kebab_case, validate, Choice, CommandInput, OptionField};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{Error, Lit, Result};
+
+/// Derives `Command` and `FromInteraction` for an argument struct, where each
+/// field is an option.
+pub fn derive(input: CommandInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let name = input.name.clone().unwrap_or_else(|| kebab_case(ident));
+    let desc = input
+        .desc
+        .clone()
+        .unwrap_or_else(|| doc_comment(&input.attrs));
+
+    validate::name(&name).map_err(|e| Error::new_spanned(ident, e))?;
+    validate::description(&desc).map_err(|e| Error::new_spanned(ident, e))?;
+
+    let items = input.items()?;
+    let fields = input
+        .data
+        .take_struct()
+        .expect("darling only allows structs")
+        .fields;
+
+    if fields.len() > validate::MAX_OPTIONS {
+        return Err(Error::new_spanned(
+            ident,
+            format!(
+                "commands can have at most {} options",
+                validate::MAX_OPTIONS
+            ),
+        ));
+    }
+
+    let mut names = Vec::new();
+    let mut schemas = Vec::new();
+    let mut parsers = Vec::new();
+    let mut providers = Vec::new();
+    let mut seen_optional = false;
+
+    for field in &fields {
+        let mut option = OptionDef::parse(field)?;
+
+        if names.contains(&option.name) {
+            return Err(Error::new_spanned(
+                &field.ident,
+                format!("duplicate option name `{}`", option.name),
+            ));
+        }
+
+        // Discord rejects commands where a required option follows an optional one.
+        if option.required && seen_optional {
+            return Err(Error::new_spanned(
+                &field.ident,
+                "required options must come before optional ones",
+            ));
+        }
+
+        if let Some(key) = &input.key {
+            let key = format!("{key}-{}", option.name);
+            option.modifiers.push(quote!(.localize(#key)));
+        }
+
+        seen_optional |= !option.required;
+        names.push(option.name.clone());
+        schemas.push(option.schema(&field.ty));
+        parsers.push(option.parser(field));
+
+        if let Some(provider) = &field.autocomplete {
+            let name = &option.name;
+            providers.push(quote!(#name => Some(Box::pin(#provider(ctx)))));
+        }
+    }
+
+    let construct = match fields.is_empty() {
+        true => quote!(Self),
+        false => quote!(Self { #(#parsers,)* }),
+    };
+
+    // Commands without autocomplete options keep the default, which does nothing.
+    let autocomplete = match providers.is_empty() {
+        true => quote!(),
+        false => quote! {
+            fn autocomplete<'a>(
+                ctx: &'a crate::core::command::AutocompleteContext,
+                options: &'a [::serenity::model::application::interaction::application_command::CommandDataOption],
+            ) -> Option<crate::core::command::AutocompleteFuture<'a>> {
+                let focused = options.iter().find(|option| option.focused)?;
+
+                match focused.name.as_str() {
+                    #(#providers,)*
+                    _ => None,
+                }
+            }
+        },
+    };
+
+    Ok(quote! {
+        impl crate::core::command::FromInteraction for #ident {
+            fn from_options(
+                options: &[::serenity::model::application::interaction::application_command::CommandDataOption],
+            ) -> ::std::result::Result<Self, crate::core::command::ArgumentError> {
+                Ok(#construct)
+            }
+        }
+
+        impl crate::core::command::Command for #ident {
+            const NAME: &'static str = #name;
+            const DESC: &'static str = #desc;
+
+            #items
+
+            fn options() -> ::std::vec::Vec<crate::core::command::OptionSchema> {
+                vec![#(#schemas),*]
+            }
+
+            #autocomplete
+        }
+    })
+}
+
+/// A single validated option.
+struct OptionDef {
+    name: String,
+    desc: String,
+    required: bool,
+    modifiers: Vec<TokenStream>,
+}
+
+impl OptionDef {
+    fn parse(field: &OptionField) -> Result<Self> {
+        let ident = field
+            .ident
+            .as_ref()
+            .expect("darling only allows named fields");
+        let name = field
+            .name
+            .clone()
+            .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+        let desc = field
+            .desc
+            .clone()
+            .unwrap_or_else(|| doc_comment(&field.attrs));
+
+        validate::name(&name).map_err(|e| Error::new_spanned(ident, e))?;
+        validate::description(&desc).map_err(|e| Error::new_spanned(ident, e))?;
+
+        let mut modifiers = Vec::new();
+
+        let min = field.min.as_ref().map(number).transpose()?;
+        let max = field.max.as_ref().map(number).transpose()?;
+
+        if let (Some((min, _)), Some((max, _))) = (&min, &max) {
+            if min > max {
+                return Err(Error::new_spanned(ident, "`min` is larger than `max`"));
+            }
+        }
+
+        if let Some((_, min)) = min {
+            modifiers.push(quote!(.min_value(#min)));
+        }
+
+        if let Some((_, max)) = max {
+            modifiers.push(quote!(.max_value(#max)));
+        }
+
+        if let (Some(min), Some(max)) = (field.min_length, field.max_length) {
+            if min > max {
+                return Err(Error::new_spanned(
+                    ident,
+                    "`min_length` is larger than `max_length`",
+                ));
+            }
+        }
+
+        if let Some(min) = field.min_length {
+            modifiers.push(quote!(.min_length(#min)));
+        }
+
+        if let Some(max) = field.max_length {
+            if !(1..=6000).contains(&max) {
+                return Err(Error::new_spanned(ident, "`max_length` must be 1-6000"));
+            }
+
+            modifiers.push(quote!(.max_length(#max)));
+        }
+
+        modifiers.extend(choices(ident, &field.choice)?);
+
+        if let Some(types) = &field.channel_types {
+            let types = types.iter().map(channel_type).collect::<Result<Vec<_>>>()?;
+
+            modifiers.push(quote!(.channel_types(&[#(#types),*])));
+        }
+
+        if field.autocomplete.is_some() {
+            if !field.choice.is_empty() {
+                return Err(Error::new_spanned(
+                    ident,
+                    "options can't have both choices and autocomplete",
+                ));
+            }
+
+            modifiers.push(quote!(.autocomplete()));
+        }
+
+        Ok(Self {
+            name,
+            desc,
+            required: !is_option(&field.ty),
+            modifiers,
+        })
+    }
+
+    /// The expression building the option's schema.
+    fn schema(&self, ty: &syn::Type) -> TokenStream {
+        let Self {
+            name,
+            desc,
+            modifiers,
+            ..
+        } = self;
+
+        quote! {
+            crate::core::command::OptionSchema::new(
+                <#ty as crate::core::command::FromOption>::KIND,
+                #name,
+                #desc,
+                <#ty as crate::core::command::FromOption>::REQUIRED,
+            )
+            #(#modifiers)*
+        }
+    }
+
+    /// The field initializer parsing the option.
+    fn parser(&self, field: &OptionField) -> TokenStream {
+        let ident = &field.ident;
+        let name = &self.name;
+
+        quote!(#ident: crate::core::command::parse_option(options, #name)?)
+    }
+}
+
+/// Whether a type is syntactically an `Option`, and therefore an optional option.
+/// This has to be decided syntactically to check option order at compile time.
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// Parses a numeric literal, returning its value for validation and its tokens.
+fn number(lit: &Lit) -> Result<(f64, TokenStream)> {
+    match lit {
+        Lit::Int(int) => {
+            let value = int.base10_parse::<i64>()?;
+            Ok((value as f64, value.into_token_stream()))
+        }
+        Lit::Float(float) => {
+            let value = float.base10_parse::<f64>()?;
+            Ok((value, value.into_token_stream()))
+        }
+        Lit::Str(s) => {
+            if let Ok(value) = s.value().parse::<i64>() {
+                return Ok((value as f64, value.into_token_stream()));
+            }
+
+            match s.value().parse::<f64>() {
+                Ok(value) => Ok((value, value.into_token_stream())),
+                Err(_) => Err(Error::new_spanned(s, "expected a number")),
+            }
+        }
+        _ => Err(Error::new_spanned(lit, "expected a number")),
+    }
+}
+
+/// Validates the fixed choices of an option and returns their modifiers.
+fn choices(ident: &syn::Ident, choices: &[Choice]) -> Result<Vec<TokenStream>> {
+    if choices.len() > validate::MAX_CHOICES {
+        return Err(Error::new_spanned(
+            ident,
+            format!("options can have at most {} choices", validate::MAX_CHOICES),
+        ));
+    }
+
+    let kinds = choices
+        .iter()
+        .map(|choice| std::mem::discriminant(&choice.value))
+        .collect::<Vec<_>>();
+
+    if kinds.windows(2).any(|pair| pair[0] != pair[1]) {
+        return Err(Error::new_spanned(
+            ident,
+            "choices must all have the same type",
+        ));
+    }
+
+    choices
+        .iter()
+        .map(|Choice { name, value }| {
+            validate::choice_name(name).map_err(call_site)?;
+
+            let value = match value {
+                Lit::Str(s) => {
+                    validate::choice_value(&s.value()).map_err(|e| Error::new_spanned(s, e))?;
+                    s.into_token_stream()
+                }
+                _ => number(value)?.1,
+            };
+
+            Ok(quote!(.choice(#name, #value)))
+        })
+        .collect()
+}
+
+/// Maps a channel type name to its Discord ID.
+fn channel_type(path: &syn::Path) -> Result<u8> {
+    let name = path
+        .get_ident()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+
+    let id = match name.as_str() {
+        "text" => 0,
+        "voice" => 2,
+        "category" => 4,
+        "news" => 5,
+        "news_thread" => 10,
+        "public_thread" => 11,
+        "private_thread" => 12,
+        "stage" => 13,
+        "forum" => 15,
+        _ => return Err(Error::new_spanned(path, "unknown channel type")),
+    };
+
+    Ok(id)
+}
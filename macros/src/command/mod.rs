@@ -0,0 +1,315 @@
+use crate::duration::parse_secs;
+use darling::{ast, util::PathList, FromDeriveInput, FromField, FromMeta, FromVariant};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{spanned::Spanned, Attribute, Lit, Meta};
+
+mod group;
+mod leaf;
+mod validate;
+
+/// The `command` attributes on the type deriving `Command`.
+#[derive(Debug, FromDeriveInput)]
+#[darling(
+    attributes(command),
+    forward_attrs(doc),
+    supports(struct_named, struct_unit, enum_newtype)
+)]
+pub struct CommandInput {
+    ident: syn::Ident,
+    generics: syn::Generics,
+    data: ast::Data<SubcommandVariant, OptionField>,
+    attrs: Vec<Attribute>,
+
+    /// Overrides the name of the command, which is otherwise the kebab-cased name
+    /// of the type.
+    #[darling(default)]
+    name: Option<String>,
+
+    /// Overrides the description, which is otherwise taken from the doc comment.
+    #[darling(default)]
+    desc: Option<String>,
+
+    /// The ID of the Fluent message translating the command, which also prefixes
+    /// those of its options, e.g. `key = "role-add"`.
+    #[darling(default)]
+    key: Option<String>,
+
+    /// The permissions members need to see the command by default, such as
+    /// `default_permissions(ban_members)`.
+    #[darling(default)]
+    default_permissions: Option<PathList>,
+
+    /// Only lets staff use the command.
+    #[darling(default)]
+    staff: bool,
+
+    /// Only lets the bot owner use the command.
+    #[darling(default)]
+    owner: bool,
+
+    /// Only lets the command be used in some channels, given as paths to
+    /// `ChannelId` constants.
+    #[darling(default)]
+    channels: Option<PathList>,
+
+    /// Rate limits the command, such as `cooldown(per = "user", uses = 3, every = "30s")`.
+    #[darling(default)]
+    cooldown: Option<CooldownArgs>,
+
+    /// An example of using the command, shown by `/help`. Can be given multiple
+    /// times, e.g. `example = "/role add role: Artist"`.
+    #[darling(default, multiple)]
+    example: Vec<String>,
+}
+
+/// A `cooldown(...)` attribute.
+#[derive(Debug, FromMeta)]
+pub struct CooldownArgs {
+    /// What the limit is counted against: `user`, `channel` or `guild`.
+    #[darling(default)]
+    per: Option<String>,
+
+    /// How many uses are allowed in each window. Defaults to one.
+    #[darling(default)]
+    uses: Option<u32>,
+
+    /// How long each window is, such as `30s` or `5m`.
+    every: String,
+}
+
+impl CommandInput {
+    /// The `KEY`, `DEFAULT_PERMISSIONS`, `REQUIRES`, `COOLDOWN` and `EXAMPLES`
+    /// items of the `Command` impl, for those that are set.
+    fn items(&self) -> syn::Result<TokenStream> {
+        let mut items = Vec::new();
+
+        if let Some(key) = &self.key {
+            items.push(quote!(const KEY: Option<&'static str> = Some(#key);));
+        }
+
+        if let Some(permissions) = &self.default_permissions {
+            let permissions = permissions
+                .iter()
+                .map(|path| {
+                    let name = path.get_ident().ok_or_else(|| {
+                        syn::Error::new_spanned(
+                            path,
+                            "expected a permission name such as `manage_guild`",
+                        )
+                    })?;
+                    let ident = syn::Ident::new(&name.to_string().to_uppercase(), path.span());
+
+                    Ok(quote!(::serenity::model::Permissions::#ident.bits()))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            items.push(quote! {
+                const DEFAULT_PERMISSIONS: Option<::serenity::model::Permissions> = Some(
+                    ::serenity::model::Permissions::from_bits_truncate(0 #(| #permissions)*),
+                );
+            });
+        }
+
+        let mut requires = Vec::new();
+
+        if self.staff {
+            requires.push(quote!(crate::core::command::Requirement::Staff));
+        }
+
+        if self.owner {
+            requires.push(quote!(crate::core::command::Requirement::Owner));
+        }
+
+        if let Some(channels) = &self.channels {
+            let channels = channels.iter();
+            requires.push(quote!(crate::core::command::Requirement::Channels(&[#(#channels),*])));
+        }
+
+        if !requires.is_empty() {
+            items.push(quote! {
+                const REQUIRES: &'static [crate::core::command::Requirement] = &[#(#requires),*];
+            });
+        }
+
+        if let Some(cooldown) = &self.cooldown {
+            items.push(cooldown.item(&self.ident)?);
+        }
+
+        if !self.example.is_empty() {
+            let examples = &self.example;
+            items.push(quote!(const EXAMPLES: &'static [&'static str] = &[#(#examples),*];));
+        }
+
+        Ok(quote!(#(#items)*))
+    }
+}
+
+impl CooldownArgs {
+    /// The `COOLDOWN` item of the `Command` impl.
+    fn item(&self, ident: &syn::Ident) -> syn::Result<TokenStream> {
+        let per = match self.per.as_deref().unwrap_or("user") {
+            "user" => quote!(User),
+            "channel" => quote!(Channel),
+            "guild" => quote!(Guild),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "`per` must be `user`, `channel` or `guild`",
+                ))
+            }
+        };
+
+        let uses = match self.uses.unwrap_or(1) {
+            0 => return Err(syn::Error::new_spanned(ident, "`uses` must be at least 1")),
+            uses => uses,
+        };
+
+        let every = match parse_secs(&self.every) {
+            Ok(0) => return Err(syn::Error::new_spanned(ident, "`every` must be positive")),
+            Ok(secs) => secs,
+            Err(e) => return Err(syn::Error::new_spanned(ident, e)),
+        };
+
+        Ok(quote! {
+            const COOLDOWN: Option<crate::core::command::Cooldown> = Some(crate::core::command::Cooldown {
+                per: crate::core::command::Bucket::#per,
+                uses: #uses,
+                every: ::std::time::Duration::from_secs(#every),
+            });
+        })
+    }
+}
+
+/// The `option` attributes on a field of the type deriving `Command`.
+#[derive(Debug, FromField)]
+#[darling(attributes(option), forward_attrs(doc))]
+pub struct OptionField {
+    ident: Option<syn::Ident>,
+    ty: syn::Type,
+    attrs: Vec<Attribute>,
+
+    /// Overrides the name of the option, which is otherwise the field name.
+    #[darling(default)]
+    name: Option<String>,
+
+    /// Overrides the description, which is otherwise taken from the doc comment.
+    #[darling(default)]
+    desc: Option<String>,
+
+    /// Minimum value of a numeric option. Negative numbers must be given as strings.
+    #[darling(default)]
+    min: Option<Lit>,
+
+    /// Maximum value of a numeric option. Negative numbers must be given as strings.
+    #[darling(default)]
+    max: Option<Lit>,
+
+    /// Minimum length of a string option.
+    #[darling(default)]
+    min_length: Option<u16>,
+
+    /// Maximum length of a string option.
+    #[darling(default)]
+    max_length: Option<u16>,
+
+    /// A fixed choice, can be given multiple times.
+    #[darling(default, multiple)]
+    choice: Vec<Choice>,
+
+    /// The channel types allowed for a channel option, e.g. `channel_types(text, news)`.
+    #[darling(default)]
+    channel_types: Option<PathList>,
+
+    /// Enables autocomplete for the option, with the path of an async function
+    /// providing the suggestions, e.g. `autocomplete = "role_names"`.
+    #[darling(default)]
+    autocomplete: Option<syn::Path>,
+}
+
+/// A variant of an enum deriving `Command`, which wraps a subcommand or a
+/// subcommand group.
+#[derive(Debug, FromVariant)]
+pub struct SubcommandVariant {
+    ident: syn::Ident,
+    fields: ast::Fields<SubcommandField>,
+}
+
+/// The single field of a `SubcommandVariant`.
+#[derive(Debug, FromField)]
+pub struct SubcommandField {
+    ty: syn::Type,
+}
+
+/// A `choice(name = "...", value = ...)` attribute.
+#[derive(Debug, FromMeta)]
+pub struct Choice {
+    name: String,
+    value: Lit,
+}
+
+pub fn derive(input: syn::DeriveInput) -> TokenStream {
+    let input = match CommandInput::from_derive_input(&input) {
+        Ok(input) => input,
+        Err(e) => return e.write_errors(),
+    };
+
+    if !input.generics.params.is_empty() {
+        return error(&input.generics, "commands can't be generic");
+    }
+
+    let output = match input.data.is_enum() {
+        true => group::derive(input),
+        false => leaf::derive(input),
+    };
+
+    match output {
+        Ok(output) => output,
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+/// Joins the doc comments of an item into a single line, which is how
+/// descriptions are written.
+pub(crate) fn doc_comment(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(meta)) => match meta.lit {
+                Lit::Str(s) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Converts a type name such as `RoleAdd` into a command name such as `role-add`.
+pub(crate) fn kebab_case(ident: &syn::Ident) -> String {
+    let mut name = String::new();
+
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            name.push('-');
+        }
+
+        name.extend(c.to_lowercase());
+    }
+
+    name
+}
+
+fn error(spanned: &impl quote::ToTokens, message: &str) -> TokenStream {
+    syn::Error::new_spanned(spanned, message).to_compile_error()
+}
+
+/// Shorthand for a validation error at the call site.
+fn call_site(message: String) -> syn::Error {
+    syn::Error::new(Span::call_site(), message)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,283 @@
+use super::{derive, kebab_case, validate};
+use syn::parse_quote;
+
+/// The compile errors the derive emits for an input, if any.
+fn errors(input: syn::DeriveInput) -> Option<String> {
+    let output = derive(input).to_string();
+
+    output.contains("compile_error").then_some(output)
+}
+
+/// Asserts that the derive fails with an error containing a message.
+#[track_caller]
+fn assert_error(input: syn::DeriveInput, message: &str) {
+    match errors(input) {
+        Some(errors) => assert!(errors.contains(message), "{errors}"),
+        None => panic!("expected an error containing {message:?}"),
+    }
+}
+
+#[test]
+fn names_are_kebab_cased_type_names() {
+    assert_eq!(kebab_case(&parse_quote!(Ping)), "ping");
+    assert_eq!(kebab_case(&parse_quote!(RoleAdd)), "role-add");
+}
+
+#[test]
+fn validates_names() {
+    assert!(validate::name("role-add").is_ok());
+    assert!(validate::name("user_2").is_ok());
+    assert!(validate::name("").is_err());
+    assert!(validate::name("Role").is_err());
+    assert!(validate::name("role add").is_err());
+    assert!(validate::name(&"a".repeat(33)).is_err());
+}
+
+#[test]
+fn validates_descriptions() {
+    assert!(validate::description("Pings the bot.").is_ok());
+    assert!(validate::description(&"a".repeat(100)).is_ok());
+    assert!(validate::description("").is_err());
+    assert!(validate::description(&"a".repeat(101)).is_err());
+}
+
+#[test]
+fn accepts_a_valid_command() {
+    let output = errors(parse_quote! {
+        /// Adds a role.
+        #[command(default_permissions(manage_roles, ban_members), staff)]
+        struct RoleAdd {
+            /// The role to add.
+            role: Role,
+
+            /// How many days to add it for.
+            #[option(min = 1, max = 30)]
+            days: Option<i64>,
+
+            /// Why it's being added.
+            #[option(choice(name = "Event", value = "event"))]
+            reason: Option<String>,
+        }
+    });
+
+    assert_eq!(output, None);
+}
+
+#[test]
+fn requires_a_description() {
+    assert_error(
+        parse_quote! {
+            struct Ping;
+        },
+        "description is missing",
+    );
+}
+
+#[test]
+fn rejects_paths_as_permissions() {
+    assert_error(
+        parse_quote! {
+            /// Bans someone.
+            #[command(default_permissions(Permissions::BAN_MEMBERS))]
+            struct Ban;
+        },
+        "expected a permission name such as `manage_guild`",
+    );
+}
+
+#[test]
+fn rejects_required_options_after_optional_ones() {
+    assert_error(
+        parse_quote! {
+            /// Adds a role.
+            struct Add {
+                /// The role to add.
+                role: Option<Role>,
+
+                /// Who to add it to.
+                user: User,
+            }
+        },
+        "required options must come before optional ones",
+    );
+}
+
+#[test]
+fn rejects_duplicate_option_names() {
+    assert_error(
+        parse_quote! {
+            /// Adds a role.
+            struct Add {
+                /// The role to add.
+                role: Role,
+
+                /// Another role to add.
+                #[option(name = "role")]
+                other: Role,
+            }
+        },
+        "duplicate option name `role`",
+    );
+}
+
+#[test]
+fn rejects_inverted_ranges() {
+    assert_error(
+        parse_quote! {
+            /// Rolls a die.
+            struct Roll {
+                /// How many sides the die has.
+                #[option(min = 6, max = 1)]
+                sides: i64,
+            }
+        },
+        "`min` is larger than `max`",
+    );
+}
+
+#[test]
+fn rejects_mixed_choice_types() {
+    assert_error(
+        parse_quote! {
+            /// Rolls a die.
+            struct Roll {
+                /// How many sides the die has.
+                #[option(choice(name = "Six", value = 6), choice(name = "Ten", value = "10"))]
+                sides: i64,
+            }
+        },
+        "choices must all have the same type",
+    );
+}
+
+#[test]
+fn rejects_long_choice_values() {
+    let value = "a".repeat(101);
+
+    assert_error(
+        parse_quote! {
+            /// Picks a colour.
+            struct Colour {
+                /// The colour to pick.
+                #[option(choice(name = "Red", value = #value))]
+                colour: String,
+            }
+        },
+        "choice value is 101 characters long, the limit is 100",
+    );
+}
+
+#[test]
+fn rejects_choices_with_autocomplete() {
+    assert_error(
+        parse_quote! {
+            /// Picks a colour.
+            struct Colour {
+                /// The colour to pick.
+                #[option(choice(name = "Red", value = "red"), autocomplete = "colours")]
+                colour: String,
+            }
+        },
+        "options can't have both choices and autocomplete",
+    );
+}
+
+#[test]
+fn rejects_too_many_options() {
+    let fields = (0..26).map(|i| quote::format_ident!("option_{i}"));
+
+    assert_error(
+        parse_quote! {
+            /// Takes too many options.
+            struct Many {
+                #(
+                    /// An option.
+                    #fields: i64,
+                )*
+            }
+        },
+        "commands can have at most 25 options",
+    );
+}
+
+#[test]
+fn rejects_bad_cooldowns() {
+    assert_error(
+        parse_quote! {
+            /// Pings the bot.
+            #[command(cooldown(per = "server", every = "5s"))]
+            struct Ping;
+        },
+        "`per` must be `user`, `channel` or `guild`",
+    );
+
+    assert_error(
+        parse_quote! {
+            /// Pings the bot.
+            #[command(cooldown(uses = 0, every = "5s"))]
+            struct Ping;
+        },
+        "`uses` must be at least 1",
+    );
+
+    assert_error(
+        parse_quote! {
+            /// Pings the bot.
+            #[command(cooldown(every = "300000000000000d"))]
+            struct Ping;
+        },
+        "is too long",
+    );
+}
+
+#[test]
+fn accepts_a_valid_group() {
+    let output = errors(parse_quote! {
+        /// Manages roles.
+        #[command(default_permissions(manage_roles))]
+        enum Role {
+            Add(role::Add),
+            Remove(role::Remove),
+        }
+    });
+
+    assert_eq!(output, None);
+}
+
+#[test]
+fn rejects_empty_groups() {
+    assert_error(
+        parse_quote! {
+            /// Manages roles.
+            enum Role {}
+        },
+        "commands with subcommands need at least one",
+    );
+}
+
+#[test]
+fn rejects_variants_that_arent_newtypes() {
+    assert!(errors(parse_quote! {
+        /// Manages roles.
+        enum Role {
+            Add { role: Role },
+        }
+    })
+    .is_some());
+}
+
+#[test]
+fn checks_subcommands_once_they_implement_command() {
+    let output = derive(parse_quote! {
+        /// Manages roles.
+        enum Role {
+            Add(role::Add),
+            Remove(role::Remove),
+        }
+    })
+    .to_string();
+
+    assert!(output.contains("two subcommands have the same name"));
+    assert!(output.contains("subcommand groups can only contain subcommands"));
+    assert!(output.contains("subcommands can't have `default_permissions`"));
+}
@@ -0,0 +1,61 @@
+//! Compile-time checks against Discord's limits on commands, so that a bad
+//! definition fails the build rather than the upload.
+
+/// The most options (or subcommands) a single command can have.
+pub const MAX_OPTIONS: usize = 25;
+
+/// The most fixed choices a single option can have.
+pub const MAX_CHOICES: usize = 25;
+
+/// Checks a command, subcommand or option name. Names must be 1-32 characters
+/// of lowercase letters, numbers, `-` and `_`.
+pub fn name(name: &str) -> Result<(), String> {
+    let len = name.chars().count();
+
+    if !(1..=32).contains(&len) {
+        return Err(format!("name `{name}` must be 1-32 characters long"));
+    }
+
+    let valid = name
+        .chars()
+        .all(|c| c == '-' || c == '_' || (c.is_alphanumeric() && !c.is_uppercase()));
+
+    if !valid {
+        return Err(format!(
+            "name `{name}` may only contain lowercase letters, numbers, `-` and `_`"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks a command or option description, which must be 1-100 characters.
+pub fn description(description: &str) -> Result<(), String> {
+    match description.chars().count() {
+        0 => Err("description is missing, add a doc comment or a `desc`".into()),
+        n if n > 100 => Err(format!(
+            "description is {n} characters long, the limit is 100"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the name of a fixed choice, which must be 1-100 characters.
+pub fn choice_name(name: &str) -> Result<(), String> {
+    match name.chars().count() {
+        1..=100 => Ok(()),
+        _ => Err(format!(
+            "choice name `{name}` must be 1-100 characters long"
+        )),
+    }
+}
+
+/// Checks the value of a fixed string choice, which can be at most 100 characters.
+pub fn choice_value(value: &str) -> Result<(), String> {
+    match value.chars().count() {
+        n if n > 100 => Err(format!(
+            "choice value is {n} characters long, the limit is 100"
+        )),
+        _ => Ok(()),
+    }
+}
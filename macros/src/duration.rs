@@ -0,0 +1,43 @@
+/// Parses a human-readable duration such as `90s`, `15m` or `1h30m` into
+/// a number of seconds. Supported units are `s`, `m`, `h` and `d`.
+pub fn parse_secs(input: &str) -> Result<u64, String> {
+    let mut total = 0u64;
+    let mut digits = String::new();
+
+    for c in input.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 60 * 60 * 24,
+            _ => return Err(format!("unknown duration unit `{c}` in `{input}`")),
+        };
+
+        if digits.is_empty() {
+            return Err(format!("missing amount before `{c}` in `{input}`"));
+        }
+
+        total = digits
+            .parse::<u64>()
+            .ok()
+            .and_then(|amount| amount.checked_mul(unit))
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| format!("duration `{input}` is too long"))?;
+        digits.clear();
+    }
+
+    if !digits.is_empty() {
+        return Err(format!("missing unit after `{digits}` in `{input}`"));
+    }
+
+    if total == 0 {
+        return Err(format!("duration `{input}` must be longer than zero"));
+    }
+
+    Ok(total)
+}
@@ -5,16 +5,31 @@ use quote::quote;
 pub fn init(mut function: syn::ItemFn) -> TokenStream {
     let ident = function.sig.ident;
     let fn_ident = Ident::new(&format!("{}_body", &ident), Span::call_site());
+    let exec_ident = Ident::new(&format!("{}_exec", &ident), Span::call_site());
 
     function.sig.ident = fn_ident.clone();
 
+    // Initializers may be sync or async, but are always stored as a function
+    // returning a boxed future, so sync ones are wrapped in an async block.
+    let call = match function.sig.asyncness {
+        Some(_) => quote!(#fn_ident(args).await),
+        None => quote!(#fn_ident(args)),
+    };
+
     let output = quote! {
         #[allow(non_upper_case_globals)]
         const #ident: crate::core::init::Init = Init {
             name: stringify!(#ident),
-            __exec: #fn_ident,
+            feature: None,
+            __exec: #exec_ident,
         };
 
+        fn #exec_ident<'a>(
+            args: &'a crate::core::init::InitArgs<'a>,
+        ) -> crate::core::init::InitFuture<'a> {
+            Box::pin(async move { #call })
+        }
+
         #function
     };
 
@@ -0,0 +1,72 @@
+use crate::duration::parse_secs;
+use darling::FromMeta;
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span};
+use quote::quote;
+
+/// Arguments to the `job` attribute, which make up the retry policy.
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+pub struct JobArgs {
+    /// How many times the job is attempted before it's dead-lettered.
+    max_attempts: Option<u32>,
+
+    /// The delay before the first retry, which doubles with each retry after.
+    backoff: Option<String>,
+}
+
+pub fn job(args: JobArgs, mut function: syn::ItemFn) -> TokenStream {
+    let ident = function.sig.ident;
+    let vis = &function.vis;
+    let fn_ident = Ident::new(&format!("{}_body", &ident), Span::call_site());
+    let exec_ident = Ident::new(&format!("{}_exec", &ident), Span::call_site());
+
+    if function.sig.asyncness.is_none() {
+        return syn::Error::new_spanned(function.sig.fn_token, "jobs must be `async fn`s")
+            .to_compile_error()
+            .into();
+    }
+
+    let default = quote!(crate::core::job::RetryPolicy::DEFAULT);
+
+    let max_attempts = match args.max_attempts {
+        Some(0) => {
+            return syn::Error::new_spanned(&ident, "`max_attempts` must be at least 1")
+                .to_compile_error()
+                .into()
+        }
+        Some(n) => quote!(#n),
+        None => quote!(#default.max_attempts),
+    };
+
+    let backoff = match args.backoff.as_deref().map(parse_secs).transpose() {
+        Ok(Some(secs)) => quote!(::std::time::Duration::from_secs(#secs)),
+        Ok(None) => quote!(#default.backoff),
+        Err(e) => return syn::Error::new_spanned(&ident, e).to_compile_error().into(),
+    };
+
+    function.sig.ident = fn_ident.clone();
+
+    let output = quote! {
+        #[allow(non_upper_case_globals)]
+        #vis const #ident: crate::core::job::Job = crate::core::job::Job {
+            name: stringify!(#ident),
+            retry: crate::core::job::RetryPolicy {
+                max_attempts: #max_attempts,
+                backoff: #backoff,
+            },
+            feature: None,
+            __exec: #exec_ident,
+        };
+
+        fn #exec_ident<'a>(
+            args: &'a crate::core::job::JobArgs<'a>,
+        ) -> crate::core::job::JobFuture<'a> {
+            Box::pin(#fn_ident(args))
+        }
+
+        #function
+    };
+
+    output.into()
+}
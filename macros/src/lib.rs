@@ -1,6 +1,12 @@
+use darling::FromMeta;
 use proc_macro::TokenStream;
 
+mod command;
+mod duration;
 mod init;
+mod job;
+mod modal;
+mod task;
 
 // TODO: at least validate that args is empty? Or use it later.
 #[proc_macro_attribute]
@@ -8,3 +14,37 @@ pub fn init(_args: TokenStream, function: TokenStream) -> TokenStream {
     let function = syn::parse_macro_input!(function as syn::ItemFn);
     init::init(function)
 }
+
+#[proc_macro_attribute]
+pub fn task(args: TokenStream, function: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(args as syn::AttributeArgs);
+    let function = syn::parse_macro_input!(function as syn::ItemFn);
+
+    match task::TaskArgs::from_list(&args) {
+        Ok(args) => task::task(args, function),
+        Err(e) => e.write_errors().into(),
+    }
+}
+
+#[proc_macro_attribute]
+pub fn job(args: TokenStream, function: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(args as syn::AttributeArgs);
+    let function = syn::parse_macro_input!(function as syn::ItemFn);
+
+    match job::JobArgs::from_list(&args) {
+        Ok(args) => job::job(args, function),
+        Err(e) => e.write_errors().into(),
+    }
+}
+
+#[proc_macro_derive(Command, attributes(command, option))]
+pub fn command(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    command::derive(input).into()
+}
+
+#[proc_macro_derive(Modal, attributes(modal, input))]
+pub fn modal(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    modal::derive(input).into()
+}
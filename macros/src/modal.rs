@@ -0,0 +1,229 @@
+use crate::command::{doc_comment, kebab_case};
+use darling::{ast, FromDeriveInput, FromField};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Error, Result};
+
+/// The most text inputs a modal can have.
+const MAX_INPUTS: usize = 5;
+
+/// The longest a text input's value can be.
+const MAX_LENGTH: u16 = 4000;
+
+/// The `modal` attributes on the type deriving `Modal`.
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(modal), forward_attrs(doc), supports(struct_named))]
+pub struct ModalInput {
+    ident: syn::Ident,
+    generics: syn::Generics,
+    data: ast::Data<(), InputField>,
+    attrs: Vec<Attribute>,
+
+    /// Overrides the custom ID submissions are routed by, which is otherwise the
+    /// kebab-cased name of the type.
+    #[darling(default)]
+    id: Option<String>,
+
+    /// Overrides the title, which is otherwise taken from the doc comment.
+    #[darling(default)]
+    title: Option<String>,
+}
+
+/// The `input` attributes on a field of the type deriving `Modal`.
+#[derive(Debug, FromField)]
+#[darling(attributes(input), forward_attrs(doc))]
+pub struct InputField {
+    ident: Option<syn::Ident>,
+    ty: syn::Type,
+    attrs: Vec<Attribute>,
+
+    /// Overrides the label, which is otherwise taken from the doc comment.
+    #[darling(default)]
+    label: Option<String>,
+
+    /// Text shown in the input while it's empty.
+    #[darling(default)]
+    placeholder: Option<String>,
+
+    /// Minimum length of the value.
+    #[darling(default)]
+    min_length: Option<u16>,
+
+    /// Maximum length of the value.
+    #[darling(default)]
+    max_length: Option<u16>,
+
+    /// Makes the input a multi-line text box.
+    #[darling(default)]
+    paragraph: bool,
+}
+
+pub fn derive(input: syn::DeriveInput) -> TokenStream {
+    let input = match ModalInput::from_derive_input(&input) {
+        Ok(input) => input,
+        Err(e) => return e.write_errors(),
+    };
+
+    match modal(input) {
+        Ok(output) => output,
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+/// Derives `Modal` for a struct, where each field is a text input.
+fn modal(input: ModalInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "modals can't be generic",
+        ));
+    }
+
+    let id = input.id.clone().unwrap_or_else(|| kebab_case(ident));
+    let title = input
+        .title
+        .clone()
+        .unwrap_or_else(|| doc_comment(&input.attrs));
+
+    if !(1..=100).contains(&id.chars().count()) {
+        return Err(Error::new_spanned(
+            ident,
+            "`id` must be 1-100 characters long",
+        ));
+    }
+
+    text(ident, "title", &title, 45)?;
+
+    let fields = input
+        .data
+        .take_struct()
+        .expect("darling only allows structs")
+        .fields;
+
+    if fields.is_empty() || fields.len() > MAX_INPUTS {
+        return Err(Error::new_spanned(
+            ident,
+            format!("modals must have 1-{MAX_INPUTS} inputs"),
+        ));
+    }
+
+    let mut inputs = Vec::new();
+    let mut parsers = Vec::new();
+
+    for field in &fields {
+        let field_ident = field
+            .ident
+            .as_ref()
+            .expect("darling only allows named fields");
+        let name = field_ident.to_string().trim_start_matches("r#").to_string();
+        let label = field
+            .label
+            .clone()
+            .unwrap_or_else(|| doc_comment(&field.attrs));
+
+        text(field_ident, "label", &label, 45)?;
+
+        let mut modifiers = Vec::new();
+
+        if let Some(placeholder) = &field.placeholder {
+            if placeholder.chars().count() > 100 {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "placeholder is longer than the limit of 100 characters",
+                ));
+            }
+
+            modifiers.push(quote!(.placeholder(#placeholder)));
+        }
+
+        if let (Some(min), Some(max)) = (field.min_length, field.max_length) {
+            if min > max {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "`min_length` is larger than `max_length`",
+                ));
+            }
+        }
+
+        if let Some(min) = field.min_length {
+            if min > MAX_LENGTH {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    format!("`min_length` must be 0-{MAX_LENGTH}"),
+                ));
+            }
+
+            let min = min as u64;
+            modifiers.push(quote!(.min_length(#min)));
+        }
+
+        if let Some(max) = field.max_length {
+            if !(1..=MAX_LENGTH).contains(&max) {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    format!("`max_length` must be 1-{MAX_LENGTH}"),
+                ));
+            }
+
+            let max = max as u64;
+            modifiers.push(quote!(.max_length(#max)));
+        }
+
+        let style = match field.paragraph {
+            true => quote!(Paragraph),
+            false => quote!(Short),
+        };
+        let ty = &field.ty;
+
+        inputs.push(quote! {
+            .create_action_row(|row| {
+                row.create_input_text(|input| {
+                    input
+                        .custom_id(#name)
+                        .label(#label)
+                        .style(::serenity::model::application::component::InputTextStyle::#style)
+                        .required(<#ty as crate::core::modal::FromInput>::REQUIRED)
+                        #(#modifiers)*
+                })
+            })
+        });
+
+        parsers.push(quote!(#field_ident: crate::core::modal::parse_input(inputs, #name)?));
+    }
+
+    Ok(quote! {
+        impl crate::core::modal::Modal for #ident {
+            const ID: &'static str = #id;
+            const TITLE: &'static str = #title;
+
+            fn inputs(
+                components: &mut ::serenity::builder::CreateComponents,
+            ) -> &mut ::serenity::builder::CreateComponents {
+                components #(#inputs)*
+            }
+
+            fn from_inputs(
+                inputs: &[::serenity::model::application::component::ActionRow],
+            ) -> ::std::result::Result<Self, crate::core::modal::ModalError> {
+                Ok(Self { #(#parsers,)* })
+            }
+        }
+    })
+}
+
+/// Checks a title or label, which must be 1 to `max` characters.
+fn text(spanned: &syn::Ident, what: &str, text: &str, max: usize) -> Result<()> {
+    match text.chars().count() {
+        0 => Err(Error::new_spanned(
+            spanned,
+            format!("{what} is missing, add a doc comment or a `{what}`"),
+        )),
+        n if n > max => Err(Error::new_spanned(
+            spanned,
+            format!("{what} is {n} characters long, the limit is {max}"),
+        )),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,92 @@
+use crate::duration::parse_secs;
+use darling::FromMeta;
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span};
+use quote::quote;
+use std::str::FromStr;
+
+/// Arguments to the `task` attribute. Exactly one of `every`, `cron`
+/// and `after` must be given.
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+pub struct TaskArgs {
+    /// Runs the task repeatedly, with this duration between runs.
+    every: Option<String>,
+
+    /// Runs the task on a cron schedule, e.g. `0 0 * * * *` for hourly.
+    cron: Option<String>,
+
+    /// Runs the task once, this long after setup finishes.
+    after: Option<String>,
+
+    /// Adds a random delay of up to this duration before each run.
+    jitter: Option<String>,
+}
+
+pub fn task(args: TaskArgs, mut function: syn::ItemFn) -> TokenStream {
+    let ident = function.sig.ident;
+    let vis = &function.vis;
+    let fn_ident = Ident::new(&format!("{}_body", &ident), Span::call_site());
+    let exec_ident = Ident::new(&format!("{}_exec", &ident), Span::call_site());
+
+    if function.sig.asyncness.is_none() {
+        return error(&function.sig.fn_token, "tasks must be `async fn`s");
+    }
+
+    let schedule = match (&args.every, &args.cron, &args.after) {
+        (Some(every), None, None) => match parse_secs(every) {
+            Ok(secs) => quote!(crate::core::task::Schedule::Interval(
+                ::std::time::Duration::from_secs(#secs)
+            )),
+            Err(e) => return error(&ident, &e),
+        },
+        (None, Some(cron), None) => match cron::Schedule::from_str(cron) {
+            Ok(_) => quote!(crate::core::task::Schedule::Cron(#cron)),
+            Err(e) => return error(&ident, &format!("invalid cron expression: {e}")),
+        },
+        (None, None, Some(after)) => match parse_secs(after) {
+            Ok(secs) => quote!(crate::core::task::Schedule::Delay(
+                ::std::time::Duration::from_secs(#secs)
+            )),
+            Err(e) => return error(&ident, &e),
+        },
+        _ => {
+            return error(
+                &ident,
+                "tasks need exactly one of `every`, `cron` or `after`",
+            )
+        }
+    };
+
+    let jitter = match args.jitter.as_deref().map(parse_secs).transpose() {
+        Ok(secs) => secs.unwrap_or(0),
+        Err(e) => return error(&ident, &e),
+    };
+
+    function.sig.ident = fn_ident.clone();
+
+    let output = quote! {
+        #[allow(non_upper_case_globals)]
+        #vis const #ident: crate::core::task::Task = crate::core::task::Task {
+            name: stringify!(#ident),
+            schedule: #schedule,
+            jitter: ::std::time::Duration::from_secs(#jitter),
+            feature: None,
+            __exec: #exec_ident,
+        };
+
+        fn #exec_ident(args: &crate::core::task::TaskArgs) -> crate::core::task::TaskFuture<'_> {
+            Box::pin(#fn_ident(args))
+        }
+
+        #function
+    };
+
+    output.into()
+}
+
+fn error(spanned: &impl quote::ToTokens, message: &str) -> TokenStream {
+    syn::Error::new_spanned(spanned, message)
+        .to_compile_error()
+        .into()
+}
@@ -0,0 +1,141 @@
+use super::pool::{ActivityPool, PoolError};
+use crate::core::command::MAX_SUGGESTIONS;
+use crate::core::prelude::*;
+
+/// Discord refuses messages longer than this.
+const MAX_MESSAGE_LEN: usize = 2000;
+
+/// Manage the messages the bot's activity rotates through.
+#[derive(Command)]
+#[command(example = "/activity add message: just vibing")]
+pub enum Activity {
+    List(List),
+    Add(Add),
+    Remove(Remove),
+    Preview(Preview),
+}
+
+/// List every message in the activity pool.
+#[derive(Command)]
+pub struct List;
+
+/// Add a message to the activity pool.
+#[derive(Command)]
+pub struct Add {
+    /// The message to add.
+    #[option(max_length = 100)]
+    message: String,
+}
+
+/// Remove a message from the activity pool.
+#[derive(Command)]
+pub struct Remove {
+    /// The message to remove, exactly as listed.
+    #[option(autocomplete = "pool_messages")]
+    message: String,
+}
+
+/// Preview how a message would look as the bot's activity.
+#[derive(Command)]
+pub struct Preview {
+    /// The message to preview.
+    message: String,
+}
+
+#[async_trait]
+impl Handler for List {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let messages = pool(ctx).await?.list().await;
+        let mut content = format!("**{} activity messages:**", messages.len());
+
+        let more =
+            |count: usize| format!("\n{}", ctx.tr("activity-more", &[("count", count.into())]));
+        let mut shown = 0;
+
+        for message in &messages {
+            let line = format!("\n- {message}");
+
+            // Unless this is the last message, leave room to say how many more
+            // there are, counting them all to be safe.
+            let reserved = match shown + 1 < messages.len() {
+                true => more(messages.len()).len(),
+                false => 0,
+            };
+
+            if content.len() + line.len() + reserved > MAX_MESSAGE_LEN {
+                break;
+            }
+
+            content.push_str(&line);
+            shown += 1;
+        }
+
+        if shown < messages.len() {
+            content.push_str(&more(messages.len() - shown));
+        }
+
+        ctx.reply(Reply::text(content).ephemeral()).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Add {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        pool(ctx)
+            .await?
+            .add(&self.message)
+            .await
+            .map_err(|e| CommandError::refuse(e, PoolError::is_io))?;
+        ctx.reply(format!("Added `{}`.", self.message.trim()))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Remove {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        pool(ctx)
+            .await?
+            .remove(&self.message)
+            .await
+            .map_err(|e| CommandError::refuse(e, PoolError::is_io))?;
+        ctx.reply(format!("Removed `{}`.", self.message.trim()))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Preview {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let preview = ActivityPool::preview(&self.message)
+            .map_err(|e| CommandError::refuse(e, PoolError::is_io))?;
+        ctx.reply(Reply::text(preview).ephemeral()).await?;
+        Ok(())
+    }
+}
+
+/// Suggests the messages in the pool that contain what's been typed so far.
+async fn pool_messages(ctx: &AutocompleteContext) -> Vec<Suggestion> {
+    let typed = ctx.value().to_lowercase();
+    let pool = match ActivityPool::get(ctx.data()).await {
+        Some(pool) => pool,
+        None => return Vec::new(),
+    };
+
+    pool.list()
+        .await
+        .into_iter()
+        .filter(|message| message.to_lowercase().contains(&typed))
+        .take(MAX_SUGGESTIONS)
+        .map(Suggestion::from)
+        .collect()
+}
+
+async fn pool(ctx: &CommandContext) -> Result<ActivityPool, CommandError> {
+    ActivityPool::get(ctx.data())
+        .await
+        .ok_or_else(|| CommandError::internal("activity pool is unavailable"))
+}
@@ -0,0 +1,115 @@
+use crate::core::{config::Config, prelude::*};
+use porygon_macros::{init, task};
+use serenity::model::gateway::Activity;
+
+mod commands;
+mod pool;
+
+#[cfg(test)]
+mod tests;
+
+use pool::ActivityPool;
+
+/// Loads the activity pool and makes it available on the client data.
+#[init]
+async fn activity(args: &InitArgs<'_>) {
+    let config = Config::get(args.data()).await;
+    let dir = &config.storage.data_dir;
+    let pool = ActivityPool::load(dir).unwrap_or_else(|e| {
+        error!(%e, "failed to load activity pool, falling back to seed");
+        ActivityPool::seeded(dir)
+    });
+
+    args.data().write().await.insert::<ActivityPool>(pool);
+}
+
+/// Sets the first activity shortly after startup, once the shards have had
+/// time to connect.
+#[task(after = "10s")]
+async fn initial_activity(args: &TaskArgs) {
+    set_random_activity(args).await;
+}
+
+/// Rotates the activity to a random one from the pool every quarter hour.
+#[task(cron = "0 */15 * * * *", jitter = "1m")]
+async fn rotate_activity(args: &TaskArgs) {
+    set_random_activity(args).await;
+}
+
+/// Installs the `activity` system.
+pub fn installer(setup: Setup) -> Setup {
+    setup
+        .add_command::<commands::Activity>(POKECOM_STAFF)
+        .add_init(GLOBAL, activity)
+        .add_task(GLOBAL, initial_activity)
+        .add_task(GLOBAL, rotate_activity)
+}
+
+/// Picks a random message from the pool and sets it as the activity on every shard.
+async fn set_random_activity(args: &TaskArgs) {
+    let message = match ActivityPool::get(args.data()).await {
+        Some(pool) => pool.random().await,
+        None => None,
+    };
+
+    let message = match message {
+        Some(message) => message,
+        None => return warn!("activity pool is unavailable"),
+    };
+
+    let manager = args.shard_manager().lock().await;
+
+    for runner in manager.runners.lock().await.values() {
+        runner
+            .runner_tx
+            .set_activity(Some(Activity::playing(&message)));
+    }
+
+    debug!(%message, "set activity");
+}
+
+/// The messages the activity pool is seeded with the first time it's loaded.
+/// After that, the persisted pool is the source of truth.
+const MESSAGES: [&str; 41] = [
+    "cyberduck supreme",
+    "just vibing",
+    "drunk internet duck",
+    "Duck Game",
+    "downloading more ram",
+    "plotting against dakota",
+    "planning a coup",
+    "high on potenuse",
+    "hacking the mainframe",
+    "deleting the database",
+    "beep boop. error",
+    "how are you?",
+    "taking a nap",
+    "sleeping in class",
+    "in a duck pond",
+    "ducking around",
+    "calculating...",
+    "using math for evil",
+    "writing more statuses",
+    "press ctrl-c to quit",
+    "dumb",
+    "committing crimes",
+    "being gay, doing crimes",
+    "MCR — Black Parade",
+    "quacking in the matrix",
+    "porygone to the store",
+    "stanning inky",
+    "beating up geese",
+    "eatin quackers",
+    "doing hot bot shit",
+    "playing with firequackers",
+    "hey got any grapes",
+    "remaking the remakes",
+    "duck duck goose",
+    "daffy-duck",
+    "watching an*me",
+    "release the quacken!",
+    "hugging minecraft bee",
+    "doing communism",
+    "when i was a young duck",
+    "no thots head empty",
+];
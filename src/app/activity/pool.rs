@@ -0,0 +1,183 @@
+use super::MESSAGES;
+use crate::core::{command::MAX_SUGGESTION_LEN, data};
+use rand::{seq::SliceRandom, thread_rng};
+use serenity::prelude::{RwLock, TypeMap, TypeMapKey};
+use std::path::{Path, PathBuf};
+use std::{fmt, io, sync::Arc};
+
+/// Name of the file in the data directory the pool is persisted to.
+const FILE: &str = "activity.json";
+
+/// Discord allows activity names up to 128 characters, but `/activity remove`
+/// suggests messages through autocomplete, which can't show anything longer
+/// than this without cutting it short.
+const MAX_LEN: usize = MAX_SUGGESTION_LEN;
+
+/// The live pool of activity messages that the bot picks from. Staff can
+/// edit it at runtime, and every edit is persisted to the data directory.
+///
+/// The first time the pool is loaded it is seeded from `MESSAGES`.
+#[derive(Debug, Clone)]
+pub struct ActivityPool {
+    messages: Arc<RwLock<Vec<String>>>,
+
+    /// The data directory the pool is persisted to.
+    dir: PathBuf,
+}
+
+impl ActivityPool {
+    /// Loads the pool from the data directory, seeding and saving it
+    /// if it doesn't exist yet.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let messages = match data::load(dir, FILE)? {
+            Some(messages) => messages,
+            None => {
+                let seed = Self::seed();
+                data::save(dir, FILE, &seed)?;
+                seed
+            }
+        };
+
+        Ok(Self::new(messages, dir))
+    }
+
+    /// Fetches the pool from the client data. Only `None` before the `activity`
+    /// initializer has run.
+    pub async fn get(data: &RwLock<TypeMap>) -> Option<Self> {
+        data.read().await.get::<Self>().cloned()
+    }
+
+    /// Creates an in-memory pool from the seed messages without touching
+    /// the data directory. Used as a fallback when loading fails.
+    pub fn seeded(dir: &Path) -> Self {
+        Self::new(Self::seed(), dir)
+    }
+
+    fn new(messages: Vec<String>, dir: &Path) -> Self {
+        Self {
+            messages: Arc::new(RwLock::new(messages)),
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn seed() -> Vec<String> {
+        MESSAGES.iter().map(|m| m.to_string()).collect()
+    }
+
+    /// Returns a copy of every message in the pool.
+    pub async fn list(&self) -> Vec<String> {
+        self.messages.read().await.clone()
+    }
+
+    /// Picks a random message from the pool. Only `None` if staff have
+    /// somehow emptied it, which `remove` prevents.
+    pub async fn random(&self) -> Option<String> {
+        self.messages
+            .read()
+            .await
+            .choose(&mut thread_rng())
+            .cloned()
+    }
+
+    /// Adds a message to the pool and persists it.
+    pub async fn add(&self, message: &str) -> Result<(), PoolError> {
+        let message = Self::validate(message)?;
+        let mut messages = self.messages.write().await;
+
+        if messages.iter().any(|m| m == message) {
+            return Err(PoolError::Duplicate);
+        }
+
+        let mut edited = messages.clone();
+        edited.push(message.to_string());
+        self.commit(&mut messages, edited)
+    }
+
+    /// Removes a message from the pool and persists it. The last message
+    /// can't be removed, since the rotation always needs something to pick.
+    pub async fn remove(&self, message: &str) -> Result<(), PoolError> {
+        let mut messages = self.messages.write().await;
+        let index = messages
+            .iter()
+            .position(|m| m == message.trim())
+            .ok_or(PoolError::NotFound)?;
+
+        if messages.len() == 1 {
+            return Err(PoolError::LastMessage);
+        }
+
+        let mut edited = messages.clone();
+        edited.remove(index);
+        self.commit(&mut messages, edited)
+    }
+
+    /// Saves an edited copy of the pool, and only swaps it in once it's on disk,
+    /// so a failed save leaves the pool as it was.
+    fn commit(&self, messages: &mut Vec<String>, edited: Vec<String>) -> Result<(), PoolError> {
+        data::save(&self.dir, FILE, &edited).map_err(PoolError::Io)?;
+        *messages = edited;
+        Ok(())
+    }
+
+    /// Renders a message the way it appears on the bot's profile, so staff
+    /// can see it before adding it. Fails if the message would be rejected
+    /// by `add`.
+    pub fn preview(message: &str) -> Result<String, PoolError> {
+        Self::validate(message).map(|message| format!("Playing **{message}**"))
+    }
+
+    fn validate(message: &str) -> Result<&str, PoolError> {
+        let message = message.trim();
+
+        match message.chars().count() {
+            0 => Err(PoolError::Empty),
+            n if n > MAX_LEN => Err(PoolError::TooLong),
+            _ => Ok(message),
+        }
+    }
+}
+
+impl TypeMapKey for ActivityPool {
+    type Value = Self;
+}
+
+/// Reasons an edit to the `ActivityPool` can be refused.
+#[derive(Debug)]
+pub enum PoolError {
+    Empty,
+    TooLong,
+    Duplicate,
+    NotFound,
+    LastMessage,
+    Io(io::Error),
+}
+
+impl PoolError {
+    /// Whether the edit failed to be saved, which isn't the user's fault, rather
+    /// than being refused.
+    pub fn is_io(&self) -> bool {
+        matches!(self, Self::Io(_))
+    }
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Activity messages can't be empty."),
+            Self::TooLong => write!(f, "Activity messages can be at most {MAX_LEN} characters."),
+            Self::Duplicate => write!(f, "That message is already in the pool."),
+            Self::NotFound => write!(f, "That message isn't in the pool."),
+            Self::LastMessage => write!(f, "The last message in the pool can't be removed."),
+            Self::Io(e) => write!(f, "Failed to save the pool: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PoolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use super::MESSAGES;
+use crate::core::{
+    controller::POKECOM_STAFF,
+    data,
+    testing::{FakeGuild, FakeMember, Harness},
+};
+use serde_json::json;
+
+const STAFF: u64 = 10;
+const MOD: u64 = 100;
+
+async fn harness() -> Harness {
+    let guild = FakeGuild::of(POKECOM_STAFF)
+        .staff_role(STAFF)
+        .member(FakeMember::new(MOD, "mod").role(STAFF));
+
+    Harness::builder().guild(guild).start().await
+}
+
+#[tokio::test]
+async fn lists_the_seeded_messages() {
+    let harness = harness().await;
+    let outcome = harness.user(MOD).command("/activity list", json!({})).await;
+    let reply = outcome.reply();
+
+    assert!(reply.is_ephemeral());
+    assert!(reply
+        .content()
+        .starts_with(&format!("**{} activity messages:**", MESSAGES.len())));
+    assert!(reply.content().contains("\n- just vibing"));
+}
+
+#[tokio::test]
+async fn adds_and_persists_messages() {
+    let harness = harness().await;
+    let moderator = harness.user(MOD);
+    let outcome = moderator
+        .command("/activity add", json!({ "message": "  testing offline  " }))
+        .await;
+
+    assert_eq!(outcome.reply().content(), "Added `testing offline`.");
+
+    let list = moderator.command("/activity list", json!({})).await;
+    assert!(list.reply().content().contains("\n- testing offline"));
+
+    let saved = data::load::<Vec<String>>(harness.data_dir(), "activity.json")
+        .unwrap()
+        .unwrap();
+    assert_eq!(saved.last().map(String::as_str), Some("testing offline"));
+}
+
+#[tokio::test]
+async fn refuses_duplicate_messages() {
+    let harness = harness().await;
+    let outcome = harness
+        .user(MOD)
+        .command("/activity add", json!({ "message": "just vibing" }))
+        .await;
+
+    assert!(outcome.reply().is_ephemeral());
+    assert_eq!(
+        outcome.reply().description(),
+        "That message is already in the pool."
+    );
+}
+
+#[tokio::test]
+async fn removes_messages() {
+    let harness = harness().await;
+    let moderator = harness.user(MOD);
+    let outcome = moderator
+        .command("/activity remove", json!({ "message": "just vibing" }))
+        .await;
+
+    assert_eq!(outcome.reply().content(), "Removed `just vibing`.");
+
+    let list = moderator.command("/activity list", json!({})).await;
+    assert!(!list.reply().content().contains("\n- just vibing"));
+}
+
+#[tokio::test]
+async fn suggests_messages_to_remove() {
+    let harness = harness().await;
+    let outcome = harness
+        .user(MOD)
+        .autocomplete(
+            "/activity remove",
+            json!({ "message": "DUCK G" }),
+            "message",
+        )
+        .await;
+
+    assert_eq!(outcome.suggestions(), ["Duck Game", "duck duck goose"]);
+}
+
+#[tokio::test]
+async fn previews_messages() {
+    let harness = harness().await;
+    let outcome = harness
+        .user(MOD)
+        .command("/activity preview", json!({ "message": "hello" }))
+        .await;
+
+    assert_eq!(outcome.reply().content(), "Playing **hello**");
+    assert!(outcome.reply().is_ephemeral());
+}
+
+#[tokio::test]
+async fn refuses_messages_too_long_to_suggest() {
+    let harness = harness().await;
+    let outcome = harness
+        .user(MOD)
+        .command("/activity add", json!({ "message": "quack".repeat(21) }))
+        .await;
+
+    assert_eq!(
+        outcome.reply().description(),
+        "Activity messages can be at most 100 characters."
+    );
+}
+
+#[tokio::test]
+async fn counts_the_messages_past_the_length_limit() {
+    let harness = harness().await;
+    let moderator = harness.user(MOD);
+
+    for i in 0..25 {
+        let message = format!("{i:02} {}", "quack".repeat(17));
+        moderator
+            .command("/activity add", json!({ "message": message }))
+            .await;
+    }
+
+    let list = moderator.command("/activity list", json!({})).await;
+    let content = list.reply().content();
+    let (shown, total) = (content.matches("\n- ").count(), MESSAGES.len() + 25);
+
+    assert!(content.len() <= 2000);
+    assert!(shown < total);
+    assert!(content.ends_with(&format!("\n…and {} more.", total - shown)));
+}
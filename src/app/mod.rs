@@ -9,11 +9,17 @@
 //!
 //! Some app modules may access each others exports, but nothing
 //! but `installer` should be accessed by the outside.
+//!
+//! Features are tested end to end, offline, with the harness in
+//! `core::testing`, in a `tests` module next to their installer.
 
 use crate::core::setup::Setup;
 
 mod activity;
 
+#[cfg(test)]
+mod snapshots;
+
 /// Installs all of the myriad setup steps needed for the application.
 pub fn installer(setup: Setup) -> Setup {
     setup.add_from(activity::installer)
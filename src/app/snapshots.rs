@@ -0,0 +1,72 @@
+//! Snapshots of the commands each controller uploads, checked into
+//! `snapshots/commands` so that changes to command schemas show up in review
+//! before they reach live guilds.
+//!
+//! The test fails if a snapshot is out of date. Run it with `UPDATE_SNAPSHOTS=1`
+//! to rewrite them instead, then commit the result:
+//!
+//! ```sh
+//! UPDATE_SNAPSHOTS=1 cargo test snapshots
+//! ```
+
+use crate::core::{controller::Controller, setup::Setup};
+use std::{env, fs, path::PathBuf};
+
+/// Set to rewrite the snapshots rather than check them.
+const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+fn dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots/commands")
+}
+
+#[test]
+fn uploaded_commands_match_snapshots() {
+    let mut data = Setup::new().add_from(super::installer).command_data();
+    let update = env::var_os(UPDATE_VAR).is_some();
+    let mut stale = Vec::new();
+
+    for controller in Controller::ALL {
+        let commands = data.remove(controller.key()).unwrap_or_default();
+        let path = dir().join(format!("{}.json", controller.key()));
+        let rendered = serde_json::to_string_pretty(&commands).unwrap() + "\n";
+
+        if update {
+            fs::create_dir_all(dir()).unwrap();
+            fs::write(&path, rendered).unwrap();
+            continue;
+        }
+
+        let snapshot = fs::read_to_string(&path).unwrap_or_default();
+
+        if let Some(difference) = first_difference(&snapshot, &rendered) {
+            stale.push(format!("{}: {difference}", path.display()));
+        }
+    }
+
+    assert!(
+        stale.is_empty(),
+        "command snapshots are out of date, rerun with {UPDATE_VAR}=1 to accept the changes:\n\n{}",
+        stale.join("\n\n"),
+    );
+}
+
+/// Describes the first line where a snapshot and the rendered JSON differ.
+fn first_difference(snapshot: &str, rendered: &str) -> Option<String> {
+    let (mut old, mut new) = (snapshot.lines(), rendered.lines());
+
+    for line in 1.. {
+        match (old.next(), new.next()) {
+            (None, None) => return None,
+            (old, new) if old == new => continue,
+            (old, new) => {
+                return Some(format!(
+                    "line {line}\n- {}\n+ {}",
+                    old.unwrap_or("<end of snapshot>"),
+                    new.unwrap_or("<end of commands>"),
+                ))
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,148 @@
+//! The command line interface.
+//!
+//! Besides running the bot, the binary has subcommands for the chores of
+//! deploying it, such as uploading or cleaning up commands, so they can be
+//! scripted. Each of them talks to Discord over HTTP only, without connecting
+//! to the gateway.
+
+use crate::core::{api::DiscordApi, config::ConfigArgs, controller::Controller, setup::Setup};
+use clap::{Parser, Subcommand};
+use std::fmt::Write;
+use std::io::{self, BufRead};
+
+/// The Porygon Discord bot.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    /// What to do, which is to run the bot if not given.
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// A subcommand of the binary.
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum CliCommand {
+    /// Connects to Discord and runs the bot.
+    Run,
+
+    /// Lists the setup steps the bot would run, without connecting to Discord.
+    Plan,
+
+    /// Uploads the commands of every connected controller, then exits.
+    SyncCommands,
+
+    /// Lists the commands uploaded under a controller.
+    ListCommands {
+        /// The key of the controller, such as `POKECOM`.
+        #[arg(long, value_parser = controller)]
+        controller: Controller,
+    },
+
+    /// Lists the commands uploaded under a controller, then deletes every one of
+    /// them once confirmed.
+    PurgeCommands {
+        /// The key of the controller, such as `POKECOM`.
+        #[arg(long, value_parser = controller)]
+        controller: Controller,
+
+        /// Deletes the commands without asking first, for scripts.
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Checks the configuration, then prints it with secrets redacted.
+    CheckConfig,
+
+    /// Shows whether the bot can reach the guild of every controller.
+    Guilds,
+}
+
+/// Parses a controller from its key, for `--controller`.
+fn controller(key: &str) -> Result<Controller, String> {
+    Controller::from_key(key).ok_or_else(|| {
+        let keys = Controller::ALL.map(|c| c.key()).join(", ");
+        format!("`{key}` isn't a controller, expected one of: {keys}")
+    })
+}
+
+/// Lists the setup steps of a setup, one per line.
+pub fn plan(setup: &Setup) -> String {
+    setup
+        .plan()
+        .into_iter()
+        .fold(String::new(), |mut out, step| {
+            let _ = writeln!(out, "{step}");
+            out
+        })
+}
+
+/// Lists the commands uploaded under a controller, one per line.
+pub async fn list_commands(
+    api: &dyn DiscordApi,
+    controller: Controller,
+) -> serenity::Result<String> {
+    let mut commands = controller.upload_iface().list(api).await?;
+    commands.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let mut out = String::new();
+
+    for command in commands {
+        let _ = writeln!(out, "{}  {:?}  {}", command.id, command.kind, command.name);
+    }
+
+    Ok(out)
+}
+
+/// Deletes every command uploaded under a controller, listing each one deleted.
+pub async fn purge_commands(
+    api: &dyn DiscordApi,
+    controller: Controller,
+) -> serenity::Result<String> {
+    let iface = controller.upload_iface();
+    let mut out = String::new();
+
+    for command in iface.list(api).await? {
+        iface.delete(api, command.id).await?;
+        let _ = writeln!(out, "Deleted {} ({})", command.name, command.id);
+    }
+
+    Ok(out)
+}
+
+/// Asks a yes or no question, reading the answer from `input`. Anything but `y`
+/// or `yes` is taken as no, including the input ending without an answer.
+pub fn confirm(
+    question: &str,
+    mut input: impl BufRead,
+    mut output: impl io::Write,
+) -> io::Result<bool> {
+    write!(output, "{question} [y/N] ")?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Shows whether each controller is connected, one per line.
+pub async fn guilds(api: &dyn DiscordApi) -> String {
+    let mut out = String::new();
+
+    for controller in Controller::ALL {
+        let status = match controller.is_connected(api).await {
+            true => "connected",
+            false => "disconnected",
+        };
+
+        let _ = writeln!(out, "{:<16}{status}", controller.key());
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests;
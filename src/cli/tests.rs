@@ -0,0 +1,137 @@
+use super::{confirm, guilds, list_commands, plan, purge_commands, Cli, CliCommand};
+use crate::core::{
+    api::{Call, FakeApi},
+    controller::{Controller, DUCK_COMMUNISM, GLOBAL, POKECOM},
+    setup::Setup,
+};
+use clap::Parser;
+use serde_json::json;
+use std::path::Path;
+
+fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+    Cli::try_parse_from(["porygon"].iter().chain(args))
+}
+
+/// Uploads a chat input command under a controller.
+async fn upload(api: &FakeApi, controller: Controller, name: &str) {
+    let data = json!({ "name": name, "description": "A command.", "type": 1 });
+
+    controller
+        .upload_iface()
+        .upload(api, &data, None)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn runs_the_bot_without_a_subcommand() {
+    let cli = parse(&["--data-dir", "elsewhere"]).unwrap();
+
+    assert_eq!(cli.command, None);
+    assert_eq!(cli.config.data_dir.as_deref(), Some(Path::new("elsewhere")));
+}
+
+#[test]
+fn parses_controllers_by_key() {
+    let cli = parse(&["list-commands", "--controller", "POKECOM"]).unwrap();
+    assert_eq!(
+        cli.command,
+        Some(CliCommand::ListCommands {
+            controller: POKECOM
+        }),
+    );
+
+    let error = parse(&["purge-commands", "--controller", "POKEMON"]).unwrap_err();
+    assert!(error.to_string().contains("`POKEMON` isn't a controller"));
+
+    assert!(parse(&["purge-commands"]).is_err());
+}
+
+#[test]
+fn purges_without_asking_only_when_told_to() {
+    let purge =
+        |args: &[&str]| parse(&[&["purge-commands", "--controller", "GLOBAL"], args].concat());
+
+    assert_eq!(
+        purge(&[]).unwrap().command,
+        Some(CliCommand::PurgeCommands {
+            controller: GLOBAL,
+            yes: false
+        }),
+    );
+    assert_eq!(
+        purge(&["--yes"]).unwrap().command,
+        Some(CliCommand::PurgeCommands {
+            controller: GLOBAL,
+            yes: true
+        }),
+    );
+}
+
+#[test]
+fn confirms_only_when_the_answer_is_yes() {
+    let ask = |answer: &str| {
+        let mut output = Vec::new();
+        let confirmed = confirm("Delete?", answer.as_bytes(), &mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "Delete? [y/N] ");
+        confirmed
+    };
+
+    assert!(ask("y\n"));
+    assert!(ask(" Yes\n"));
+    assert!(!ask("n\n"));
+    assert!(!ask("\n"));
+    assert!(!ask(""));
+}
+
+#[test]
+fn plans_steps_in_setup_order() {
+    let setup = Setup::new();
+    let steps = setup.plan();
+
+    let position = |name: &str| steps.iter().position(|s| s.name.starts_with(name));
+    assert!(position("Command").unwrap() < position("Task").unwrap());
+
+    let command = steps
+        .iter()
+        .find(|s| s.name.starts_with("Command"))
+        .unwrap();
+    assert_eq!(command.operands, 2);
+    assert!(plan(&setup).contains(&format!("Command::{GLOBAL} (2)\n")));
+}
+
+#[tokio::test]
+async fn lists_and_purges_commands_under_a_controller() {
+    let api = FakeApi::new();
+    let target = POKECOM.upload_iface().guild_id();
+
+    upload(&api, POKECOM, "trade").await;
+    upload(&api, POKECOM, "battle").await;
+
+    let listed = list_commands(&api, POKECOM).await.unwrap();
+    assert_eq!(listed, "2  ChatInput  battle\n1  ChatInput  trade\n");
+
+    let purged = purge_commands(&api, POKECOM).await.unwrap();
+    assert_eq!(purged.lines().count(), 2);
+    assert!(api.commands(target).is_empty());
+    assert!(api.calls().contains(&Call::DeleteCommand(target, 1.into())));
+
+    assert_eq!(list_commands(&api, POKECOM).await.unwrap(), "");
+}
+
+#[tokio::test]
+async fn shows_which_guilds_are_connected() {
+    let api = FakeApi::new().with_guild(POKECOM.upload_iface().guild_id().unwrap());
+    let out = guilds(&api).await;
+
+    assert!(out.contains("GLOBAL          connected\n"));
+    assert!(out.contains("POKECOM         connected\n"));
+
+    // Staging acts as though every guild is connected.
+    let duck = match cfg!(feature = "staging") {
+        true => "connected",
+        false => "disconnected",
+    };
+    assert!(out.contains(&format!("{:<16}{duck}\n", DUCK_COMMUNISM.key())));
+}
@@ -0,0 +1,260 @@
+use super::DiscordApi;
+use crate::core::testing::json;
+use serde_json::{json, Value};
+use serenity::{
+    http::{
+        error::{Error as HttpError, ErrorResponse},
+        StatusCode,
+    },
+    model::{
+        application::command::Command as ApplicationCommand,
+        channel::Message,
+        guild::PartialGuild,
+        id::{ChannelId, CommandId, GuildId, MessageId},
+    },
+    Result,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+/// A call made to a `FakeApi`, recorded so tests can check what was done.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Call {
+    GetGuild(GuildId),
+    GetCommands(Option<GuildId>),
+    GetCommand(Option<GuildId>, CommandId),
+
+    /// Holds the name of the uploaded command.
+    CreateCommand(Option<GuildId>, String),
+
+    /// Holds the new name of the edited command.
+    EditCommand(Option<GuildId>, CommandId, String),
+
+    DeleteCommand(Option<GuildId>, CommandId),
+    SendMessage(ChannelId),
+}
+
+/// A failure a `FakeApi` can be told to respond with.
+#[derive(Debug, Clone, Copy)]
+pub enum Failure {
+    /// Discord responds with an error status, such as 500.
+    Status(u16),
+
+    /// Discord rate limits the request for a number of seconds.
+    RateLimited { retry_after: f64 },
+}
+
+/// Decides which calls an injected failure applies to.
+type Matcher = Box<dyn Fn(&Call) -> bool + Send>;
+
+/// An in-memory stand-in for Discord. Knows a set of guilds, keeps the commands
+/// uploaded to it, and records every call made to it. Failures can be injected
+/// with `FakeApi::fail`.
+#[derive(Default)]
+pub struct FakeApi {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    guilds: HashSet<GuildId>,
+    commands: BTreeMap<CommandId, (Option<GuildId>, Value)>,
+    next_id: u64,
+    calls: Vec<Call>,
+    failures: Vec<(Matcher, Failure)>,
+}
+
+impl FakeApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a guild the bot is in.
+    pub fn with_guild(self, guild_id: GuildId) -> Self {
+        self.state().guilds.insert(guild_id);
+        self
+    }
+
+    /// Makes the next call matching a predicate fail. Failures are used up in
+    /// the order they're added.
+    pub fn fail(&self, when: impl Fn(&Call) -> bool + Send + 'static, failure: Failure) {
+        self.state().failures.push((Box::new(when), failure));
+    }
+
+    /// Every call made so far, in order.
+    pub fn calls(&self) -> Vec<Call> {
+        self.state().calls.clone()
+    }
+
+    /// The data of the commands uploaded globally or to a guild, by name.
+    pub fn commands(&self, guild_id: Option<GuildId>) -> BTreeMap<String, Value> {
+        self.state()
+            .commands
+            .values()
+            .filter(|(guild, _)| *guild == guild_id)
+            .map(|(_, data)| (name(data), data.clone()))
+            .collect()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records a call, failing it if an injected failure matches.
+    fn record(&self, call: Call) -> std::result::Result<MutexGuard<'_, State>, Failure> {
+        let mut state = self.state();
+        state.calls.push(call.clone());
+
+        match state.failures.iter().position(|(when, _)| when(&call)) {
+            Some(i) => Err(state.failures.remove(i).1),
+            None => Ok(state),
+        }
+    }
+}
+
+impl State {
+    /// Stores a command under a new ID, replacing any with the same name in the
+    /// same place, as Discord does.
+    fn create(&mut self, guild_id: Option<GuildId>, data: &Value) -> ApplicationCommand {
+        let existing = self
+            .commands
+            .iter()
+            .find(|(_, (guild, stored))| *guild == guild_id && name(stored) == name(data))
+            .map(|(id, _)| *id);
+
+        let id = existing.unwrap_or_else(|| {
+            self.next_id += 1;
+            CommandId(self.next_id)
+        });
+
+        self.commands.insert(id, (guild_id, data.clone()));
+        command(id, guild_id, data)
+    }
+
+    fn get(
+        &self,
+        guild_id: Option<GuildId>,
+        command_id: CommandId,
+    ) -> std::result::Result<&Value, Failure> {
+        match self.commands.get(&command_id) {
+            Some((guild, data)) if *guild == guild_id => Ok(data),
+            _ => Err(Failure::Status(404)),
+        }
+    }
+}
+
+/// The error serenity returns for the failure.
+impl From<Failure> for serenity::Error {
+    fn from(failure: Failure) -> Self {
+        let (status, message) = match failure {
+            Failure::Status(status) => (status, "injected failure".to_string()),
+            Failure::RateLimited { retry_after } => (
+                429,
+                format!("You are being rate limited, retry after {retry_after}s."),
+            ),
+        };
+
+        let error = serde_json::from_value(json!({ "code": 0, "message": message }))
+            .expect("Discord errors only need a code and message");
+
+        HttpError::UnsuccessfulRequest(ErrorResponse {
+            status_code: StatusCode::from_u16(status).expect("failures have valid statuses"),
+            url: "https://discord.com/api/v10"
+                .parse()
+                .expect("the URL is valid"),
+            error,
+        })
+        .into()
+    }
+}
+
+#[async_trait]
+impl DiscordApi for FakeApi {
+    async fn get_guild(&self, guild_id: GuildId) -> Result<PartialGuild> {
+        let state = self.record(Call::GetGuild(guild_id))?;
+
+        if !state.guilds.contains(&guild_id) {
+            return Err(Failure::Status(404).into());
+        }
+
+        Ok(guild(guild_id))
+    }
+
+    async fn get_commands(&self, guild_id: Option<GuildId>) -> Result<Vec<ApplicationCommand>> {
+        let state = self.record(Call::GetCommands(guild_id))?;
+
+        Ok(state
+            .commands
+            .iter()
+            .filter(|(_, (guild, _))| *guild == guild_id)
+            .map(|(id, (_, data))| command(*id, guild_id, data))
+            .collect())
+    }
+
+    async fn get_command(
+        &self,
+        guild_id: Option<GuildId>,
+        command_id: CommandId,
+    ) -> Result<ApplicationCommand> {
+        let state = self.record(Call::GetCommand(guild_id, command_id))?;
+        let data = state.get(guild_id, command_id)?;
+
+        Ok(command(command_id, guild_id, data))
+    }
+
+    async fn create_command(
+        &self,
+        guild_id: Option<GuildId>,
+        data: &Value,
+    ) -> Result<ApplicationCommand> {
+        let mut state = self.record(Call::CreateCommand(guild_id, name(data)))?;
+        Ok(state.create(guild_id, data))
+    }
+
+    async fn edit_command(
+        &self,
+        guild_id: Option<GuildId>,
+        command_id: CommandId,
+        data: &Value,
+    ) -> Result<ApplicationCommand> {
+        let mut state = self.record(Call::EditCommand(guild_id, command_id, name(data)))?;
+        state.get(guild_id, command_id)?;
+        state.commands.insert(command_id, (guild_id, data.clone()));
+
+        Ok(command(command_id, guild_id, data))
+    }
+
+    async fn delete_command(&self, guild_id: Option<GuildId>, command_id: CommandId) -> Result<()> {
+        let mut state = self.record(Call::DeleteCommand(guild_id, command_id))?;
+        state.get(guild_id, command_id)?;
+        state.commands.remove(&command_id);
+
+        Ok(())
+    }
+
+    async fn send_message(&self, channel_id: ChannelId, data: &Value) -> Result<Message> {
+        let _state = self.record(Call::SendMessage(channel_id))?;
+        Ok(message(channel_id, data))
+    }
+}
+
+fn name(data: &Value) -> String {
+    data["name"].as_str().unwrap_or_default().to_string()
+}
+
+/// The command Discord would return for uploaded data.
+fn command(id: CommandId, guild_id: Option<GuildId>, data: &Value) -> ApplicationCommand {
+    serde_json::from_value(json::command(id, guild_id, data)).expect("uploaded commands are valid")
+}
+
+/// A guild with nothing in it.
+fn guild(id: GuildId) -> PartialGuild {
+    let guild = json::guild(id, &format!("Guild {id}"), Vec::new());
+    serde_json::from_value(guild).expect("empty guilds are valid")
+}
+
+/// The message Discord would return for sent data.
+fn message(channel_id: ChannelId, data: &Value) -> Message {
+    let message = json::message(MessageId(1), channel_id, json::bot(), data);
+    serde_json::from_value(message).expect("sent messages are valid")
+}
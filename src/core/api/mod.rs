@@ -0,0 +1,136 @@
+//! The Discord operations the core performs outside of handling interactions.
+//!
+//! Setup and controllers talk to Discord through the `DiscordApi` trait rather
+//! than serenity's `Http` directly, so that they can be run against `FakeApi` in
+//! tests, without a token. Serenity's `Http` implements it for real use.
+
+use serde_json::Value;
+use serenity::{
+    http::Http,
+    model::{
+        application::command::Command as ApplicationCommand,
+        channel::Message,
+        guild::PartialGuild,
+        id::{ChannelId, CommandId, GuildId},
+    },
+    Result,
+};
+
+#[cfg(test)]
+mod fake;
+
+#[cfg(test)]
+pub use fake::*;
+
+/// The Discord operations used by setup and controllers. Commands are global if
+/// `guild_id` is `None`, and specific to that guild otherwise.
+#[async_trait]
+pub trait DiscordApi: Send + Sync {
+    /// Fetches a guild the bot is in.
+    async fn get_guild(&self, guild_id: GuildId) -> Result<PartialGuild>;
+
+    /// Lists the commands uploaded globally or to a guild.
+    async fn get_commands(&self, guild_id: Option<GuildId>) -> Result<Vec<ApplicationCommand>>;
+
+    /// Fetches an uploaded command.
+    async fn get_command(
+        &self,
+        guild_id: Option<GuildId>,
+        command_id: CommandId,
+    ) -> Result<ApplicationCommand>;
+
+    /// Uploads a command, overwriting any existing command with the same name.
+    async fn create_command(
+        &self,
+        guild_id: Option<GuildId>,
+        data: &Value,
+    ) -> Result<ApplicationCommand>;
+
+    /// Replaces the data of an uploaded command.
+    async fn edit_command(
+        &self,
+        guild_id: Option<GuildId>,
+        command_id: CommandId,
+        data: &Value,
+    ) -> Result<ApplicationCommand>;
+
+    /// Deletes an uploaded command.
+    async fn delete_command(&self, guild_id: Option<GuildId>, command_id: CommandId) -> Result<()>;
+
+    /// Sends a message to a channel, given as the JSON of its content and embeds.
+    async fn send_message(&self, channel_id: ChannelId, data: &Value) -> Result<Message>;
+}
+
+#[async_trait]
+impl DiscordApi for Http {
+    async fn get_guild(&self, guild_id: GuildId) -> Result<PartialGuild> {
+        Http::get_guild(self, guild_id.0).await
+    }
+
+    async fn get_commands(&self, guild_id: Option<GuildId>) -> Result<Vec<ApplicationCommand>> {
+        match guild_id {
+            Some(guild_id) => self.get_guild_application_commands(guild_id.0).await,
+            None => self.get_global_application_commands().await,
+        }
+    }
+
+    async fn get_command(
+        &self,
+        guild_id: Option<GuildId>,
+        command_id: CommandId,
+    ) -> Result<ApplicationCommand> {
+        match guild_id {
+            Some(guild_id) => {
+                self.get_guild_application_command(guild_id.0, command_id.0)
+                    .await
+            }
+            None => self.get_global_application_command(command_id.0).await,
+        }
+    }
+
+    async fn create_command(
+        &self,
+        guild_id: Option<GuildId>,
+        data: &Value,
+    ) -> Result<ApplicationCommand> {
+        match guild_id {
+            Some(guild_id) => {
+                self.create_guild_application_command(guild_id.0, data)
+                    .await
+            }
+            None => self.create_global_application_command(data).await,
+        }
+    }
+
+    async fn edit_command(
+        &self,
+        guild_id: Option<GuildId>,
+        command_id: CommandId,
+        data: &Value,
+    ) -> Result<ApplicationCommand> {
+        match guild_id {
+            Some(guild_id) => {
+                self.edit_guild_application_command(guild_id.0, command_id.0, data)
+                    .await
+            }
+            None => {
+                self.edit_global_application_command(command_id.0, data)
+                    .await
+            }
+        }
+    }
+
+    async fn delete_command(&self, guild_id: Option<GuildId>, command_id: CommandId) -> Result<()> {
+        match guild_id {
+            Some(guild_id) => {
+                self.delete_guild_application_command(guild_id.0, command_id.0)
+                    .await
+            }
+            None => self.delete_global_application_command(command_id.0).await,
+        }
+    }
+
+    async fn send_message(&self, channel_id: ChannelId, data: &Value) -> Result<Message> {
+        Http::send_message(self, channel_id.0, data).await
+    }
+}
@@ -0,0 +1,228 @@
+use super::{parse_option, FromOption};
+use crate::core::controller::Controller;
+use serde_json::{json, Value};
+use serenity::{
+    client::Context,
+    http::Http,
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::{
+                application_command::CommandDataOption, autocomplete::AutocompleteInteraction,
+            },
+        },
+        guild::Member,
+        id::GuildId,
+        user::User,
+    },
+    prelude::{RwLock, TypeMap},
+};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Discord shows at most this many suggestions.
+pub const MAX_SUGGESTIONS: usize = 25;
+
+/// Discord refuses suggestion names and string values longer than this.
+pub const MAX_SUGGESTION_LEN: usize = 100;
+
+/// How long a provider can take before the dispatcher gives up on it. Unlike
+/// commands, autocomplete can't be deferred, so there's no way to take longer
+/// than Discord's three-second window.
+pub(super) const DEADLINE: Duration = Duration::from_millis(2500);
+
+/// The future returned by an autocomplete provider.
+pub type AutocompleteFuture<'a> = Pin<Box<dyn Future<Output = Vec<Suggestion>> + Send + 'a>>;
+
+/// A single autocomplete suggestion. Strings convert into suggestions whose name
+/// and value are the same.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    name: String,
+    value: Value,
+}
+
+impl Suggestion {
+    /// Creates a suggestion that shows `name` to the user, but fills in the option
+    /// with `value` when picked.
+    pub fn new(name: impl ToString, value: impl Into<Value>) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.into(),
+        }
+    }
+
+    /// Returns the suggestion as Discord expects it, shortening anything that's
+    /// too long rather than failing the whole response.
+    fn to_json(&self) -> Value {
+        let value = match &self.value {
+            Value::String(s) => Value::String(truncate(s)),
+            value => value.clone(),
+        };
+
+        json!({ "name": truncate(&self.name), "value": value })
+    }
+}
+
+impl From<&str> for Suggestion {
+    fn from(value: &str) -> Self {
+        Self::new(value, value)
+    }
+}
+
+impl From<String> for Suggestion {
+    fn from(value: String) -> Self {
+        Self::new(value.clone(), value)
+    }
+}
+
+/// Everything an autocomplete provider has access to. Providers are declared on
+/// an option with `#[option(autocomplete = "provider")]`, and are async functions
+/// taking this and returning the suggestions:
+///
+/// ```ignore
+/// async fn role_names(ctx: &AutocompleteContext) -> Vec<Suggestion> { ... }
+/// ```
+pub struct AutocompleteContext {
+    ctx: Context,
+    interaction: AutocompleteInteraction,
+    controller: Controller,
+}
+
+impl fmt::Debug for AutocompleteContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutocompleteContext")
+            .field("interaction", &self.interaction)
+            .field("controller", &self.controller)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AutocompleteContext {
+    pub fn new(ctx: Context, interaction: AutocompleteInteraction, controller: Controller) -> Self {
+        Self {
+            ctx,
+            interaction,
+            controller,
+        }
+    }
+
+    /// The serenity context of the event.
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
+
+    /// The interaction being handled.
+    pub fn interaction(&self) -> &AutocompleteInteraction {
+        &self.interaction
+    }
+
+    /// The controller the command was registered under.
+    pub fn controller(&self) -> Controller {
+        self.controller
+    }
+
+    /// The guild the command is being used in, or `None` in DMs.
+    pub fn guild_id(&self) -> Option<GuildId> {
+        self.interaction.guild_id
+    }
+
+    /// The member using the command, or `None` in DMs.
+    pub fn member(&self) -> Option<&Member> {
+        self.interaction.member.as_ref()
+    }
+
+    /// The user using the command.
+    pub fn user(&self) -> &User {
+        &self.interaction.user
+    }
+
+    pub fn http(&self) -> &Arc<Http> {
+        &self.ctx.http
+    }
+
+    pub fn data(&self) -> &Arc<RwLock<TypeMap>> {
+        &self.ctx.data
+    }
+
+    /// The options of the subcommand being used, including the focused one.
+    pub fn options(&self) -> &[CommandDataOption] {
+        leaf_options(&self.interaction.data.options)
+    }
+
+    /// What the user has typed into the focused option so far. Partially typed
+    /// numbers are given as strings, since they may not be valid yet.
+    pub fn value(&self) -> String {
+        let value = self
+            .options()
+            .iter()
+            .find(|option| option.focused)
+            .and_then(|option| option.value.as_ref());
+
+        match value {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        }
+    }
+
+    /// Parses another option the user has already filled in, or `None` if they
+    /// haven't or it isn't valid yet.
+    pub fn option<T: FromOption>(&self, name: &'static str) -> Option<T> {
+        parse_option::<Option<T>>(self.options(), name)
+            .ok()
+            .flatten()
+    }
+
+    /// Suggests the names that contain what's been typed so far, for options
+    /// that pick from a known set, such as the registered settings.
+    pub fn suggest_names<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Vec<Suggestion> {
+        let typed = self.value().to_lowercase();
+
+        names
+            .into_iter()
+            .filter(|name| name.contains(&typed))
+            .take(MAX_SUGGESTIONS)
+            .map(Suggestion::from)
+            .collect()
+    }
+}
+
+/// Walks down through subcommands and groups to the options of the subcommand
+/// actually being used.
+fn leaf_options(mut options: &[CommandDataOption]) -> &[CommandDataOption] {
+    while let Some(option) = options.first() {
+        match option.kind {
+            CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup => {
+                options = &option.options
+            }
+            _ => break,
+        }
+    }
+
+    options
+}
+
+/// Converts suggestions into the JSON array Discord expects, dropping any past
+/// the limit.
+pub(super) fn suggestions_to_json(suggestions: Vec<Suggestion>) -> Value {
+    if suggestions.len() > MAX_SUGGESTIONS {
+        debug!(
+            count = suggestions.len(),
+            "too many suggestions, dropping some"
+        );
+    }
+
+    suggestions
+        .iter()
+        .take(MAX_SUGGESTIONS)
+        .map(Suggestion::to_json)
+        .collect()
+}
+
+fn truncate(s: &str) -> String {
+    s.chars().take(MAX_SUGGESTION_LEN).collect()
+}
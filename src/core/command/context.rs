@@ -0,0 +1,260 @@
+use super::response::{Reply, ResponseState};
+use crate::core::{
+    component,
+    controller::Controller,
+    i18n::{self, FluentValue},
+    modal::{self, Modal},
+};
+use serenity::{
+    client::Context,
+    http::Http,
+    model::{
+        application::interaction::{
+            application_command::ApplicationCommandInteraction,
+            message_component::MessageComponentInteraction, modal::ModalSubmitInteraction,
+            InteractionResponseType,
+        },
+        channel::Message,
+        guild::Member,
+        id::{ChannelId, GuildId},
+        user::User,
+    },
+    prelude::{Mutex, RwLock, TypeMap},
+    Result,
+};
+use std::fmt;
+use std::sync::Arc;
+
+/// Everything a command handler has access to while handling an interaction.
+///
+/// Responses are sent through the context rather than the raw interaction, so
+/// that it can keep track of whether the interaction has been acknowledged. See
+/// `InteractionContext::reply`.
+pub type CommandContext = InteractionContext<ApplicationCommandInteraction>;
+
+/// Everything a handler has access to while handling an interaction, whether a
+/// command, a component or a modal submission. Usually named through one of its
+/// aliases, `CommandContext`, `ComponentContext` or `ModalContext`.
+pub struct InteractionContext<I> {
+    ctx: Context,
+    interaction: I,
+    controller: Controller,
+    pub(super) response: Mutex<ResponseState>,
+}
+
+impl<I: AnyInteraction> InteractionContext<I> {
+    pub fn new(ctx: Context, interaction: I, controller: Controller) -> Self {
+        Self {
+            ctx,
+            interaction,
+            controller,
+            response: Mutex::new(ResponseState::Pending),
+        }
+    }
+
+    /// The serenity context of the event.
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
+
+    /// The interaction being handled.
+    pub fn interaction(&self) -> &I {
+        &self.interaction
+    }
+
+    /// The controller the handler was registered under. For components awaited
+    /// by a command, the one the command was registered under.
+    pub fn controller(&self) -> Controller {
+        self.controller
+    }
+
+    /// The guild the interaction happened in, or `None` in DMs.
+    pub fn guild_id(&self) -> Option<GuildId> {
+        self.interaction.guild_id()
+    }
+
+    /// The member who used the interaction, or `None` in DMs.
+    pub fn member(&self) -> Option<&Member> {
+        self.interaction.member()
+    }
+
+    /// The user who used the interaction.
+    pub fn user(&self) -> &User {
+        self.interaction.user()
+    }
+
+    /// The locale of the user's Discord client, such as `en-US`.
+    pub fn locale(&self) -> &str {
+        self.interaction.locale()
+    }
+
+    /// Translates a message into the user's locale. See `i18n::tr`.
+    pub fn tr(&self, key: &str, args: &[(&str, FluentValue)]) -> String {
+        i18n::tr(self.locale(), key, args)
+    }
+
+    pub fn http(&self) -> &Arc<Http> {
+        &self.ctx.http
+    }
+
+    pub fn data(&self) -> &Arc<RwLock<TypeMap>> {
+        &self.ctx.data
+    }
+}
+
+impl<I: fmt::Debug> fmt::Debug for InteractionContext<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InteractionContext")
+            .field("interaction", &self.interaction)
+            .field("controller", &self.controller)
+            .field("response", &self.response)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The kinds of interaction an `InteractionContext` can handle. Serenity's
+/// interaction types share no trait, so this covers what the context needs from
+/// them. Handlers should respond through the context rather than these methods,
+/// so it can keep track of what has been sent.
+#[sealed]
+#[async_trait]
+pub trait AnyInteraction: fmt::Debug + Send + Sync {
+    /// What kind of interaction this is, such as `"Command"`.
+    const KIND: &'static str;
+
+    /// How the interaction is deferred: with a loading message for commands and
+    /// modals, and without changing anything for components.
+    const DEFER: InteractionResponseType;
+
+    /// What was being used, such as the name of the command.
+    fn name(&self) -> &str;
+
+    fn user(&self) -> &User;
+    fn member(&self) -> Option<&Member>;
+    fn guild_id(&self) -> Option<GuildId>;
+    fn channel_id(&self) -> ChannelId;
+    fn locale(&self) -> &str;
+
+    async fn create_response(
+        &self,
+        http: &Http,
+        kind: InteractionResponseType,
+        reply: &Reply,
+    ) -> Result<()>;
+
+    async fn create_defer(&self, http: &Http, ephemeral: bool) -> Result<()>;
+    async fn create_modal<M: Modal>(&self, http: &Http) -> Result<()>;
+    async fn edit_response(&self, http: &Http, reply: &Reply) -> Result<Message>;
+    async fn delete_response(&self, http: &Http) -> Result<()>;
+    async fn create_followup(&self, http: &Http, reply: &Reply) -> Result<Message>;
+}
+
+macro_rules! impl_any_interaction {
+    ($ty:ty, $kind:literal, $defer:ident, |$this:ident| $name:expr) => {
+        #[sealed]
+        #[async_trait]
+        impl AnyInteraction for $ty {
+            const KIND: &'static str = $kind;
+            const DEFER: InteractionResponseType = InteractionResponseType::$defer;
+
+            fn name(&self) -> &str {
+                let $this = self;
+                $name
+            }
+
+            fn user(&self) -> &User {
+                &self.user
+            }
+
+            fn member(&self) -> Option<&Member> {
+                self.member.as_ref()
+            }
+
+            fn guild_id(&self) -> Option<GuildId> {
+                self.guild_id
+            }
+
+            fn channel_id(&self) -> ChannelId {
+                self.channel_id
+            }
+
+            fn locale(&self) -> &str {
+                &self.locale
+            }
+
+            async fn create_response(
+                &self,
+                http: &Http,
+                kind: InteractionResponseType,
+                reply: &Reply,
+            ) -> Result<()> {
+                self.create_interaction_response(http, |response| {
+                    response.kind(kind).interaction_response_data(|data| {
+                        reply.build_data(data);
+                        data
+                    })
+                })
+                .await
+            }
+
+            async fn create_defer(&self, http: &Http, ephemeral: bool) -> Result<()> {
+                self.create_interaction_response(http, |response| {
+                    response.kind(Self::DEFER);
+
+                    if ephemeral {
+                        response.interaction_response_data(|data| data.ephemeral(true));
+                    }
+
+                    response
+                })
+                .await
+            }
+
+            async fn create_modal<M: Modal>(&self, http: &Http) -> Result<()> {
+                self.create_interaction_response(http, modal::build_response::<M>)
+                    .await
+            }
+
+            async fn edit_response(&self, http: &Http, reply: &Reply) -> Result<Message> {
+                self.edit_original_interaction_response(http, |edit| {
+                    reply.build_edit(edit);
+                    edit
+                })
+                .await
+            }
+
+            async fn delete_response(&self, http: &Http) -> Result<()> {
+                self.delete_original_interaction_response(http).await
+            }
+
+            async fn create_followup(&self, http: &Http, reply: &Reply) -> Result<Message> {
+                self.create_followup_message(http, |followup| {
+                    reply.build_followup(followup);
+                    followup
+                })
+                .await
+            }
+        }
+    };
+}
+
+impl_any_interaction!(
+    ApplicationCommandInteraction,
+    "Command",
+    DeferredChannelMessageWithSource,
+    |interaction| &interaction.data.name
+);
+
+impl_any_interaction!(
+    MessageComponentInteraction,
+    "Component",
+    DeferredUpdateMessage,
+    |interaction| component::split(&interaction.data.custom_id).0
+);
+
+impl_any_interaction!(
+    ModalSubmitInteraction,
+    "Modal",
+    DeferredChannelMessageWithSource,
+    |interaction| &interaction.data.custom_id
+);
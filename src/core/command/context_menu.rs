@@ -0,0 +1,148 @@
+use super::{CommandContext, CommandError, CommandResult, Cooldown, Requirement};
+use crate::core::i18n;
+use serde_json::{json, Value};
+use serenity::model::{
+    application::{command::CommandType, interaction::application_command::ResolvedTarget},
+    channel::Message,
+    guild::PartialMember,
+    user::User,
+    Permissions,
+};
+use std::marker::PhantomData;
+
+/// A command in the "Apps" menu shown when right-clicking a user, such as
+/// "Warn user". Registered with `Setup::add_user_command`.
+///
+/// Context menu commands have no options or description, just a name and the
+/// user they were used on.
+#[async_trait]
+pub trait UserCommand: Send + 'static {
+    /// The name of the command as shown in the menu. Unlike the names of slash
+    /// commands, this can contain capitals and spaces.
+    const NAME: &'static str;
+
+    /// The permissions members need to see and use the command. See
+    /// `Command::DEFAULT_PERMISSIONS`.
+    const DEFAULT_PERMISSIONS: Option<Permissions> = None;
+
+    /// The rules users must meet to use the command. See `Command::REQUIRES`.
+    const REQUIRES: &'static [Requirement] = &[];
+
+    /// The ID of the message translating the command's name. See `Command::KEY`.
+    const KEY: Option<&'static str> = None;
+
+    /// The rate limit on using the command. See `Command::COOLDOWN`.
+    const COOLDOWN: Option<Cooldown> = None;
+
+    async fn handle(ctx: &CommandContext, target: TargetUser) -> CommandResult;
+}
+
+/// A command in the "Apps" menu shown when right-clicking a message, such as
+/// "Report message". Registered with `Setup::add_message_command`.
+#[async_trait]
+pub trait MessageCommand: Send + 'static {
+    /// The name of the command as shown in the menu. Unlike the names of slash
+    /// commands, this can contain capitals and spaces.
+    const NAME: &'static str;
+
+    /// The permissions members need to see and use the command. See
+    /// `Command::DEFAULT_PERMISSIONS`.
+    const DEFAULT_PERMISSIONS: Option<Permissions> = None;
+
+    /// The rules users must meet to use the command. See `Command::REQUIRES`.
+    const REQUIRES: &'static [Requirement] = &[];
+
+    /// The ID of the message translating the command's name. See `Command::KEY`.
+    const KEY: Option<&'static str> = None;
+
+    /// The rate limit on using the command. See `Command::COOLDOWN`.
+    const COOLDOWN: Option<Cooldown> = None;
+
+    async fn handle(ctx: &CommandContext, target: Message) -> CommandResult;
+}
+
+/// The user a user command was used on, along with their membership of the guild
+/// if they're in it.
+#[derive(Debug, Clone)]
+pub struct TargetUser {
+    pub user: User,
+    pub member: Option<PartialMember>,
+}
+
+/// Returns a context menu command as the JSON data it's uploaded with.
+pub(super) fn data(
+    name: &str,
+    kind: CommandType,
+    permissions: Option<Permissions>,
+    key: Option<&str>,
+) -> Value {
+    let mut data = json!({ "name": name, "type": kind as u8 });
+
+    if let Some(permissions) = permissions {
+        data["default_member_permissions"] = permissions.bits().to_string().into();
+    }
+
+    if let (Some(key), Value::Object(map)) = (key, &mut data) {
+        i18n::add_localizations(map, key);
+    }
+
+    data
+}
+
+/// Extracts the target of a user command from its interaction.
+pub(super) fn target_user(ctx: &CommandContext) -> Result<TargetUser, CommandError> {
+    match ctx.interaction().data.target() {
+        Some(ResolvedTarget::User(user, member)) => Ok(TargetUser {
+            user,
+            member: member.map(|member| *member),
+        }),
+        _ => Err(CommandError::internal("user command has no target user")),
+    }
+}
+
+/// Extracts the target of a message command from its interaction.
+pub(super) fn target_message(ctx: &CommandContext) -> Result<Message, CommandError> {
+    match ctx.interaction().data.target() {
+        Some(ResolvedTarget::Message(message)) => Ok(*message),
+        _ => Err(CommandError::internal(
+            "message command has no target message",
+        )),
+    }
+}
+
+/// Checks the name of a context menu command at compile time. Referencing
+/// `ValidName::<C>::USER` or `ValidName::<C>::MESSAGE` fails the build if `C`
+/// has an invalid name.
+pub(super) struct ValidName<C>(PhantomData<C>);
+
+impl<C: UserCommand> ValidName<C> {
+    pub(super) const USER: () = assert!(
+        valid_name(C::NAME),
+        "context menu command names must be 1-32 characters"
+    );
+}
+
+impl<C: MessageCommand> ValidName<C> {
+    pub(super) const MESSAGE: () = assert!(
+        valid_name(C::NAME),
+        "context menu command names must be 1-32 characters"
+    );
+}
+
+/// Discord allows 1-32 characters of anything in context menu command names.
+const fn valid_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    let mut chars = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        // Count every byte that isn't a UTF-8 continuation byte.
+        if bytes[i] & 0xC0 != 0x80 {
+            chars += 1;
+        }
+
+        i += 1;
+    }
+
+    chars >= 1 && chars <= 32
+}
@@ -0,0 +1,154 @@
+use super::{permission, CommandContext, CommandError, CommandResult};
+use serenity::prelude::{Mutex, RwLock, TypeMap, TypeMapKey};
+use std::any::TypeId;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Once this many buckets are being tracked, expired ones are cleared out.
+const PRUNE_AFTER: usize = 256;
+
+/// A rate limit on a command, declared with the `cooldown` attribute of the
+/// `Command` derive, such as `cooldown(per = "channel", uses = 3, every = "30s")`.
+///
+/// A command can be used `uses` times in any `every`-long window, so `uses` is also
+/// how many times it can be used in a burst. Staff aren't limited.
+#[derive(Debug, Clone, Copy)]
+pub struct Cooldown {
+    pub per: Bucket,
+    pub uses: u32,
+    pub every: Duration,
+}
+
+/// What a cooldown is counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bucket {
+    /// Each user has their own limit.
+    User,
+
+    /// Everyone in a channel shares a limit.
+    Channel,
+
+    /// Everyone in a guild shares a limit. In DMs, this is the same as `Channel`.
+    Guild,
+}
+
+impl Bucket {
+    /// The ID of the user, channel or guild the bucket belongs to.
+    fn id(&self, ctx: &CommandContext) -> u64 {
+        match (self, ctx.guild_id()) {
+            (Self::User, _) => ctx.user().id.0,
+            (Self::Guild, Some(guild)) => guild.0,
+            (Self::Channel | Self::Guild, _) => ctx.interaction().channel_id.0,
+        }
+    }
+}
+
+/// Identifies a bucket, by the command it limits and who it belongs to.
+pub(super) type Key = (TypeId, Bucket, u64);
+
+/// The recent uses of a command in one bucket, oldest first.
+#[derive(Debug)]
+struct Uses {
+    every: Duration,
+    times: VecDeque<Instant>,
+}
+
+/// The recent uses of rate-limited commands. Lives on the client data, and is
+/// only kept in memory, so cooldowns reset when the bot restarts.
+#[derive(Debug, Clone, Default)]
+pub struct Cooldowns {
+    uses: Arc<Mutex<HashMap<Key, Uses>>>,
+}
+
+impl Cooldowns {
+    /// Fetches the cooldowns from the client data, creating them if needed.
+    pub async fn get(data: &RwLock<TypeMap>) -> Self {
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(Self::default)
+            .clone()
+    }
+
+    /// Records a use of a command at a given time, or returns how long until it
+    /// can be used again if it's on cooldown.
+    pub(super) async fn try_use(
+        &self,
+        key: Key,
+        cooldown: Cooldown,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let mut uses = self.uses.lock().await;
+
+        if uses.len() > PRUNE_AFTER {
+            uses.retain(|_, Uses { every, times }| {
+                times.back().is_some_and(|last| now - *last < *every)
+            });
+        }
+
+        let Uses { times, .. } = uses.entry(key).or_insert_with(|| Uses {
+            every: cooldown.every,
+            times: VecDeque::new(),
+        });
+
+        while times
+            .front()
+            .is_some_and(|first| now - *first >= cooldown.every)
+        {
+            times.pop_front();
+        }
+
+        if times.len() >= cooldown.uses as usize {
+            let first = times.front().copied().unwrap_or(now);
+            return Err(cooldown.every - (now - first));
+        }
+
+        times.push_back(now);
+        Ok(())
+    }
+}
+
+impl TypeMapKey for Cooldowns {
+    type Value = Self;
+}
+
+/// Counts a use of a command against its cooldown, failing with a user error if
+/// it's been used too much. `command` tells commands apart, and is usually the
+/// `TypeId` of the command's type.
+///
+/// Called by the dispatcher for the command being used, and by the handlers
+/// generated for subcommands for the subcommand being used.
+pub async fn throttle(
+    ctx: &CommandContext,
+    command: TypeId,
+    cooldown: Option<Cooldown>,
+) -> CommandResult {
+    let cooldown = match cooldown {
+        Some(cooldown) => cooldown,
+        None => return Ok(()),
+    };
+
+    if permission::is_staff(ctx, ctx.controller()).await {
+        return Ok(());
+    }
+
+    let key = (command, cooldown.per, cooldown.per.id(ctx));
+    let result = Cooldowns::get(ctx.data())
+        .await
+        .try_use(key, cooldown, Instant::now())
+        .await;
+
+    match result {
+        Ok(()) => Ok(()),
+        Err(remaining) => {
+            let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+
+            debug!(user = %ctx.user().id, secs, "command on cooldown");
+
+            Err(CommandError::user(
+                ctx.tr("cooldown", &[("secs", secs.into())]),
+            ))
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use super::{
+    autocomplete::{self, suggestions_to_json},
+    context_menu::{self, MessageCommand, UserCommand, ValidName},
+    cooldown, permission,
+    response::DEFER_AFTER,
+    AutocompleteContext, AutocompleteFuture, CommandContext, CommandError, CommandResult, Handler,
+    Reply, Requirement, Suggestion,
+};
+use crate::core::{
+    controller::Controller,
+    feature::{self, Feature},
+    setup::{ControllerCollection, Step, StepArgs},
+};
+use serde_json::{json, Value};
+use serenity::{
+    client::Context,
+    model::{
+        application::command::CommandType,
+        application::interaction::{
+            application_command::{ApplicationCommandInteraction, CommandDataOption},
+            autocomplete::AutocompleteInteraction,
+            InteractionResponseType,
+        },
+        id::{CommandId, GuildId},
+    },
+    prelude::{RwLock, TypeMap, TypeMapKey},
+};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// The future returned by a command's handler once it's been type-erased.
+pub type CommandFuture<'a> = Pin<Box<dyn Future<Output = CommandResult> + Send + 'a>>;
+
+/// A command registered with `Setup::add_command` or one of its context menu
+/// variants, with its type erased so that commands of different types can be
+/// stored together.
+#[derive(Clone, Copy)]
+pub struct CommandRoute {
+    /// The name of the command.
+    pub name: &'static str,
+
+    /// Whether this is a slash command or a context menu command.
+    pub kind: CommandType,
+
+    /// The rules users must meet to use the command.
+    pub requires: &'static [Requirement],
+
+    /// The feature the command belongs to, if it can be turned off.
+    pub feature: Option<&'static Feature>,
+
+    data: fn() -> Value,
+    examples: fn() -> Vec<&'static str>,
+    exec: for<'a> fn(&'a CommandContext) -> CommandFuture<'a>,
+    autocomplete: for<'a> fn(
+        &'a AutocompleteContext,
+        &'a [CommandDataOption],
+    ) -> Option<AutocompleteFuture<'a>>,
+}
+
+impl fmt::Debug for CommandRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandRoute")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("requires", &self.requires)
+            .field("feature", &self.feature)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CommandRoute {
+    /// Creates the route for a slash command.
+    pub fn of<C: Handler + 'static>() -> Self {
+        Self {
+            name: C::NAME,
+            kind: CommandType::ChatInput,
+            requires: C::REQUIRES,
+            feature: None,
+            data: C::data,
+            examples: C::examples,
+            exec: exec::<C>,
+            autocomplete: C::autocomplete,
+        }
+    }
+
+    /// Creates the route for a user context menu command.
+    pub fn user<C: UserCommand>() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = ValidName::<C>::USER;
+
+        Self {
+            name: C::NAME,
+            kind: CommandType::User,
+            requires: C::REQUIRES,
+            feature: None,
+            data: || context_menu::data(C::NAME, CommandType::User, C::DEFAULT_PERMISSIONS, C::KEY),
+            examples: Vec::new,
+            exec: exec_user::<C>,
+            autocomplete: |_, _| None,
+        }
+    }
+
+    /// Creates the route for a message context menu command.
+    pub fn message<C: MessageCommand>() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = ValidName::<C>::MESSAGE;
+
+        Self {
+            name: C::NAME,
+            kind: CommandType::Message,
+            requires: C::REQUIRES,
+            feature: None,
+            data: || {
+                context_menu::data(
+                    C::NAME,
+                    CommandType::Message,
+                    C::DEFAULT_PERMISSIONS,
+                    C::KEY,
+                )
+            },
+            examples: Vec::new,
+            exec: exec_message::<C>,
+            autocomplete: |_, _| None,
+        }
+    }
+
+    /// Makes the command belong to a feature, so it's refused while the feature
+    /// is off.
+    pub fn in_feature(self, feature: Option<&'static Feature>) -> Self {
+        Self { feature, ..self }
+    }
+
+    /// The JSON data the command is uploaded with.
+    pub fn data(&self) -> Value {
+        (self.data)()
+    }
+
+    /// Examples of using the command and its subcommands. Always empty for context
+    /// menu commands, which have nothing to type.
+    pub fn examples(&self) -> Vec<&'static str> {
+        (self.examples)()
+    }
+
+    /// Checks the command's requirements and cooldown, then parses the arguments of the
+    /// interaction and runs the handler.
+    pub async fn exec(&self, ctx: &CommandContext) -> CommandResult {
+        (self.exec)(ctx).await
+    }
+
+    /// Runs the autocomplete provider of the focused option. `None` if it has no
+    /// provider, which means the uploaded schema is out of date.
+    pub async fn autocomplete(&self, ctx: &AutocompleteContext) -> Option<Vec<Suggestion>> {
+        let options = &ctx.interaction().data.options;
+        Some((self.autocomplete)(ctx, options)?.await)
+    }
+}
+
+fn exec<C: Handler + 'static>(ctx: &CommandContext) -> CommandFuture<'_> {
+    Box::pin(async move {
+        permission::check(ctx, C::REQUIRES).await?;
+        cooldown::throttle(ctx, TypeId::of::<C>(), C::COOLDOWN).await?;
+
+        let args = C::from_interaction(ctx.interaction()).map_err(CommandError::argument)?;
+        args.handle(ctx).await
+    })
+}
+
+fn exec_user<C: UserCommand>(ctx: &CommandContext) -> CommandFuture<'_> {
+    Box::pin(async move {
+        permission::check(ctx, C::REQUIRES).await?;
+        cooldown::throttle(ctx, TypeId::of::<C>(), C::COOLDOWN).await?;
+        C::handle(ctx, context_menu::target_user(ctx)?).await
+    })
+}
+
+fn exec_message<C: MessageCommand>(ctx: &CommandContext) -> CommandFuture<'_> {
+    Box::pin(async move {
+        permission::check(ctx, C::REQUIRES).await?;
+        cooldown::throttle(ctx, TypeId::of::<C>(), C::COOLDOWN).await?;
+        C::handle(ctx, context_menu::target_message(ctx)?).await
+    })
+}
+
+/// Routes incoming command interactions to their handlers, by the ID Discord
+/// assigned each command when it was uploaded. Lives on the client data, where
+/// `CommandStep` fills it in during setup.
+#[derive(Debug, Clone)]
+pub struct Dispatcher {
+    routes: Arc<RwLock<HashMap<CommandId, (Controller, CommandRoute)>>>,
+
+    /// Whether a command's controller accepts the guild it was used in. Always
+    /// `Controller::matches_guild` outside of tests, which can't otherwise reach
+    /// the rejection in staging, where every controller matches every guild.
+    matches_guild: fn(&Controller, Option<GuildId>) -> bool,
+}
+
+impl Default for Dispatcher {
+    fn default() -> Self {
+        Self {
+            routes: Arc::default(),
+            matches_guild: Controller::matches_guild,
+        }
+    }
+}
+
+impl Dispatcher {
+    /// Fetches the dispatcher from the client data, creating it if needed.
+    pub async fn get(data: &RwLock<TypeMap>) -> Self {
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(Self::default)
+            .clone()
+    }
+
+    /// Replaces how commands are matched to the guild they're used in.
+    #[cfg(test)]
+    pub async fn match_guilds_with(
+        data: &RwLock<TypeMap>,
+        matches_guild: fn(&Controller, Option<GuildId>) -> bool,
+    ) {
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(Self::default)
+            .matches_guild = matches_guild;
+    }
+
+    /// Records which command and controller an uploaded command ID belongs to.
+    async fn insert(&self, id: CommandId, controller: Controller, route: CommandRoute) {
+        let previous = self.routes.write().await.insert(id, (controller, route));
+
+        // Only possible in staging, where every controller uploads to the same
+        // guild and a command with the same name overwrites the previous one.
+        if let Some((previous, _)) = previous {
+            warn!(name = route.name, %previous, %controller, "command was uploaded twice");
+        }
+    }
+
+    /// Every command that's been uploaded, with the controller it belongs to.
+    pub async fn routes(&self) -> Vec<(Controller, CommandRoute)> {
+        self.routes.read().await.values().copied().collect()
+    }
+
+    /// Handles a command interaction, logging rather than returning any failure,
+    /// since there's nobody to return it to.
+    #[instrument(skip_all, fields(command = %interaction.data.name))]
+    pub async fn dispatch(&self, ctx: Context, interaction: ApplicationCommandInteraction) {
+        let route = self.routes.read().await.get(&interaction.data.id).copied();
+
+        let (controller, route) = match route {
+            Some(route) => route,
+            None => return warn!(id = %interaction.data.id, "no handler for command"),
+        };
+
+        let ctx = CommandContext::new(ctx, interaction, controller);
+
+        // Guild commands are only uploaded to their own guild, so this should only
+        // happen if a command was left over from an earlier upload.
+        if !(self.matches_guild)(&controller, ctx.guild_id()) {
+            warn!(%controller, guild = ?ctx.guild_id(), "command used outside its guild");
+
+            let reply = Reply::text(ctx.tr("command-unavailable", &[])).ephemeral();
+
+            if let Err(e) = ctx.reply(reply).await {
+                error!(%e, "failed to reject command");
+            }
+
+            return;
+        }
+
+        let result = match feature::check(&ctx, route.feature).await {
+            Ok(()) => run(&ctx, route).await,
+            Err(e) => Err(e),
+        };
+
+        // Whatever happened, the interaction must be acknowledged, or Discord
+        // shows the user that it failed.
+        let reply = match result {
+            Ok(()) if ctx.has_replied().await => return,
+            Ok(()) => {
+                warn!("command finished without replying");
+                Reply::text(ctx.tr("done", &[])).ephemeral()
+            }
+            Err(e) => e.report(&ctx).await,
+        };
+
+        if let Err(e) = ctx.reply(reply).await {
+            error!(%e, "failed to acknowledge command");
+        }
+    }
+
+    /// Handles an autocomplete interaction. An empty list of suggestions is sent
+    /// if anything goes wrong, which Discord shows as no results.
+    #[instrument(skip_all, fields(command = %interaction.data.name))]
+    pub async fn autocomplete(&self, ctx: Context, interaction: AutocompleteInteraction) {
+        let route = self.routes.read().await.get(&interaction.data.id).copied();
+
+        let (controller, route) = match route {
+            Some(route) => route,
+            None => return warn!(id = %interaction.data.id, "no handler for command"),
+        };
+
+        let ctx = AutocompleteContext::new(ctx, interaction, controller);
+        let allowed = (self.matches_guild)(&controller, ctx.guild_id())
+            && feature::allows(ctx.data(), controller, route.feature).await;
+
+        let suggestions = match allowed {
+            true => suggest(&ctx, route).await,
+            false => Vec::new(),
+        };
+
+        let response = json!({
+            "type": InteractionResponseType::Autocomplete as u8,
+            "data": { "choices": suggestions_to_json(suggestions) },
+        });
+
+        let interaction = ctx.interaction();
+        let result = ctx
+            .http()
+            .create_interaction_response(interaction.id.0, &interaction.token, &response)
+            .await;
+
+        if let Err(e) = result {
+            error!(%e, "failed to send suggestions");
+        }
+    }
+}
+
+/// Runs an autocomplete provider, giving up if it would miss Discord's deadline.
+async fn suggest(ctx: &AutocompleteContext, route: CommandRoute) -> Vec<Suggestion> {
+    match tokio::time::timeout(autocomplete::DEADLINE, route.autocomplete(ctx)).await {
+        Ok(Some(suggestions)) => suggestions,
+        Ok(None) => {
+            warn!("no autocomplete provider for focused option");
+            Vec::new()
+        }
+        Err(_) => {
+            warn!("autocomplete provider missed the deadline");
+            Vec::new()
+        }
+    }
+}
+
+/// Runs a command's handler, deferring the interaction if it's slow to reply.
+async fn run(ctx: &CommandContext, route: CommandRoute) -> CommandResult {
+    let handler = route.exec(ctx);
+    tokio::pin!(handler);
+
+    tokio::select! {
+        result = &mut handler => result,
+        _ = tokio::time::sleep(DEFER_AFTER) => {
+            ctx.auto_defer().await;
+            handler.await
+        }
+    }
+}
+
+impl TypeMapKey for Dispatcher {
+    type Value = Self;
+}
+
+/// Setup step that uploads the commands registered under a controller, and
+/// records them with the `Dispatcher` so their interactions can be routed.
+#[derive(Debug)]
+pub struct CommandStep {
+    controller: Controller,
+    commands: HashMap<(CommandType, &'static str), CommandRoute>,
+}
+
+#[async_trait]
+impl Step for CommandStep {
+    type Operand = CommandRoute;
+    type Collection = ControllerCollection<Self>;
+
+    const NAME: &'static str = "Command";
+
+    fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            commands: HashMap::default(),
+        }
+    }
+
+    fn operand_count(&self) -> usize {
+        self.commands.len()
+    }
+
+    fn append(&mut self, command: CommandRoute) {
+        self.commands.insert((command.kind, command.name), command);
+    }
+
+    async fn execute<'a>(self, args: StepArgs<'a, Self>) -> serenity::Result<()> {
+        let iface = self.controller.upload_iface();
+        let dispatcher = Dispatcher::get(&args.client.data).await;
+
+        for route in self.commands.into_values() {
+            // Creating a command with the name of an existing one overwrites it,
+            // so there's no need to know the IDs of previous uploads.
+            let command = iface.upload(args.api, &route.data(), None).await?;
+
+            debug!(name = route.name, id = %command.id, "uploaded command");
+            dispatcher.insert(command.id, self.controller, route).await;
+        }
+
+        Ok(())
+    }
+}
+
+impl CommandStep {
+    /// The JSON of every command that `execute` uploads, sorted by type and name
+    /// so it's the same from one run to the next.
+    pub fn data(&self) -> Vec<Value> {
+        let mut commands = self.commands.iter().collect::<Vec<_>>();
+        commands.sort_unstable_by_key(|((kind, name), _)| (*kind as u8, *name));
+
+        commands
+            .into_iter()
+            .map(|(_, route)| route.data())
+            .collect()
+    }
+}
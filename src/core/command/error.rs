@@ -0,0 +1,169 @@
+use super::{ArgumentError, Invocation, Reply};
+use crate::core::{api::DiscordApi, config::Config, controller::Controller};
+use rand::{thread_rng, Rng};
+use serde_json::json;
+use serenity::{model::id::ChannelId, utils::Colour};
+use std::{error::Error, fmt};
+
+/// Discord refuses embed descriptions longer than this.
+pub(super) const MAX_DESCRIPTION_LEN: usize = 4096;
+
+/// The ways a command can fail. Anything that implements `Error` converts into
+/// an internal error, so `?` works as usual in handlers, and user errors are
+/// made explicitly with `CommandError::user`.
+#[derive(Debug)]
+pub enum CommandError {
+    /// The user did something wrong, such as giving an invalid value. The message
+    /// is shown to them verbatim, and nothing is logged beyond a debug event.
+    User(String),
+
+    /// Something went wrong that's not the user's fault. The user is only told
+    /// that it happened, and the error is logged in full.
+    Internal(Box<dyn Error + Send + Sync>),
+}
+
+impl CommandError {
+    /// Creates an error whose message is shown to the user.
+    pub fn user(message: impl ToString) -> Self {
+        Self::User(message.to_string())
+    }
+
+    /// Creates an internal error from an error or a message.
+    pub fn internal(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self::Internal(error.into())
+    }
+
+    /// Shows the user why something they asked for was refused, unless the caller
+    /// says the error isn't their fault, such as a failure storing it:
+    ///
+    /// ```ignore
+    /// .map_err(|e| CommandError::refuse(e, |e| matches!(e, SettingsError::Storage(_))))?;
+    /// ```
+    pub fn refuse<E: Error + Send + Sync + 'static>(
+        error: E,
+        is_internal: impl FnOnce(&E) -> bool,
+    ) -> Self {
+        match is_internal(&error) {
+            true => Self::internal(error),
+            false => Self::user(error),
+        }
+    }
+
+    /// Classifies an argument parsing failure. Most of these mean the uploaded
+    /// schema has drifted from the code, but some are the user's doing.
+    pub(super) fn argument(error: ArgumentError) -> Self {
+        match error {
+            ArgumentError::NotAMember { .. } => Self::user(error),
+            error => Self::internal(error),
+        }
+    }
+
+    /// Logs the error with the context it happened in, posts internal errors to
+    /// the controller's error channel if it has one, and returns the reply that
+    /// should be shown to the user.
+    pub(crate) async fn report<S: Invocation>(self, source: &S) -> Reply {
+        let error = match self {
+            Self::User(message) => {
+                debug!(%message, kind = S::KIND, "interaction refused");
+
+                return Reply::default()
+                    .embed(|e| e.description(message).colour(Colour::ORANGE))
+                    .ephemeral();
+            }
+            Self::Internal(error) => error,
+        };
+
+        let id = error_id();
+        let controller = source.controller();
+
+        error!(
+            %id,
+            error = %error,
+            details = ?error,
+            kind = S::KIND,
+            name = source.name(),
+            %controller,
+            user = %source.user().id,
+            guild = ?source.guild_id(),
+            "interaction failed",
+        );
+
+        if let Some(channel) = error_channel(source, controller).await {
+            let details = truncate(format!("```\n{error:?}\n```"));
+            let message = json!({
+                "embeds": [{
+                    "title": format!("Error {id}"),
+                    "description": details,
+                    "fields": [
+                        { "name": S::KIND, "value": source.name(), "inline": true },
+                        { "name": "User", "value": format!("<@{}>", source.user().id), "inline": true },
+                        { "name": "Controller", "value": controller.to_string(), "inline": true },
+                    ],
+                    "color": Colour::RED.0,
+                }],
+            });
+
+            let api: &dyn DiscordApi = source.http().as_ref();
+            let result = api.send_message(channel, &message).await;
+
+            if let Err(e) = result {
+                error!(%e, %id, "failed to post error to error channel");
+            }
+        }
+
+        Reply::default()
+            .embed(|e| {
+                e.title(source.tr("error-title", &[]))
+                    .description(source.tr("error-description", &[("id", id.as_str().into())]))
+                    .colour(Colour::RED)
+            })
+            .ephemeral()
+    }
+}
+
+impl<E: Error + Send + Sync + 'static> From<E> for CommandError {
+    fn from(error: E) -> Self {
+        Self::Internal(Box::new(error))
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User(message) => write!(f, "{message}"),
+            Self::Internal(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+/// A short random ID for an internal error, which users can give to staff so the
+/// error can be found in the logs.
+fn error_id() -> String {
+    format!("{:08X}", thread_rng().gen::<u32>())
+}
+
+/// The channel internal errors under a controller are posted to, if any. Set in
+/// the `Config`, such as with `ERROR_CHANNEL_POKECOM_STAFF`.
+async fn error_channel(source: &impl Invocation, controller: Controller) -> Option<ChannelId> {
+    Config::get(source.data())
+        .await
+        .controller(controller)
+        .error_channel
+}
+
+/// Shortens error details to fit in an embed description, keeping the closing
+/// code fence.
+pub(super) fn truncate(mut text: String) -> String {
+    if text.len() > MAX_DESCRIPTION_LEN {
+        let mut end = MAX_DESCRIPTION_LEN - "…\n```".len();
+
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        text.truncate(end);
+        text.push_str("…\n```");
+    }
+
+    text
+}
@@ -0,0 +1,14 @@
+use super::{Command, CommandContext, CommandError};
+
+/// The result of running a command handler.
+pub type CommandResult = Result<(), CommandError>;
+
+/// The code run when a command is invoked. Implemented by hand for each leaf
+/// command, which is given its parsed arguments as `self`.
+///
+/// Commands with subcommands derive this along with `Command`, routing to the
+/// handler of whichever subcommand was invoked.
+#[async_trait]
+pub trait Handler: Command + Send {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult;
+}
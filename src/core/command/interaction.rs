@@ -1 +1,87 @@
-pub trait FromInteraction {}
+use serenity::model::application::{
+    command::CommandOptionType,
+    interaction::application_command::{ApplicationCommandInteraction, CommandDataOption},
+};
+use std::fmt;
+
+/// A type that can be parsed from the option data of a command interaction. Each
+/// command has an argument struct implementing this, so that handlers are given
+/// strongly typed arguments rather than walking raw option vectors.
+///
+/// Implementations are usually just a `parse_option` call per field.
+pub trait FromInteraction: Sized {
+    /// Parses the arguments from a list of options. For a command without
+    /// subcommands, this is the top-level option list of the interaction. For a
+    /// subcommand, it's the option list nested under it.
+    fn from_options(options: &[CommandDataOption]) -> Result<Self, ArgumentError>;
+
+    /// Parses the arguments from an interaction. Need not be overridden.
+    fn from_interaction(
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<Self, ArgumentError> {
+        Self::from_options(&interaction.data.options)
+    }
+}
+
+/// Command without any arguments.
+impl FromInteraction for () {
+    fn from_options(_: &[CommandDataOption]) -> Result<Self, ArgumentError> {
+        Ok(())
+    }
+}
+
+/// Reasons the options of an interaction can fail to parse into arguments.
+///
+/// Discord validates options against the uploaded schema, so these generally only
+/// happen if the schema and the argument struct have drifted apart.
+#[derive(Debug, Clone)]
+pub enum ArgumentError {
+    /// A required option was not given.
+    Missing { name: &'static str },
+
+    /// An option was given with a different type than expected.
+    WrongType {
+        name: &'static str,
+        expected: CommandOptionType,
+        found: CommandOptionType,
+    },
+
+    /// An option was given, but its value couldn't be resolved into the expected
+    /// object, such as a role that Discord didn't include in the payload.
+    Unresolved { name: &'static str },
+
+    /// A user was given for an option that requires a member of the guild.
+    NotAMember { name: &'static str },
+
+    /// A command with subcommands was invoked without one.
+    MissingSubcommand,
+
+    /// A subcommand was invoked that the command doesn't have.
+    UnknownSubcommand { name: String },
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { name } => write!(f, "Missing required option `{name}`."),
+            Self::WrongType {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Option `{name}` should be of type {expected:?}, but was {found:?}."
+            ),
+            Self::Unresolved { name } => {
+                write!(f, "The value of option `{name}` could not be resolved.")
+            }
+            Self::NotAMember { name } => {
+                write!(f, "Option `{name}` must be a member of this server.")
+            }
+            Self::MissingSubcommand => write!(f, "No subcommand was given."),
+            Self::UnknownSubcommand { name } => write!(f, "Unknown subcommand `{name}`."),
+        }
+    }
+}
+
+impl std::error::Error for ArgumentError {}
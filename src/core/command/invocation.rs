@@ -0,0 +1,71 @@
+use super::{AnyInteraction, InteractionContext};
+use crate::core::{controller::Controller, i18n::FluentValue};
+use serenity::{
+    http::Http,
+    model::{
+        guild::Member,
+        id::{ChannelId, GuildId},
+        user::User,
+    },
+    prelude::{RwLock, TypeMap},
+};
+use std::sync::Arc;
+
+/// An interaction being handled, whether a command, a component or a modal. Lets
+/// errors be reported and requirements be checked the same way for all of them.
+pub(crate) trait Invocation: Sync {
+    /// What kind of interaction this is, such as `"Command"`.
+    const KIND: &'static str;
+
+    /// What was being used, such as the name of the command.
+    fn name(&self) -> &str;
+
+    fn http(&self) -> &Arc<Http>;
+    fn data(&self) -> &Arc<RwLock<TypeMap>>;
+    fn controller(&self) -> Controller;
+    fn user(&self) -> &User;
+    fn member(&self) -> Option<&Member>;
+    fn guild_id(&self) -> Option<GuildId>;
+    fn channel_id(&self) -> ChannelId;
+    fn tr(&self, key: &str, args: &[(&str, FluentValue)]) -> String;
+}
+
+impl<I: AnyInteraction> Invocation for InteractionContext<I> {
+    const KIND: &'static str = I::KIND;
+
+    fn name(&self) -> &str {
+        self.interaction().name()
+    }
+
+    fn http(&self) -> &Arc<Http> {
+        InteractionContext::http(self)
+    }
+
+    fn data(&self) -> &Arc<RwLock<TypeMap>> {
+        InteractionContext::data(self)
+    }
+
+    fn controller(&self) -> Controller {
+        InteractionContext::controller(self)
+    }
+
+    fn user(&self) -> &User {
+        InteractionContext::user(self)
+    }
+
+    fn member(&self) -> Option<&Member> {
+        InteractionContext::member(self)
+    }
+
+    fn guild_id(&self) -> Option<GuildId> {
+        InteractionContext::guild_id(self)
+    }
+
+    fn channel_id(&self) -> ChannelId {
+        self.interaction().channel_id()
+    }
+
+    fn tr(&self, key: &str, args: &[(&str, FluentValue)]) -> String {
+        InteractionContext::tr(self, key, args)
+    }
+}
@@ -1,6 +1,180 @@
+use crate::core::i18n;
+use serde_json::{json, Value};
+use serenity::model::{
+    application::{
+        command::CommandOptionType, interaction::application_command::CommandDataOption,
+    },
+    Permissions,
+};
+
+mod autocomplete;
+mod context;
+mod context_menu;
+mod cooldown;
+mod dispatch;
+mod error;
+mod handler;
 mod interaction;
+mod invocation;
+mod option;
+mod permission;
+mod response;
+mod schema;
+
+pub use autocomplete::*;
+pub use context::*;
+pub use context_menu::*;
+pub use cooldown::*;
+pub use dispatch::*;
+pub use error::*;
+pub use handler::*;
+pub use interaction::*;
+pub(crate) use invocation::*;
+pub use option::*;
+pub use permission::*;
+pub use porygon_macros::Command;
+pub use response::*;
+pub use schema::*;
 
-pub trait Command {
+/// Subcommand groups can contain subcommands, but nothing deeper.
+pub const MAX_DEPTH: u8 = 2;
+
+/// A slash command. The implementing type doubles as the command's argument
+/// struct, so it's parsed from the interaction through `FromInteraction`.
+///
+/// Usually implemented with the `Command` derive, which builds the option schema
+/// from the struct's fields and doc comments, and validates it against Discord's
+/// limits at compile time.
+///
+/// Deriving on an enum instead makes a command with subcommands. Each variant
+/// wraps another `Command`, which becomes a subcommand if it's a struct or a
+/// subcommand group if it's itself an enum. Subcommands keep their own names, so
+/// they're usually kept in a module named after the command:
+///
+/// ```ignore
+/// /// Manage self-assignable roles.
+/// #[derive(Command)]
+/// enum Role {
+///     Add(role::Add),
+///     Remove(role::Remove),
+/// }
+/// ```
+pub trait Command: FromInteraction {
+    /// The name of the command, as typed by users.
     const NAME: &'static str;
+
+    /// The description of the command, shown by Discord in the command picker.
     const DESC: &'static str;
+
+    /// How many levels of subcommands sit below this command. Zero for a command
+    /// with plain options, one for a command with subcommands, and two for a
+    /// command with subcommand groups. Never more than `MAX_DEPTH`.
+    const DEPTH: u8 = 0;
+
+    /// The ID of the message translating the command's name and description, and
+    /// the prefix of those of its options. Commands without one aren't translated.
+    const KEY: Option<&'static str> = None;
+
+    /// The permissions members need to see and use the command, unless a guild
+    /// admin overrides them. Only top-level commands can set this, since Discord
+    /// doesn't support it for subcommands, and the derive rejects it on them.
+    const DEFAULT_PERMISSIONS: Option<Permissions> = None;
+
+    /// The rules users must meet to use the command, checked when it's used. For a
+    /// subcommand, these are checked on top of those of its parents.
+    const REQUIRES: &'static [Requirement] = &[];
+
+    /// The rate limit on using the command. For a subcommand, this is counted
+    /// separately from that of its parents.
+    const COOLDOWN: Option<Cooldown> = None;
+
+    /// Examples of using the command, listed by `/help`, such as
+    /// `/role add role: Artist`.
+    const EXAMPLES: &'static [&'static str] = &[];
+
+    /// The schema of the command's options. For a command with subcommands, these
+    /// are the subcommands themselves.
+    fn options() -> Vec<OptionSchema>;
+
+    /// The examples of the command, followed by those of its subcommands, if it
+    /// has any. Generated by the `Command` derive for commands with subcommands.
+    fn examples() -> Vec<&'static str> {
+        Self::EXAMPLES.to_vec()
+    }
+
+    /// Starts the autocomplete provider of the focused option, if it has one. The
+    /// options are those at this command's level, so subcommands are routed to
+    /// the same way as for `FromInteraction::from_options`.
+    ///
+    /// Generated by the `Command` derive for options with an `autocomplete`
+    /// provider. Need not be overridden otherwise.
+    fn autocomplete<'a>(
+        _ctx: &'a AutocompleteContext,
+        _options: &'a [CommandDataOption],
+    ) -> Option<AutocompleteFuture<'a>> {
+        None
+    }
+
+    /// Returns the command as a subcommand or subcommand group of another command,
+    /// depending on its depth. Need not be overridden.
+    fn as_option() -> OptionSchema {
+        let kind = match Self::DEPTH {
+            0 => CommandOptionType::SubCommand,
+            _ => CommandOptionType::SubCommandGroup,
+        };
+
+        let option =
+            OptionSchema::new(kind, Self::NAME, Self::DESC, false).options(Self::options());
+
+        match Self::KEY {
+            Some(key) => option.localize(key),
+            None => option,
+        }
+    }
+
+    /// Returns the command as the JSON data handed to `UploadInterface::upload`.
+    /// Need not be overridden.
+    fn data() -> Value {
+        let mut data = json!({
+            "name": Self::NAME,
+            "description": Self::DESC,
+            "options": options_to_json(&Self::options()),
+        });
+
+        if let Some(permissions) = Self::DEFAULT_PERMISSIONS {
+            data["default_member_permissions"] = permissions.bits().to_string().into();
+        }
+
+        if let (Some(key), Value::Object(map)) = (Self::KEY, &mut data) {
+            i18n::add_localizations(map, key);
+        }
+
+        data
+    }
+}
+
+/// Compares two strings in a const context. Used by the `Command` derive to reject
+/// subcommands with the same name at compile time.
+#[doc(hidden)]
+pub const fn __str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    true
 }
+
+#[cfg(test)]
+mod tests;
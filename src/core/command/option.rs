@@ -0,0 +1,163 @@
+use super::ArgumentError;
+use serenity::model::{
+    application::{
+        command::CommandOptionType,
+        interaction::application_command::{CommandDataOption, CommandDataOptionValue},
+    },
+    channel::{Attachment, PartialChannel},
+    guild::{PartialMember, Role},
+    user::User,
+};
+
+/// A type that can be parsed from the value of a single command option. This is
+/// what the fields of an argument struct implementing `FromInteraction` are made of.
+///
+/// Implemented for primitives, users, members, roles, channels, mentionables,
+/// attachments, and `Option<T>` for optional options.
+pub trait FromOption: Sized {
+    /// The Discord type of the option, used both to validate the incoming value and
+    /// to describe the option when it's uploaded.
+    const KIND: CommandOptionType;
+
+    /// Whether the option must be given. Only `Option<T>` overrides this.
+    const REQUIRED: bool = true;
+
+    /// Converts a resolved option value, failing if it doesn't have the shape this
+    /// type expects. The option's type has already been checked at this point.
+    fn from_value(
+        name: &'static str,
+        value: &CommandDataOptionValue,
+    ) -> Result<Self, ArgumentError>;
+
+    /// Parses the option with the given name, which is `None` if the user didn't
+    /// give it.
+    fn from_option(
+        name: &'static str,
+        option: Option<&CommandDataOption>,
+    ) -> Result<Self, ArgumentError> {
+        let option = option.ok_or(ArgumentError::Missing { name })?;
+
+        if option.kind != Self::KIND {
+            return Err(ArgumentError::WrongType {
+                name,
+                expected: Self::KIND,
+                found: option.kind,
+            });
+        }
+
+        match &option.resolved {
+            Some(value) => Self::from_value(name, value),
+            None => Err(ArgumentError::Unresolved { name }),
+        }
+    }
+}
+
+/// Finds the option with a given name and parses it. This is the building block
+/// for `FromInteraction` implementations.
+pub fn parse_option<T: FromOption>(
+    options: &[CommandDataOption],
+    name: &'static str,
+) -> Result<T, ArgumentError> {
+    T::from_option(name, options.iter().find(|o| o.name == name))
+}
+
+impl<T: FromOption> FromOption for Option<T> {
+    const KIND: CommandOptionType = T::KIND;
+    const REQUIRED: bool = false;
+
+    fn from_value(
+        name: &'static str,
+        value: &CommandDataOptionValue,
+    ) -> Result<Self, ArgumentError> {
+        T::from_value(name, value).map(Some)
+    }
+
+    fn from_option(
+        name: &'static str,
+        option: Option<&CommandDataOption>,
+    ) -> Result<Self, ArgumentError> {
+        match option {
+            Some(option) => T::from_option(name, Some(option)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A member given as an option, along with their user. Unlike a plain `User`
+/// option, this fails if the user isn't a member of the guild.
+#[derive(Debug, Clone)]
+pub struct ResolvedMember {
+    pub user: User,
+    pub member: PartialMember,
+}
+
+/// The value of a mentionable option, which can be either a user or a role.
+#[derive(Debug, Clone)]
+pub enum Mentionable {
+    User(User, Option<PartialMember>),
+    Role(Role),
+}
+
+/// Implements `FromOption` for a type that maps directly from a single variant
+/// of the resolved option value.
+macro_rules! impl_from_option {
+    ($ty:ty, $kind:ident, $pat:pat => $value:expr) => {
+        impl FromOption for $ty {
+            const KIND: CommandOptionType = CommandOptionType::$kind;
+
+            fn from_value(
+                name: &'static str,
+                value: &CommandDataOptionValue,
+            ) -> Result<Self, ArgumentError> {
+                match value {
+                    $pat => Ok($value),
+                    _ => Err(ArgumentError::Unresolved { name }),
+                }
+            }
+        }
+    };
+}
+
+impl_from_option!(String, String, CommandDataOptionValue::String(s) => s.clone());
+impl_from_option!(i64, Integer, CommandDataOptionValue::Integer(i) => *i);
+impl_from_option!(f64, Number, CommandDataOptionValue::Number(n) => *n);
+impl_from_option!(bool, Boolean, CommandDataOptionValue::Boolean(b) => *b);
+impl_from_option!(User, User, CommandDataOptionValue::User(user, _) => user.clone());
+impl_from_option!(Role, Role, CommandDataOptionValue::Role(role) => role.clone());
+impl_from_option!(PartialChannel, Channel, CommandDataOptionValue::Channel(channel) => channel.clone());
+impl_from_option!(Attachment, Attachment, CommandDataOptionValue::Attachment(a) => a.clone());
+
+impl FromOption for ResolvedMember {
+    const KIND: CommandOptionType = CommandOptionType::User;
+
+    fn from_value(
+        name: &'static str,
+        value: &CommandDataOptionValue,
+    ) -> Result<Self, ArgumentError> {
+        match value {
+            CommandDataOptionValue::User(user, Some(member)) => Ok(Self {
+                user: user.clone(),
+                member: member.clone(),
+            }),
+            CommandDataOptionValue::User(_, None) => Err(ArgumentError::NotAMember { name }),
+            _ => Err(ArgumentError::Unresolved { name }),
+        }
+    }
+}
+
+impl FromOption for Mentionable {
+    const KIND: CommandOptionType = CommandOptionType::Mentionable;
+
+    fn from_value(
+        name: &'static str,
+        value: &CommandDataOptionValue,
+    ) -> Result<Self, ArgumentError> {
+        match value {
+            CommandDataOptionValue::User(user, member) => {
+                Ok(Self::User(user.clone(), member.clone()))
+            }
+            CommandDataOptionValue::Role(role) => Ok(Self::Role(role.clone())),
+            _ => Err(ArgumentError::Unresolved { name }),
+        }
+    }
+}
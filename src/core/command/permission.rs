@@ -0,0 +1,150 @@
+use super::{CommandContext, CommandError, CommandResult, Invocation};
+use crate::core::{config::Config, controller::Controller};
+use serenity::{
+    model::id::{ChannelId, RoleId, UserId},
+    prelude::TypeMapKey,
+};
+use std::{collections::HashSet, sync::Arc};
+
+/// A rule about who can use a command, checked when it's used. Declared with the
+/// `staff`, `owner` and `channels` attributes of the `Command` derive.
+///
+/// These are on top of the default member permissions uploaded with the command,
+/// which Discord enforces itself, but which guild admins can override.
+#[derive(Debug, Clone, Copy)]
+pub enum Requirement {
+    /// The user must have the staff role of the controller's guild.
+    Staff,
+
+    /// The user must own the bot, or be on the team that does.
+    Owner,
+
+    /// The command must be used in one of these channels.
+    Channels(&'static [ChannelId]),
+}
+
+impl Requirement {
+    /// Whether the user of an interaction meets the requirement of a command
+    /// registered under a controller.
+    async fn is_met(
+        &self,
+        source: &impl Invocation,
+        controller: Controller,
+    ) -> Result<bool, CommandError> {
+        match self {
+            Self::Staff => Ok(is_staff(source, controller).await),
+            Self::Owner => Ok(owners(source).await?.contains(&source.user().id)),
+            Self::Channels(channels) => Ok(channels.contains(&source.channel_id())),
+        }
+    }
+
+    /// The message shown to users who don't meet the requirement.
+    fn denial(&self, ctx: &CommandContext) -> String {
+        match self {
+            Self::Staff => ctx.tr("denied-staff", &[]),
+            Self::Owner => ctx.tr("denied-owner", &[]),
+            Self::Channels(channels) => {
+                let channels = channels
+                    .iter()
+                    .map(|channel| format!("<#{channel}>"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                ctx.tr("denied-channels", &[("channels", channels.into())])
+            }
+        }
+    }
+}
+
+/// Checks that the user of a command meets all of a set of requirements, failing
+/// with a user error explaining the first one they don't.
+///
+/// Called by the dispatcher for the command being used, and by the handlers
+/// generated for subcommands for the subcommand being used.
+pub async fn check(ctx: &CommandContext, requirements: &[Requirement]) -> CommandResult {
+    for requirement in requirements {
+        if !requirement.is_met(ctx, ctx.controller()).await? {
+            info!(
+                ?requirement,
+                user = %ctx.user().id,
+                channel = %ctx.interaction().channel_id,
+                "command denied",
+            );
+
+            return Err(CommandError::user(requirement.denial(ctx)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the user of an interaction could use a command registered under a
+/// controller with a set of requirements, as far as who they are goes. Channel
+/// restrictions are about where a command is used, so they're ignored.
+pub(crate) async fn allows(
+    source: &impl Invocation,
+    controller: Controller,
+    requirements: &[Requirement],
+) -> Result<bool, CommandError> {
+    for requirement in requirements {
+        if let Requirement::Channels(_) = requirement {
+            continue;
+        }
+
+        if !requirement.is_met(source, controller).await? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Whether the user of an interaction has the staff role of a controller's guild.
+pub(super) async fn is_staff(source: &impl Invocation, controller: Controller) -> bool {
+    let role = match staff_role(source, controller).await {
+        Some(role) => role,
+        None => return false,
+    };
+
+    source
+        .member()
+        .is_some_and(|member| member.roles.contains(&role))
+}
+
+/// The staff role of the guild of a controller, if it has one. Set in the
+/// `Config`, such as with `STAFF_ROLE_POKECOM`.
+async fn staff_role(source: &impl Invocation, controller: Controller) -> Option<RoleId> {
+    Config::get(source.data())
+        .await
+        .controller(controller)
+        .staff_role
+}
+
+/// The users who own the bot, fetched from Discord the first time they're needed
+/// and kept on the client data after that.
+struct Owners;
+
+impl TypeMapKey for Owners {
+    type Value = Arc<HashSet<UserId>>;
+}
+
+async fn owners(source: &impl Invocation) -> Result<Arc<HashSet<UserId>>, CommandError> {
+    if let Some(owners) = source.data().read().await.get::<Owners>() {
+        return Ok(owners.clone());
+    }
+
+    let info = source.http().get_current_application_info().await?;
+    let owners = match info.team {
+        Some(team) => team
+            .members
+            .into_iter()
+            .map(|member| member.user.id)
+            .collect(),
+        None => HashSet::from([info.owner.id]),
+    };
+
+    let owners = Arc::new(owners);
+    source.data().write().await.insert::<Owners>(owners.clone());
+
+    Ok(owners)
+}
@@ -0,0 +1,428 @@
+use super::{AnyInteraction, CommandContext, InteractionContext, Invocation};
+use crate::core::modal::Modal;
+use serenity::{
+    builder::{
+        CreateComponents, CreateEmbed, CreateInteractionResponseData,
+        CreateInteractionResponseFollowup, EditInteractionResponse,
+    },
+    model::{
+        application::interaction::InteractionResponseType, channel::AttachmentType,
+        channel::Message,
+    },
+    Error, Result,
+};
+use std::borrow::Cow;
+use std::time::Duration;
+
+/// How long a handler can run before the dispatcher defers the interaction on
+/// its behalf. Discord fails interactions that aren't acknowledged within three
+/// seconds, so this leaves some room for latency.
+pub(crate) const DEFER_AFTER: Duration = Duration::from_secs(2);
+
+/// How far along responding to an interaction is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ResponseState {
+    /// Nothing has been sent yet.
+    Pending,
+
+    /// Discord is showing a loading message, which the next reply replaces.
+    Deferred { ephemeral: bool },
+
+    /// The message a component is on has been updated, or will be. Replies are
+    /// sent as follow-ups, and the message can still be updated.
+    Updated,
+
+    /// A response has been sent, so anything else is a follow-up.
+    Sent,
+}
+
+/// Discord refuses embeds with more fields than this.
+pub const MAX_FIELDS: usize = 25;
+
+/// Discord refuses embed field values longer than this.
+pub const MAX_FIELD_LEN: usize = 1024;
+
+/// A message sent in response to an interaction. Plain strings convert into
+/// replies, so the builder is only needed for ephemeral replies, embeds,
+/// components or attachments.
+#[derive(Debug, Clone, Default)]
+pub struct Reply {
+    content: Option<String>,
+    embeds: Vec<CreateEmbed>,
+    components: Option<CreateComponents>,
+    ephemeral: bool,
+    attachments: Vec<(String, Vec<u8>)>,
+}
+
+impl Reply {
+    /// Creates a reply with some text.
+    pub fn text(content: impl ToString) -> Self {
+        Self::default().content(content)
+    }
+
+    /// Sets the text of the reply.
+    pub fn content(mut self, content: impl ToString) -> Self {
+        self.content = Some(content.to_string());
+        self
+    }
+
+    /// Adds an embed to the reply.
+    pub fn embed(mut self, f: impl FnOnce(&mut CreateEmbed) -> &mut CreateEmbed) -> Self {
+        let mut embed = CreateEmbed::default();
+        f(&mut embed);
+
+        self.embeds.push(embed);
+        self
+    }
+
+    /// Sets the buttons and select menus of the reply. See `core::component` for
+    /// handling them.
+    pub fn components(
+        mut self,
+        f: impl FnOnce(&mut CreateComponents) -> &mut CreateComponents,
+    ) -> Self {
+        let mut components = CreateComponents::default();
+        f(&mut components);
+
+        self.components = Some(components);
+        self
+    }
+
+    /// Makes the reply only visible to the user who used the command.
+    pub fn ephemeral(mut self) -> Self {
+        self.ephemeral = true;
+        self
+    }
+
+    /// Attaches a file to the reply.
+    pub fn attachment(mut self, filename: impl ToString, data: impl Into<Vec<u8>>) -> Self {
+        self.attachments.push((filename.to_string(), data.into()));
+        self
+    }
+
+    pub(crate) fn is_ephemeral(&self) -> bool {
+        self.ephemeral
+    }
+
+    pub(crate) fn has_attachments(&self) -> bool {
+        !self.attachments.is_empty()
+    }
+
+    /// Splits the attachments off into a reply of their own.
+    pub(crate) fn take_attachments(&mut self, ephemeral: bool) -> Reply {
+        Reply {
+            ephemeral,
+            attachments: std::mem::take(&mut self.attachments),
+            ..Reply::default()
+        }
+    }
+
+    /// Fills in the data of an initial response.
+    pub(crate) fn build_data<'a>(&'a self, data: &mut CreateInteractionResponseData<'a>) {
+        if let Some(content) = &self.content {
+            data.content(content);
+        }
+
+        if let Some(components) = &self.components {
+            data.set_components(components.clone());
+        }
+
+        data.add_embeds(self.embeds.clone())
+            .ephemeral(self.ephemeral)
+            .add_files(self.files());
+    }
+
+    /// Fills in a follow-up message.
+    pub(crate) fn build_followup<'a>(
+        &'a self,
+        followup: &mut CreateInteractionResponseFollowup<'a>,
+    ) {
+        if let Some(content) = &self.content {
+            followup.content(content);
+        }
+
+        if let Some(components) = &self.components {
+            followup.set_components(components.clone());
+        }
+
+        followup
+            .add_embeds(self.embeds.clone())
+            .ephemeral(self.ephemeral)
+            .add_files(self.files());
+    }
+
+    /// Fills in an edit of the initial response. Only what the reply sets is
+    /// changed, and attachments can't be added this way.
+    pub(crate) fn build_edit(&self, edit: &mut EditInteractionResponse) {
+        if let Some(content) = &self.content {
+            edit.content(content);
+        }
+
+        if let Some(components) = &self.components {
+            edit.set_components(components.clone());
+        }
+
+        if !self.embeds.is_empty() {
+            edit.set_embeds(self.embeds.clone());
+        }
+    }
+
+    fn files(&self) -> impl Iterator<Item = AttachmentType<'_>> {
+        self.attachments
+            .iter()
+            .map(|(filename, data)| AttachmentType::Bytes {
+                data: Cow::Borrowed(data),
+                filename: filename.clone(),
+            })
+    }
+}
+
+impl From<&str> for Reply {
+    fn from(content: &str) -> Self {
+        Self::text(content)
+    }
+}
+
+impl From<String> for Reply {
+    fn from(content: String) -> Self {
+        Self::text(content)
+    }
+}
+
+/// Adds fields to an embed, cutting their values down to what Discord accepts.
+/// Fields past `room`, which can't be more than `MAX_FIELDS`, are left out, and
+/// the footer says how many were.
+pub(crate) fn add_fields(
+    source: &impl Invocation,
+    embed: &mut CreateEmbed,
+    fields: Vec<(String, String)>,
+    room: usize,
+) {
+    let omitted = fields.len().saturating_sub(room);
+
+    for (name, value) in fields.into_iter().take(room) {
+        embed.field(name, field_value(value), false);
+    }
+
+    if omitted > 0 {
+        let footer = source.tr("fields-omitted", &[("count", omitted.into())]);
+        embed.footer(|f| f.text(footer));
+    }
+}
+
+/// Cuts an embed field value down to `MAX_FIELD_LEN` characters.
+pub(crate) fn field_value(value: String) -> String {
+    match value.chars().count() > MAX_FIELD_LEN {
+        true => value.chars().take(MAX_FIELD_LEN - 1).chain(['…']).collect(),
+        false => value,
+    }
+}
+
+impl<I: AnyInteraction> InteractionContext<I> {
+    /// Responds to the interaction. This does the right thing whatever has been
+    /// sent so far: it's the initial response if nothing has, replaces the loading
+    /// message if the interaction was deferred, and is sent as a follow-up if a
+    /// response already exists.
+    ///
+    /// A reply after an ephemeral defer is always ephemeral, since Discord can't
+    /// change the visibility of the loading message.
+    pub async fn reply(&self, reply: impl Into<Reply>) -> Result<()> {
+        let mut reply = reply.into();
+        let mut state = self.response.lock().await;
+
+        match *state {
+            ResponseState::Pending => {
+                self.interaction()
+                    .create_response(
+                        self.http(),
+                        InteractionResponseType::ChannelMessageWithSource,
+                        &reply,
+                    )
+                    .await?
+            }
+
+            ResponseState::Sent | ResponseState::Updated => {
+                self.interaction()
+                    .create_followup(self.http(), &reply)
+                    .await?;
+                return Ok(());
+            }
+
+            // Discord can't make the loading message ephemeral or add files to
+            // it, so it's replaced with a follow-up.
+            ResponseState::Deferred { ephemeral: false }
+                if reply.is_ephemeral() || reply.has_attachments() =>
+            {
+                self.interaction().delete_response(self.http()).await?;
+                self.interaction()
+                    .create_followup(self.http(), &reply)
+                    .await?;
+            }
+
+            ResponseState::Deferred { ephemeral } => {
+                let files = reply.take_attachments(ephemeral);
+                self.interaction()
+                    .edit_response(self.http(), &reply)
+                    .await?;
+
+                if files.has_attachments() {
+                    self.interaction()
+                        .create_followup(self.http(), &files)
+                        .await?;
+                }
+            }
+        }
+
+        *state = ResponseState::Sent;
+        Ok(())
+    }
+
+    /// Sends an additional message after the response. Fails if nothing has been
+    /// sent yet, use `reply` for that. After a defer, the first follow-up replaces
+    /// the loading message.
+    pub async fn follow_up(&self, reply: impl Into<Reply>) -> Result<Message> {
+        let mut state = self.response.lock().await;
+
+        if *state == ResponseState::Pending {
+            return Err(Error::Other("can't follow up before responding"));
+        }
+
+        let message = self
+            .interaction()
+            .create_followup(self.http(), &reply.into())
+            .await?;
+
+        if let ResponseState::Deferred { .. } = *state {
+            *state = ResponseState::Sent;
+        }
+
+        Ok(message)
+    }
+
+    /// Edits the text and embeds of the initial response. Attachments can't be
+    /// added this way, and fail the edit.
+    pub async fn edit(&self, reply: impl Into<Reply>) -> Result<Message> {
+        let reply = reply.into();
+        let state = self.response.lock().await;
+
+        if *state == ResponseState::Pending {
+            return Err(Error::Other("can't edit before responding"));
+        }
+
+        if reply.has_attachments() {
+            return Err(Error::Other(
+                "can't add attachments to a response by editing it",
+            ));
+        }
+
+        self.interaction().edit_response(self.http(), &reply).await
+    }
+
+    /// Responds by showing a modal, whose submission is handled by its
+    /// `ModalHandler`. Fails if anything has been sent already, since a modal
+    /// can only be the first response, and Discord refuses it in response to
+    /// another modal.
+    pub async fn show_modal<M: Modal>(&self) -> Result<()> {
+        let mut state = self.response.lock().await;
+
+        if *state != ResponseState::Pending {
+            return Err(Error::Other("a modal can only be the first response"));
+        }
+
+        self.interaction().create_modal::<M>(self.http()).await?;
+
+        *state = ResponseState::Sent;
+        Ok(())
+    }
+
+    /// Whether the interaction has been acknowledged, including by a defer.
+    pub async fn has_responded(&self) -> bool {
+        *self.response.lock().await != ResponseState::Pending
+    }
+
+    /// Whether a response has been sent, not counting a loading message.
+    pub async fn has_replied(&self) -> bool {
+        *self.response.lock().await == ResponseState::Sent
+    }
+
+    /// Acknowledges the interaction the way its kind is deferred, unless it
+    /// already has been.
+    pub(crate) async fn acknowledge(&self, ephemeral: bool) -> Result<()> {
+        let mut state = self.response.lock().await;
+
+        if *state == ResponseState::Pending {
+            self.interaction()
+                .create_defer(self.http(), ephemeral)
+                .await?;
+            *state = Self::deferred(ephemeral);
+        }
+
+        Ok(())
+    }
+
+    /// Defers the interaction on behalf of a slow handler. Skipped if the handler is
+    /// responding right now, since the handler's future isn't being polled while
+    /// this runs, and waiting for it would deadlock.
+    pub(crate) async fn auto_defer(&self) {
+        let mut state = match self.response.try_lock() {
+            Ok(state) => state,
+            Err(_) => return,
+        };
+
+        if *state != ResponseState::Pending {
+            return;
+        }
+
+        match self.interaction().create_defer(self.http(), false).await {
+            Ok(()) => *state = Self::deferred(false),
+            Err(e) => error!(%e, kind = I::KIND, "failed to defer slow interaction"),
+        }
+    }
+
+    /// Replaces the content, embeds and components of the message a component
+    /// is on. See `ComponentContext::update`.
+    pub(crate) async fn update_message(&self, reply: Reply) -> Result<()> {
+        let mut state = self.response.lock().await;
+
+        match *state {
+            ResponseState::Pending => {
+                self.interaction()
+                    .create_response(self.http(), InteractionResponseType::UpdateMessage, &reply)
+                    .await?
+            }
+            ResponseState::Updated => {
+                self.interaction()
+                    .edit_response(self.http(), &reply)
+                    .await?;
+            }
+            ResponseState::Deferred { .. } | ResponseState::Sent => {
+                return Err(Error::Other("can't update the message after replying"))
+            }
+        }
+
+        *state = ResponseState::Updated;
+        Ok(())
+    }
+
+    /// The state after a defer, which depends on whether it shows a loading
+    /// message or leaves the message a component is on as it is.
+    fn deferred(ephemeral: bool) -> ResponseState {
+        match I::DEFER {
+            InteractionResponseType::DeferredUpdateMessage => ResponseState::Updated,
+            _ => ResponseState::Deferred { ephemeral },
+        }
+    }
+}
+
+impl CommandContext {
+    /// Acknowledges the interaction and shows a loading message, giving the handler
+    /// up to 15 minutes to reply. Does nothing if the interaction has already been
+    /// acknowledged.
+    ///
+    /// Handlers that are slow for a known reason can call this up front to choose
+    /// the visibility of the loading message. Otherwise the dispatcher defers
+    /// publicly once the handler has taken too long.
+    pub async fn defer(&self, ephemeral: bool) -> Result<()> {
+        self.acknowledge(ephemeral).await
+    }
+}
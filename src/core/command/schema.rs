@@ -0,0 +1,163 @@
+use crate::core::i18n;
+use serde_json::{json, Map, Value};
+use serenity::model::application::command::CommandOptionType;
+
+/// The description of a single command option, as uploaded to Discord. Built by the
+/// `Command` derive from the fields of an argument struct, but can also be built by
+/// hand for commands that implement `Command` manually.
+#[derive(Debug, Clone)]
+pub struct OptionSchema {
+    kind: CommandOptionType,
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+    choices: Vec<(&'static str, Value)>,
+    min_value: Option<Value>,
+    max_value: Option<Value>,
+    min_length: Option<u16>,
+    max_length: Option<u16>,
+    channel_types: &'static [u8],
+    autocomplete: bool,
+    options: Vec<OptionSchema>,
+    key: Option<&'static str>,
+}
+
+impl OptionSchema {
+    /// Creates the schema of an option with no constraints.
+    pub fn new(
+        kind: CommandOptionType,
+        name: &'static str,
+        description: &'static str,
+        required: bool,
+    ) -> Self {
+        Self {
+            kind,
+            name,
+            description,
+            required,
+            choices: Vec::new(),
+            min_value: None,
+            max_value: None,
+            min_length: None,
+            max_length: None,
+            channel_types: &[],
+            autocomplete: false,
+            options: Vec::new(),
+            key: None,
+        }
+    }
+
+    /// Adds a fixed choice. If an option has any choices, users can only pick one of them.
+    pub fn choice(mut self, name: &'static str, value: impl Into<Value>) -> Self {
+        self.choices.push((name, value.into()));
+        self
+    }
+
+    /// Sets the minimum value of a numeric option.
+    pub fn min_value(mut self, value: impl Into<Value>) -> Self {
+        self.min_value = Some(value.into());
+        self
+    }
+
+    /// Sets the maximum value of a numeric option.
+    pub fn max_value(mut self, value: impl Into<Value>) -> Self {
+        self.max_value = Some(value.into());
+        self
+    }
+
+    /// Sets the minimum length of a string option.
+    pub fn min_length(mut self, length: u16) -> Self {
+        self.min_length = Some(length);
+        self
+    }
+
+    /// Sets the maximum length of a string option.
+    pub fn max_length(mut self, length: u16) -> Self {
+        self.max_length = Some(length);
+        self
+    }
+
+    /// Restricts a channel option to the given channel types, by their Discord IDs.
+    pub fn channel_types(mut self, types: &'static [u8]) -> Self {
+        self.channel_types = types;
+        self
+    }
+
+    /// Enables autocomplete for the option.
+    pub fn autocomplete(mut self) -> Self {
+        self.autocomplete = true;
+        self
+    }
+
+    /// Sets the nested options of a subcommand or subcommand group.
+    pub fn options(mut self, options: Vec<OptionSchema>) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Translates the name and description of the option with the message with the
+    /// given key. See `i18n::add_localizations`.
+    pub fn localize(mut self, key: &'static str) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Returns the option as the JSON object Discord expects.
+    pub fn to_json(&self) -> Value {
+        let mut map = Map::new();
+
+        map.insert("type".into(), json!(self.kind as u8));
+        map.insert("name".into(), json!(self.name));
+        map.insert("description".into(), json!(self.description));
+
+        if let Some(key) = self.key {
+            i18n::add_localizations(&mut map, key);
+        }
+
+        if self.required {
+            map.insert("required".into(), json!(true));
+        }
+
+        if !self.choices.is_empty() {
+            let choices = self
+                .choices
+                .iter()
+                .map(|(name, value)| json!({ "name": name, "value": value }))
+                .collect();
+
+            map.insert("choices".into(), Value::Array(choices));
+        }
+
+        let optional = [
+            ("min_value", self.min_value.clone()),
+            ("max_value", self.max_value.clone()),
+            ("min_length", self.min_length.map(Value::from)),
+            ("max_length", self.max_length.map(Value::from)),
+        ];
+
+        for (key, value) in optional {
+            if let Some(value) = value {
+                map.insert(key.into(), value);
+            }
+        }
+
+        if !self.channel_types.is_empty() {
+            map.insert("channel_types".into(), json!(self.channel_types));
+        }
+
+        if self.autocomplete {
+            map.insert("autocomplete".into(), json!(true));
+        }
+
+        if !self.options.is_empty() {
+            map.insert("options".into(), options_to_json(&self.options));
+        }
+
+        Value::Object(map)
+    }
+}
+
+/// Converts a list of option schemas into a JSON array.
+pub(super) fn options_to_json(options: &[OptionSchema]) -> Value {
+    Value::Array(options.iter().map(OptionSchema::to_json).collect())
+}
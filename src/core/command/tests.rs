@@ -0,0 +1,793 @@
+use super::{
+    add_fields,
+    autocomplete::suggestions_to_json,
+    error::{truncate, MAX_DESCRIPTION_LEN},
+    field_value, parse_option, ArgumentError, Bucket, Command, CommandContext, CommandError,
+    CommandResult, Cooldown, Cooldowns, Dispatcher, FromInteraction, FromOption, Handler,
+    Mentionable, Reply, ResolvedMember, Suggestion, MAX_FIELDS, MAX_FIELD_LEN, MAX_SUGGESTIONS,
+    MAX_SUGGESTION_LEN,
+};
+use crate::core::{
+    controller::POKECOM,
+    modal::{Modal, ModalContext, ModalHandler},
+    testing::{json, Effect, FakeGuild, FakeMember, Harness, Outcome},
+};
+use serde_json::{json, Value};
+use serenity::model::{
+    application::{
+        command::CommandOptionType,
+        interaction::application_command::{CommandDataOption, CommandDataOptionValue},
+    },
+    guild::{PartialMember, Role},
+    id::{ChannelId, GuildId, RoleId, UserId},
+    user::User,
+};
+use std::any::TypeId;
+use std::io;
+use std::time::{Duration, Instant};
+
+const GUILD: GuildId = GuildId(1);
+
+/// An option as Discord sends it, with the value serenity resolved it to.
+fn option(
+    name: &str,
+    kind: CommandOptionType,
+    resolved: CommandDataOptionValue,
+) -> CommandDataOption {
+    let mut option: CommandDataOption = serde_json::from_value(json!({
+        "name": name,
+        "type": kind,
+    }))
+    .unwrap();
+
+    option.resolved = Some(resolved);
+    option
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
+
+fn user() -> User {
+    from_json(json::user(UserId(2), "user"))
+}
+
+fn member() -> PartialMember {
+    from_json(json::member(GUILD, json::user(UserId(2), "user"), &[]))
+}
+
+fn role() -> Role {
+    from_json(json::role(GUILD, RoleId(3), "role"))
+}
+
+#[test]
+fn parses_primitives_by_name() {
+    let options = [
+        option(
+            "text",
+            CommandOptionType::String,
+            CommandDataOptionValue::String("hi".into()),
+        ),
+        option(
+            "count",
+            CommandOptionType::Integer,
+            CommandDataOptionValue::Integer(3),
+        ),
+        option(
+            "ratio",
+            CommandOptionType::Number,
+            CommandDataOptionValue::Number(0.5),
+        ),
+        option(
+            "flag",
+            CommandOptionType::Boolean,
+            CommandDataOptionValue::Boolean(true),
+        ),
+    ];
+
+    assert_eq!(parse_option::<String>(&options, "text").unwrap(), "hi");
+    assert_eq!(parse_option::<i64>(&options, "count").unwrap(), 3);
+    assert_eq!(parse_option::<f64>(&options, "ratio").unwrap(), 0.5);
+    assert!(parse_option::<bool>(&options, "flag").unwrap());
+}
+
+#[test]
+fn missing_options_fail_unless_optional() {
+    assert!(matches!(
+        parse_option::<String>(&[], "text"),
+        Err(ArgumentError::Missing { name: "text" })
+    ));
+    assert_eq!(parse_option::<Option<String>>(&[], "text").unwrap(), None);
+
+    let options = [option(
+        "text",
+        CommandOptionType::String,
+        CommandDataOptionValue::String("hi".into()),
+    )];
+    assert_eq!(
+        parse_option::<Option<String>>(&options, "text")
+            .unwrap()
+            .as_deref(),
+        Some("hi")
+    );
+}
+
+#[test]
+fn options_of_the_wrong_type_fail() {
+    let options = [option(
+        "count",
+        CommandOptionType::String,
+        CommandDataOptionValue::String("3".into()),
+    )];
+
+    assert!(matches!(
+        parse_option::<i64>(&options, "count"),
+        Err(ArgumentError::WrongType {
+            name: "count",
+            expected: CommandOptionType::Integer,
+            found: CommandOptionType::String,
+        })
+    ));
+
+    // An optional option that's given still has to have the right type.
+    assert!(matches!(
+        parse_option::<Option<i64>>(&options, "count"),
+        Err(ArgumentError::WrongType { .. })
+    ));
+}
+
+#[test]
+fn unresolved_options_fail() {
+    let mut unresolved = option(
+        "role",
+        CommandOptionType::Role,
+        CommandDataOptionValue::Role(role()),
+    );
+    unresolved.resolved = None;
+
+    assert!(matches!(
+        Role::from_option("role", Some(&unresolved)),
+        Err(ArgumentError::Unresolved { name: "role" })
+    ));
+
+    // Resolved to something other than what the type says.
+    let mismatched = option(
+        "role",
+        CommandOptionType::Role,
+        CommandDataOptionValue::Integer(3),
+    );
+
+    assert!(matches!(
+        Role::from_option("role", Some(&mismatched)),
+        Err(ArgumentError::Unresolved { name: "role" })
+    ));
+}
+
+#[test]
+fn members_must_be_in_the_guild() {
+    let in_guild = [option(
+        "target",
+        CommandOptionType::User,
+        CommandDataOptionValue::User(user(), Some(member())),
+    )];
+    let left = [option(
+        "target",
+        CommandOptionType::User,
+        CommandDataOptionValue::User(user(), None),
+    )];
+
+    let resolved = parse_option::<ResolvedMember>(&in_guild, "target").unwrap();
+    assert_eq!(resolved.user.id, UserId(2));
+    assert_eq!(resolved.member.guild_id, Some(GUILD));
+
+    assert!(matches!(
+        parse_option::<ResolvedMember>(&left, "target"),
+        Err(ArgumentError::NotAMember { name: "target" })
+    ));
+
+    // A plain user doesn't care whether they're a member.
+    assert_eq!(parse_option::<User>(&left, "target").unwrap().id, UserId(2));
+}
+
+#[test]
+fn mentionables_are_users_or_roles() {
+    let options = [
+        option(
+            "user",
+            CommandOptionType::Mentionable,
+            CommandDataOptionValue::User(user(), None),
+        ),
+        option(
+            "role",
+            CommandOptionType::Mentionable,
+            CommandDataOptionValue::Role(role()),
+        ),
+    ];
+
+    assert!(matches!(
+        parse_option::<Mentionable>(&options, "user"),
+        Ok(Mentionable::User(user, None)) if user.id == UserId(2)
+    ));
+    assert!(matches!(
+        parse_option::<Mentionable>(&options, "role"),
+        Ok(Mentionable::Role(role)) if role.id == RoleId(3)
+    ));
+}
+
+mod role {
+    use super::*;
+
+    /// Adds a role.
+    #[derive(Command)]
+    pub struct Add {
+        /// The role to add.
+        pub role: String,
+    }
+
+    /// Lists the roles.
+    #[derive(Command)]
+    pub struct List;
+
+    #[async_trait]
+    impl Handler for Add {
+        async fn handle(self, _: &CommandContext) -> CommandResult {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Handler for List {
+        async fn handle(self, _: &CommandContext) -> CommandResult {
+            Ok(())
+        }
+    }
+}
+
+/// Manages roles.
+#[derive(Command)]
+enum Roles {
+    Add(role::Add),
+    List(role::List),
+}
+
+/// Configures the bot.
+#[derive(Command)]
+#[command(default_permissions(manage_guild))]
+enum Settings {
+    Roles(Roles),
+}
+
+/// A subcommand or subcommand group as Discord sends it.
+fn subcommand(name: &str, options: Vec<CommandDataOption>) -> CommandDataOption {
+    let mut option: CommandDataOption = serde_json::from_value(json!({
+        "name": name,
+        "type": CommandOptionType::SubCommand,
+    }))
+    .unwrap();
+
+    option.options = options;
+    option
+}
+
+#[test]
+fn routes_to_the_invoked_subcommand() {
+    let add = subcommand(
+        "add",
+        vec![option(
+            "role",
+            CommandOptionType::String,
+            CommandDataOptionValue::String("artist".into()),
+        )],
+    );
+
+    assert!(matches!(
+        Roles::from_options(std::slice::from_ref(&add)),
+        Ok(Roles::Add(role::Add { role })) if role == "artist"
+    ));
+    assert!(matches!(
+        Roles::from_options(&[subcommand("list", vec![])]),
+        Ok(Roles::List(_))
+    ));
+    assert!(matches!(
+        Settings::from_options(&[subcommand("roles", vec![add])]),
+        Ok(Settings::Roles(Roles::Add(_)))
+    ));
+}
+
+#[test]
+fn missing_and_unknown_subcommands_fail() {
+    assert!(matches!(
+        Roles::from_options(&[]),
+        Err(ArgumentError::MissingSubcommand)
+    ));
+    assert!(matches!(
+        Roles::from_options(&[subcommand("remove", vec![])]),
+        Err(ArgumentError::UnknownSubcommand { name }) if name == "remove"
+    ));
+}
+
+#[test]
+fn groups_are_nested_by_depth() {
+    assert_eq!(role::Add::DEPTH, 0);
+    assert_eq!(Roles::DEPTH, 1);
+    assert_eq!(Settings::DEPTH, 2);
+
+    let data = Settings::data();
+    let group = &data["options"][0];
+
+    assert_eq!(data["default_member_permissions"], "32");
+    assert_eq!(group["name"], "roles");
+    assert_eq!(group["type"], 2);
+    assert_eq!(group["options"][0]["name"], "add");
+    assert_eq!(group["options"][0]["type"], 1);
+    assert_eq!(group["options"][0]["options"][0]["name"], "role");
+    assert_eq!(group["options"][1]["name"], "list");
+}
+
+const USER: u64 = 200;
+const OTHER_USER: u64 = 201;
+const ERRORS: u64 = 300;
+
+/// Replies, then edits and follows up.
+#[derive(Command)]
+struct Respond;
+
+/// Tries to respond out of order.
+#[derive(Command)]
+struct OutOfOrder;
+
+/// Defers, then replies.
+#[derive(Command)]
+struct Slow {
+    /// Whether to defer ephemerally.
+    ephemeral: bool,
+
+    /// Whether to reply ephemerally.
+    reply_ephemeral: bool,
+}
+
+/// Lists more fields than fit in an embed.
+#[derive(Command)]
+struct Crowded;
+
+/// Refuses to do anything.
+#[derive(Command)]
+struct Refused;
+
+/// Always breaks.
+#[derive(Command)]
+struct Broken;
+
+/// Waves, once a minute in each channel.
+#[derive(Command)]
+#[command(cooldown(per = "channel", every = "1m"))]
+struct Wave;
+
+/// Cheers, once a minute in each guild.
+#[derive(Command)]
+#[command(cooldown(per = "guild", every = "1m"))]
+struct Cheer;
+
+/// Report
+#[derive(Modal)]
+struct Report {
+    /// What happened?
+    details: String,
+}
+
+#[async_trait]
+impl Handler for Respond {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.reply("First").await?;
+        ctx.edit("Edited").await?;
+        ctx.follow_up("More").await?;
+        ctx.reply("Again").await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for OutOfOrder {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let early_edit = ctx.edit("Edited").await.is_err();
+        let early_follow_up = ctx.follow_up("More").await.is_err();
+
+        ctx.reply("First").await?;
+
+        let late_modal = ctx.show_modal::<Report>().await.is_err();
+
+        ctx.reply(format!("{early_edit} {early_follow_up} {late_modal}"))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ModalHandler for Report {
+    async fn handle(self, ctx: &ModalContext) -> CommandResult {
+        ctx.reply(self.details).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Slow {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.defer(self.ephemeral).await?;
+
+        let mut reply = Reply::text("Done");
+
+        if self.reply_ephemeral {
+            reply = reply.ephemeral();
+        }
+
+        ctx.reply(reply).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Crowded {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let fields = (0..30)
+            .map(|i| (i.to_string(), "Field".to_string()))
+            .collect();
+
+        ctx.reply(Reply::default().embed(|e| {
+            add_fields(ctx, e, fields, MAX_FIELDS);
+            e
+        }))
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Refused {
+    async fn handle(self, _: &CommandContext) -> CommandResult {
+        Err(CommandError::user("You can't do that."))
+    }
+}
+
+#[async_trait]
+impl Handler for Broken {
+    async fn handle(self, _: &CommandContext) -> CommandResult {
+        Err(io::Error::other("The disk is on fire.").into())
+    }
+}
+
+#[async_trait]
+impl Handler for Wave {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.reply("Hello!").await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Cheer {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.reply("Hooray!").await?;
+        Ok(())
+    }
+}
+
+async fn harness() -> Harness {
+    let guild = FakeGuild::of(POKECOM)
+        .error_channel(ERRORS)
+        .member(FakeMember::new(USER, "user"))
+        .member(FakeMember::new(OTHER_USER, "other"));
+
+    Harness::builder()
+        .guild(guild)
+        .start_with(|setup| {
+            setup
+                .add_command::<Respond>(POKECOM)
+                .add_command::<OutOfOrder>(POKECOM)
+                .add_command::<Slow>(POKECOM)
+                .add_command::<Crowded>(POKECOM)
+                .add_command::<Refused>(POKECOM)
+                .add_command::<Broken>(POKECOM)
+                .add_command::<Wave>(POKECOM)
+                .add_command::<Cheer>(POKECOM)
+        })
+        .await
+}
+
+/// What the bot did in response, as the kind of each request along with the
+/// content it sent.
+fn steps(outcome: &Outcome) -> Vec<(String, &str)> {
+    outcome
+        .effects
+        .iter()
+        .filter_map(|effect| match effect {
+            Effect::Response { kind, message, .. } => {
+                Some((format!("response {kind}"), message.content()))
+            }
+            Effect::Edit {
+                message_id,
+                message,
+                ..
+            } => Some((format!("edit {message_id}"), message.content())),
+            Effect::FollowUp { message, .. } => Some(("follow-up".into(), message.content())),
+            Effect::Delete { message_id, .. } => Some((format!("delete {message_id}"), "")),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn replies_after_the_response_are_follow_ups() {
+    let harness = harness().await;
+    let outcome = harness.user(USER).command("/respond", Value::Null).await;
+
+    assert_eq!(
+        steps(&outcome),
+        [
+            ("response 4".into(), "First"),
+            ("edit @original".into(), "Edited"),
+            ("follow-up".into(), "More"),
+            ("follow-up".into(), "Again"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn refuses_to_respond_out_of_order() {
+    let harness = harness().await;
+    let outcome = harness
+        .user(USER)
+        .command("/out-of-order", Value::Null)
+        .await;
+
+    // Only the two replies went through, and the modal was refused.
+    assert_eq!(
+        steps(&outcome),
+        [
+            ("response 4".into(), "First"),
+            ("follow-up".into(), "true true true"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn replies_after_a_defer_replace_the_loading_message() {
+    let harness = harness().await;
+    let options = json!({ "ephemeral": true, "reply_ephemeral": false });
+    let outcome = harness.user(USER).command("/slow", options).await;
+
+    assert_eq!(
+        steps(&outcome),
+        [("response 5".into(), ""), ("edit @original".into(), "Done"),]
+    );
+
+    // The loading message, and so the reply replacing it, is ephemeral.
+    assert!(matches!(
+        &outcome.effects[0],
+        Effect::Response { message, .. } if message.is_ephemeral()
+    ));
+}
+
+#[tokio::test]
+async fn ephemeral_replies_after_a_public_defer_are_follow_ups() {
+    let harness = harness().await;
+    let options = json!({ "ephemeral": false, "reply_ephemeral": true });
+    let outcome = harness.user(USER).command("/slow", options).await;
+
+    assert_eq!(
+        steps(&outcome),
+        [
+            ("response 5".into(), ""),
+            ("delete @original".into(), ""),
+            ("follow-up".into(), "Done"),
+        ]
+    );
+    assert!(outcome.reply().is_ephemeral());
+}
+
+#[tokio::test]
+async fn fields_past_the_limit_are_counted_in_the_footer() {
+    let harness = harness().await;
+    let outcome = harness.user(USER).command("/crowded", Value::Null).await;
+    let embed = &outcome.reply().embeds()[0];
+
+    assert_eq!(embed["fields"].as_array().unwrap().len(), MAX_FIELDS);
+    assert_eq!(embed["fields"][24]["name"], "24");
+    assert_eq!(embed["footer"]["text"], "5 more aren't shown.");
+}
+
+#[tokio::test]
+async fn commands_used_outside_their_controller_are_refused() {
+    let harness = harness().await;
+    Dispatcher::match_guilds_with(harness.data(), |_, guild_id| guild_id.is_none()).await;
+
+    let outcome = harness.user(USER).command("/refused", Value::Null).await;
+
+    assert_eq!(
+        outcome.reply().content(),
+        "This command isn't available here."
+    );
+    assert!(outcome.reply().embeds().is_empty());
+    assert!(outcome.reply().is_ephemeral());
+}
+
+#[tokio::test]
+async fn user_errors_are_shown_to_the_user() {
+    let harness = harness().await;
+    let outcome = harness.user(USER).command("/refused", Value::Null).await;
+
+    assert_eq!(
+        outcome.reply().embeds()[0]["description"],
+        "You can't do that."
+    );
+    assert!(outcome.reply().is_ephemeral());
+    assert!(harness.messages(ChannelId(ERRORS)).is_empty());
+}
+
+#[tokio::test]
+async fn internal_errors_are_posted_with_the_id_given_to_the_user() {
+    let harness = harness().await;
+    let outcome = harness.user(USER).command("/broken", Value::Null).await;
+
+    let description = outcome.reply().embeds()[0]["description"].as_str().unwrap();
+    let id = description.split('`').nth(1).unwrap();
+
+    assert_eq!(id.len(), 8);
+    assert!(!description.contains("The disk is on fire."));
+    assert!(outcome.reply().is_ephemeral());
+
+    let posted = harness.messages(ChannelId(ERRORS));
+    let report = &posted[0].embeds()[0];
+
+    assert_eq!(posted.len(), 1);
+    assert_eq!(report["title"], format!("Error {id}"));
+    assert!(report["description"]
+        .as_str()
+        .unwrap()
+        .contains("The disk is on fire."));
+}
+
+#[test]
+fn refusals_are_internal_when_the_caller_says_so() {
+    let refuse = |internal| CommandError::refuse(io::Error::other("Nope."), |_| internal);
+
+    assert!(matches!(refuse(false), CommandError::User(message) if message == "Nope."));
+    assert!(matches!(refuse(true), CommandError::Internal(_)));
+}
+
+#[test]
+fn long_error_details_are_truncated() {
+    let details = truncate(format!("```\n{}\n```", "é".repeat(MAX_DESCRIPTION_LEN)));
+
+    assert!(details.len() <= MAX_DESCRIPTION_LEN);
+    assert!(details.ends_with("é…\n```"));
+    assert_eq!(truncate("```\nShort\n```".into()), "```\nShort\n```");
+}
+
+#[test]
+fn long_field_values_are_truncated() {
+    let value = field_value("é".repeat(MAX_FIELD_LEN + 1));
+
+    assert_eq!(value.chars().count(), MAX_FIELD_LEN);
+    assert!(value.ends_with("é…"));
+    assert_eq!(field_value("Short".into()), "Short");
+}
+
+#[test]
+fn long_suggestions_are_truncated() {
+    let long = "é".repeat(150);
+    let json = suggestions_to_json(vec![
+        Suggestion::from(long.as_str()),
+        Suggestion::new(&long, 3),
+    ]);
+
+    let truncated = "é".repeat(MAX_SUGGESTION_LEN);
+
+    assert_eq!(json[0]["name"], truncated);
+    assert_eq!(json[0]["value"], truncated);
+    assert_eq!(json[1]["name"], truncated);
+    assert_eq!(json[1]["value"], 3);
+}
+
+#[test]
+fn suggestions_past_the_limit_are_dropped() {
+    let suggestions = (0..40).map(|i| Suggestion::from(i.to_string())).collect();
+    let json = suggestions_to_json(suggestions);
+
+    assert_eq!(json.as_array().unwrap().len(), MAX_SUGGESTIONS);
+    assert_eq!(json[0]["name"], "0");
+    assert_eq!(json[MAX_SUGGESTIONS - 1]["name"], "24");
+}
+
+const TWICE_A_MINUTE: Cooldown = Cooldown {
+    per: Bucket::User,
+    uses: 2,
+    every: Duration::from_secs(60),
+};
+
+#[tokio::test]
+async fn cooldowns_allow_uses_per_sliding_window() {
+    let cooldowns = Cooldowns::default();
+    let key = (TypeId::of::<Respond>(), Bucket::User, USER);
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+
+    assert_eq!(cooldowns.try_use(key, TWICE_A_MINUTE, at(0)).await, Ok(()));
+    assert_eq!(cooldowns.try_use(key, TWICE_A_MINUTE, at(20)).await, Ok(()));
+
+    // The window starts at the oldest use, so the third waits for it to expire.
+    assert_eq!(
+        cooldowns.try_use(key, TWICE_A_MINUTE, at(30)).await,
+        Err(Duration::from_secs(30))
+    );
+    assert_eq!(
+        cooldowns.try_use(key, TWICE_A_MINUTE, at(59)).await,
+        Err(Duration::from_secs(1))
+    );
+
+    // Refused uses don't count, and a use expires exactly `every` after it.
+    assert_eq!(cooldowns.try_use(key, TWICE_A_MINUTE, at(60)).await, Ok(()));
+    assert_eq!(
+        cooldowns.try_use(key, TWICE_A_MINUTE, at(61)).await,
+        Err(Duration::from_secs(19))
+    );
+    assert_eq!(cooldowns.try_use(key, TWICE_A_MINUTE, at(80)).await, Ok(()));
+}
+
+#[tokio::test]
+async fn cooldown_buckets_are_separate() {
+    let cooldowns = Cooldowns::default();
+    let now = Instant::now();
+    let key = (TypeId::of::<Respond>(), Bucket::User, USER);
+
+    for _ in 0..2 {
+        cooldowns.try_use(key, TWICE_A_MINUTE, now).await.unwrap();
+    }
+
+    assert!(cooldowns.try_use(key, TWICE_A_MINUTE, now).await.is_err());
+
+    let other_user = (TypeId::of::<Respond>(), Bucket::User, USER + 1);
+    let other_command = (TypeId::of::<Slow>(), Bucket::User, USER);
+
+    assert!(cooldowns
+        .try_use(other_user, TWICE_A_MINUTE, now)
+        .await
+        .is_ok());
+    assert!(cooldowns
+        .try_use(other_command, TWICE_A_MINUTE, now)
+        .await
+        .is_ok());
+}
+
+/// Whether a command was refused for being used too often.
+fn throttled(outcome: &Outcome) -> bool {
+    outcome
+        .reply()
+        .embeds()
+        .first()
+        .and_then(|embed| embed["description"].as_str())
+        .is_some_and(|description| description.starts_with("Slow down!"))
+}
+
+#[tokio::test]
+async fn channel_cooldowns_are_shared_by_the_channel() {
+    let harness = harness().await;
+    let (user, other) = (harness.user(USER), harness.user(OTHER_USER));
+
+    assert!(!throttled(&user.command("/wave", Value::Null).await));
+    assert!(throttled(&other.command("/wave", Value::Null).await));
+
+    let elsewhere = other.in_channel(2);
+    assert!(!throttled(&elsewhere.command("/wave", Value::Null).await));
+}
+
+#[tokio::test]
+async fn guild_cooldowns_are_shared_by_the_guild() {
+    let harness = harness().await;
+    let (user, other) = (harness.user(USER), harness.user(OTHER_USER));
+
+    assert!(!throttled(&user.command("/cheer", Value::Null).await));
+    assert!(throttled(
+        &other.in_channel(2).command("/cheer", Value::Null).await
+    ));
+}
@@ -0,0 +1,50 @@
+use super::custom_id;
+use crate::core::command::{InteractionContext, Reply};
+use serenity::{
+    model::{
+        application::interaction::message_component::MessageComponentInteraction, channel::Message,
+    },
+    Result,
+};
+
+/// Everything a component handler has access to while handling an interaction.
+///
+/// Unlike commands, components can either respond with a new message or update
+/// the message they're on. See `InteractionContext::reply` and
+/// `ComponentContext::update`.
+pub type ComponentContext = InteractionContext<MessageComponentInteraction>;
+
+impl ComponentContext {
+    /// The message the component is on.
+    pub fn message(&self) -> &Message {
+        &self.interaction().message
+    }
+
+    /// The custom ID of the component that was used.
+    pub fn custom_id(&self) -> &str {
+        &self.interaction().data.custom_id
+    }
+
+    /// The namespace of the custom ID of the component that was used.
+    pub fn namespace(&self) -> &str {
+        custom_id::split(self.custom_id()).0
+    }
+
+    /// The values that were picked, if the component is a select menu.
+    pub fn values(&self) -> &[String] {
+        &self.interaction().data.values
+    }
+
+    /// Replaces the content, embeds and components of the message the component
+    /// is on. Fails once a new message has been sent with `reply`.
+    pub async fn update(&self, reply: impl Into<Reply>) -> Result<()> {
+        self.update_message(reply.into()).await
+    }
+
+    /// Acknowledges the interaction without changing anything yet, giving the
+    /// handler up to 15 minutes to `update` the message. Does nothing if the
+    /// interaction has already been acknowledged.
+    pub async fn defer(&self) -> Result<()> {
+        self.acknowledge(false).await
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::{error::Error, fmt};
+
+/// Discord refuses custom IDs longer than this.
+pub const MAX_CUSTOM_ID_LEN: usize = 100;
+
+/// Separates the namespace of a custom ID from its state.
+const SEPARATOR: char = ':';
+
+/// The ways encoding or decoding a custom ID can fail.
+#[derive(Debug)]
+pub enum CustomIdError {
+    /// The encoded state doesn't fit in a custom ID.
+    TooLong { namespace: &'static str, len: usize },
+
+    /// The state couldn't be converted to or from JSON. When decoding, this means
+    /// the component was sent by an older version of the bot.
+    Json(serde_json::Error),
+}
+
+impl Error for CustomIdError {}
+
+impl fmt::Display for CustomIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong { namespace, len } => write!(
+                f,
+                "custom ID in {namespace} is {len} characters long, more than the limit of {MAX_CUSTOM_ID_LEN}"
+            ),
+            Self::Json(e) => write!(f, "invalid custom ID state: {e}"),
+        }
+    }
+}
+
+impl From<serde_json::Error> for CustomIdError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+/// Encodes a component's state as JSON after its namespace. Components without
+/// state get just their namespace.
+pub(super) fn encode<T: Serialize>(
+    namespace: &'static str,
+    state: &T,
+) -> Result<String, CustomIdError> {
+    let custom_id = match serde_json::to_string(state)?.as_str() {
+        "null" => namespace.to_string(),
+        state => format!("{namespace}{SEPARATOR}{state}"),
+    };
+
+    let len = custom_id.chars().count();
+
+    match len > MAX_CUSTOM_ID_LEN {
+        true => Err(CustomIdError::TooLong { namespace, len }),
+        false => Ok(custom_id),
+    }
+}
+
+/// Decodes the state encoded by `encode`.
+pub(super) fn decode<T: DeserializeOwned>(custom_id: &str) -> Result<T, CustomIdError> {
+    match split(custom_id).1 {
+        "" => Ok(serde_json::from_str("null")?),
+        state => Ok(serde_json::from_str(state)?),
+    }
+}
+
+/// Splits a custom ID into its namespace and state.
+pub(crate) fn split(custom_id: &str) -> (&str, &str) {
+    custom_id.split_once(SEPARATOR).unwrap_or((custom_id, ""))
+}
+
+/// Checks that a namespace can be told apart from the state after it.
+pub(super) const fn valid_namespace(namespace: &str) -> bool {
+    let bytes = namespace.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == SEPARATOR as u8 {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    !bytes.is_empty() && bytes.len() < MAX_CUSTOM_ID_LEN
+}
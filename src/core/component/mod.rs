@@ -0,0 +1,54 @@
+//! Buttons and select menus.
+//!
+//! Every component has a `custom_id`, which Discord sends back when it's used.
+//! Components handled by the `ComponentRouter` have IDs made of a namespace, which
+//! picks the `Component` that handles them, followed by the component's state:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct RemoveRole {
+//!     role: RoleId,
+//! }
+//!
+//! #[async_trait]
+//! impl Component for RemoveRole {
+//!     const NAMESPACE: &'static str = "remove-role";
+//!
+//!     async fn handle(self, ctx: &ComponentContext) -> CommandResult { ... }
+//! }
+//!
+//! // Later, when building a reply:
+//! b.custom_id(RemoveRole { role }.custom_id()?)
+//! ```
+
+use crate::core::command::CommandResult;
+use serde::{de::DeserializeOwned, Serialize};
+
+mod context;
+mod custom_id;
+mod router;
+
+pub use context::*;
+pub use custom_id::*;
+pub use router::*;
+
+/// A button or select menu handled by the `ComponentRouter`, registered with
+/// `Setup::add_component`. The implementing type is the state encoded in the
+/// custom ID, so it should be kept small, as IDs are limited to 100 characters.
+#[async_trait]
+pub trait Component: Serialize + DeserializeOwned + Send + 'static {
+    /// The prefix of the custom IDs of this component. Must be unique among the
+    /// components of a controller, which `Setup::add_component` checks, and can't
+    /// contain a colon.
+    const NAMESPACE: &'static str;
+
+    async fn handle(self, ctx: &ComponentContext) -> CommandResult;
+
+    /// Encodes the component into a custom ID, failing if it's too long.
+    fn custom_id(&self) -> Result<String, CustomIdError> {
+        custom_id::encode(Self::NAMESPACE, self)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,297 @@
+use super::{
+    custom_id::{self, valid_namespace},
+    Component, ComponentContext,
+};
+use crate::core::{
+    command::{CommandError, CommandFuture, CommandResult, Reply, DEFER_AFTER},
+    controller::Controller,
+    feature::{self, Feature},
+    setup::{ControllerCollection, Step, StepArgs},
+};
+use serenity::{
+    client::Context,
+    model::application::interaction::message_component::MessageComponentInteraction,
+    prelude::{RwLock, TypeMap, TypeMapKey},
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A component registered with `Setup::add_component`, with its type erased so
+/// that components of different types can be stored together.
+#[derive(Clone, Copy)]
+pub struct ComponentRoute {
+    /// The namespace of the component's custom IDs.
+    pub namespace: &'static str,
+
+    /// The feature the component belongs to, if it can be turned off.
+    pub feature: Option<&'static Feature>,
+
+    /// The name of the component's type, to tell components apart when they
+    /// have the same namespace.
+    component: &'static str,
+
+    exec: for<'a> fn(&'a ComponentContext) -> CommandFuture<'a>,
+}
+
+impl fmt::Debug for ComponentRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComponentRoute")
+            .field("namespace", &self.namespace)
+            .field("feature", &self.feature)
+            .field("component", &self.component)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ComponentRoute {
+    /// Creates the route for a component.
+    pub fn of<C: Component>() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = ValidNamespace::<C>::OK;
+
+        Self {
+            namespace: C::NAMESPACE,
+            feature: None,
+            component: std::any::type_name::<C>(),
+            exec: exec::<C>,
+        }
+    }
+
+    /// Makes the component belong to a feature, so it's refused while the
+    /// feature is off.
+    pub fn in_feature(self, feature: Option<&'static Feature>) -> Self {
+        Self { feature, ..self }
+    }
+
+    /// Decodes the state in the custom ID and runs the handler.
+    pub async fn exec(&self, ctx: &ComponentContext) -> CommandResult {
+        (self.exec)(ctx).await
+    }
+}
+
+fn exec<C: Component>(ctx: &ComponentContext) -> CommandFuture<'_> {
+    Box::pin(async move {
+        // State that no longer decodes was sent by an older version of the bot,
+        // so the user only needs to run the command again.
+        let state = custom_id::decode::<C>(ctx.custom_id()).map_err(|e| {
+            debug!(%e, "component state no longer decodes");
+            CommandError::user(ctx.tr("component-expired", &[]))
+        })?;
+
+        state.handle(ctx).await
+    })
+}
+
+/// Checks the namespace of a component at compile time, the same way as the names
+/// of context menu commands.
+struct ValidNamespace<C>(PhantomData<C>);
+
+impl<C: Component> ValidNamespace<C> {
+    const OK: () = assert!(
+        valid_namespace(C::NAMESPACE),
+        "component namespaces must be non-empty, short and not contain a colon"
+    );
+}
+
+/// Routes incoming component interactions to their handlers, by the namespace of
+/// their custom ID. Lives on the client data, where `ComponentStep` fills it in
+/// during setup.
+///
+/// The same component can be registered under several controllers, in which case
+/// the one matching the guild it was used in handles it.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentRouter {
+    routes: Arc<RwLock<Routes>>,
+}
+
+/// The components of each namespace, with the controllers they're registered under.
+type Routes = HashMap<&'static str, Vec<(Controller, ComponentRoute)>>;
+
+impl ComponentRouter {
+    /// Fetches the router from the client data, creating it if needed.
+    pub async fn get(data: &RwLock<TypeMap>) -> Self {
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(Self::default)
+            .clone()
+    }
+
+    async fn insert(&self, controller: Controller, route: ComponentRoute) {
+        self.routes
+            .write()
+            .await
+            .entry(route.namespace)
+            .or_default()
+            .push((controller, route));
+    }
+
+    /// Handles a component interaction, logging rather than returning any
+    /// failure, since there's nobody to return it to.
+    #[instrument(skip_all, fields(custom_id = %interaction.data.custom_id))]
+    pub async fn dispatch(&self, ctx: Context, interaction: MessageComponentInteraction) {
+        let namespace = custom_id::split(&interaction.data.custom_id).0;
+        let routes = self.routes.read().await.get(namespace).cloned();
+
+        // Components awaited by a command are handled by its collector instead.
+        let routes = match routes {
+            Some(routes) => routes,
+            None => return debug!("no handler for component"),
+        };
+
+        let route = routes
+            .iter()
+            .find(|(controller, _)| controller.matches_guild(interaction.guild_id))
+            .copied();
+
+        let (controller, route) = match route {
+            Some(route) => route,
+            None => {
+                warn!(guild = ?interaction.guild_id, "component used outside its guild");
+
+                let ctx = ComponentContext::new(ctx, interaction, routes[0].0);
+                let reply = Reply::text(ctx.tr("component-unavailable", &[])).ephemeral();
+
+                if let Err(e) = ctx.reply(reply).await {
+                    error!(%e, "failed to reject component");
+                }
+
+                return;
+            }
+        };
+
+        let ctx = ComponentContext::new(ctx, interaction, controller);
+        let result = match feature::check(&ctx, route.feature).await {
+            Ok(()) => run(&ctx, route).await,
+            Err(e) => Err(e),
+        };
+
+        // Handlers that only have side effects don't need to respond, so the
+        // interaction is acknowledged without changing the message.
+        let result = match result {
+            Ok(()) if ctx.has_responded().await => return,
+            Ok(()) => ctx.defer().await,
+            Err(e) => {
+                let reply = e.report(&ctx).await;
+                ctx.reply(reply).await
+            }
+        };
+
+        if let Err(e) = result {
+            error!(%e, "failed to acknowledge component");
+        }
+    }
+}
+
+/// Runs a component's handler, deferring the interaction if it's slow to respond.
+async fn run(ctx: &ComponentContext, route: ComponentRoute) -> CommandResult {
+    let handler = route.exec(ctx);
+    tokio::pin!(handler);
+
+    tokio::select! {
+        result = &mut handler => result,
+        _ = tokio::time::sleep(DEFER_AFTER) => {
+            ctx.auto_defer().await;
+            handler.await
+        }
+    }
+}
+
+impl TypeMapKey for ComponentRouter {
+    type Value = Self;
+}
+
+/// Two different components were registered under a controller with the same
+/// namespace, so only one of them could ever be routed to.
+#[derive(Debug)]
+pub struct NamespaceConflict {
+    pub namespace: &'static str,
+    pub components: [&'static str; 2],
+    pub controller: Controller,
+}
+
+impl fmt::Display for NamespaceConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [first, second] = self.components;
+        write!(
+            f,
+            "Components `{first}` and `{second}` under {} both have the namespace `{}`.",
+            self.controller, self.namespace
+        )
+    }
+}
+
+impl std::error::Error for NamespaceConflict {}
+
+impl From<NamespaceConflict> for serenity::Error {
+    /// Serenity can only return its own errors from setup, and a conflicting
+    /// registration is closest to being given invalid input.
+    fn from(error: NamespaceConflict) -> Self {
+        Self::Io(io::Error::new(io::ErrorKind::InvalidInput, error))
+    }
+}
+
+/// Setup step that records the components registered under a controller with the
+/// `ComponentRouter`.
+#[derive(Debug)]
+pub struct ComponentStep {
+    controller: Controller,
+    components: HashMap<&'static str, ComponentRoute>,
+
+    /// The first namespace conflict among the components, which is only
+    /// reported once the step executes, as appending can't fail.
+    conflict: Option<NamespaceConflict>,
+}
+
+#[async_trait]
+impl Step for ComponentStep {
+    type Operand = ComponentRoute;
+    type Collection = ControllerCollection<Self>;
+
+    const NAME: &'static str = "Component";
+
+    fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            components: HashMap::default(),
+            conflict: None,
+        }
+    }
+
+    fn operand_count(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Registering the same component again is fine, but another one with the
+    /// same namespace fails the step.
+    fn append(&mut self, component: ComponentRoute) {
+        if let Some(existing) = self.components.get(component.namespace) {
+            if existing.component != component.component && self.conflict.is_none() {
+                self.conflict = Some(NamespaceConflict {
+                    namespace: component.namespace,
+                    components: [existing.component, component.component],
+                    controller: self.controller,
+                });
+            }
+        }
+
+        self.components.insert(component.namespace, component);
+    }
+
+    async fn execute<'a>(self, args: StepArgs<'a, Self>) -> serenity::Result<()> {
+        if let Some(conflict) = self.conflict {
+            return Err(conflict.into());
+        }
+
+        let router = ComponentRouter::get(&args.client.data).await;
+
+        for route in self.components.into_values() {
+            router.insert(self.controller, route).await;
+        }
+
+        Ok(())
+    }
+}
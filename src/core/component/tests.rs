@@ -0,0 +1,107 @@
+use super::{
+    custom_id::{decode, encode, split, valid_namespace},
+    Component, ComponentContext, CustomIdError, MAX_CUSTOM_ID_LEN,
+};
+use crate::core::{
+    command::CommandResult,
+    controller::{GLOBAL, POKECOM},
+    setup::Setup,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct RemoveRole {
+    role: u64,
+    reason: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Dismiss;
+
+/// Another component with the namespace of `Dismiss`.
+#[derive(Serialize, Deserialize)]
+struct Close;
+
+#[async_trait]
+impl Component for Dismiss {
+    const NAMESPACE: &'static str = "dismiss";
+
+    async fn handle(self, _: &ComponentContext) -> CommandResult {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Component for Close {
+    const NAMESPACE: &'static str = "dismiss";
+
+    async fn handle(self, _: &ComponentContext) -> CommandResult {
+        Ok(())
+    }
+}
+
+#[test]
+fn state_survives_a_round_trip() {
+    let state = RemoveRole {
+        role: 42,
+        reason: "spam: again".into(),
+    };
+
+    let custom_id = encode("remove-role", &state).unwrap();
+
+    assert_eq!(split(&custom_id).0, "remove-role");
+    assert_eq!(decode::<RemoveRole>(&custom_id).unwrap(), state);
+}
+
+#[test]
+fn components_without_state_get_just_their_namespace() {
+    let custom_id = encode("dismiss", &Dismiss).unwrap();
+
+    assert_eq!(custom_id, "dismiss");
+    assert_eq!(split(&custom_id), ("dismiss", ""));
+    assert_eq!(decode::<Dismiss>(&custom_id).unwrap(), Dismiss);
+}
+
+#[test]
+fn custom_ids_are_limited_to_100_characters() {
+    // The namespace, the separator and the JSON around the reason take 35.
+    let state = |len| RemoveRole {
+        role: 42,
+        reason: "é".repeat(len),
+    };
+
+    let custom_id = encode("remove-role", &state(65)).unwrap();
+    assert_eq!(custom_id.chars().count(), MAX_CUSTOM_ID_LEN);
+
+    assert!(matches!(
+        encode("remove-role", &state(66)),
+        Err(CustomIdError::TooLong {
+            namespace: "remove-role",
+            len: 101,
+        })
+    ));
+}
+
+#[test]
+fn state_from_another_version_fails_to_decode() {
+    assert!(matches!(
+        decode::<RemoveRole>("remove-role:{\"role\":42}"),
+        Err(CustomIdError::Json(_))
+    ));
+}
+
+#[test]
+fn namespaces_must_be_short_and_colon_free() {
+    assert!(valid_namespace("remove-role"));
+    assert!(!valid_namespace(""));
+    assert!(!valid_namespace("remove:role"));
+    assert!(!valid_namespace(&"a".repeat(MAX_CUSTOM_ID_LEN)));
+}
+
+#[test]
+fn the_same_component_can_be_registered_again() {
+    Setup::new()
+        .add_component::<Dismiss>(POKECOM)
+        .add_component::<Dismiss>(POKECOM)
+        .add_component::<Close>(GLOBAL);
+}
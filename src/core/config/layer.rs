@@ -0,0 +1,259 @@
+use super::{LogFormat, Secret};
+use crate::core::controller::Controller;
+use clap::Args;
+use serde::Deserialize;
+use serenity::model::id::{ChannelId, RoleId};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The flags that override every other source of configuration.
+#[derive(Debug, Clone, Default, Args)]
+pub struct ConfigArgs {
+    /// The config file to read, instead of `porygon.toml`.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// A file holding the Discord token, such as a Docker secret.
+    #[arg(long, value_name = "FILE")]
+    pub token_file: Option<PathBuf>,
+
+    /// The ID of the bot's Discord application.
+    #[arg(long, value_name = "ID")]
+    pub application_id: Option<u64>,
+
+    /// Where the bot keeps its data.
+    #[arg(long, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Which logs are shown, in `RUST_LOG` syntax, such as `info,porygon=debug`.
+    #[arg(long, value_name = "FILTER")]
+    pub log: Option<String>,
+
+    /// How logs are shown: `tree` or `compact`.
+    #[arg(long, value_name = "FORMAT")]
+    pub log_format: Option<LogFormat>,
+}
+
+/// The configuration given by a single source, where anything can be missing.
+/// Layers are merged in order of precedence before the result is validated.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Layer {
+    #[serde(default)]
+    discord: DiscordLayer,
+
+    staging: Option<bool>,
+
+    #[serde(default)]
+    logging: LoggingLayer,
+
+    #[serde(default)]
+    storage: StorageLayer,
+
+    /// By controller key. Keys are checked once the layer is read, since serde
+    /// can't report which key was wrong.
+    #[serde(default)]
+    controllers: HashMap<String, ControllerLayer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DiscordLayer {
+    token: Option<Secret<String>>,
+    token_file: Option<PathBuf>,
+    application_id: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LoggingLayer {
+    filter: Option<String>,
+    format: Option<LogFormat>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StorageLayer {
+    data_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ControllerLayer {
+    staff_role: Option<RoleId>,
+    error_channel: Option<ChannelId>,
+}
+
+/// The resolved values of every layer, before they're validated.
+#[derive(Debug, Default)]
+pub(super) struct Merged {
+    pub token: Option<Secret<String>>,
+    pub token_file: Option<PathBuf>,
+    pub application_id: Option<u64>,
+    pub staging: Option<bool>,
+    pub filter: Option<String>,
+    pub format: Option<LogFormat>,
+    pub data_dir: Option<PathBuf>,
+    pub controllers: HashMap<Controller, (Option<RoleId>, Option<ChannelId>)>,
+}
+
+impl Layer {
+    /// Reads a TOML config file, such as:
+    ///
+    /// ```toml
+    /// [discord]
+    /// token_file = "/run/secrets/discord_token"
+    /// application_id = 964389981516881920
+    ///
+    /// [controllers.POKECOM]
+    /// staff_role = 157984424116445184
+    /// ```
+    pub fn from_toml(path: &Path, text: &str, problems: &mut Vec<String>) -> Self {
+        let layer = match toml::from_str::<Self>(text) {
+            Ok(layer) => layer,
+            Err(e) => {
+                problems.push(format!("`{}` is invalid: {e}", path.display()));
+                return Self::default();
+            }
+        };
+
+        for key in layer.controllers.keys() {
+            if Controller::from_key(key).is_none() {
+                problems.push(format!(
+                    "`controllers.{key}` in `{}` isn't a controller.",
+                    path.display(),
+                ));
+            }
+        }
+
+        layer
+    }
+
+    /// Reads the environment variables of the process, or of a test:
+    ///
+    /// - `DISCORD_TOKEN`, `DISCORD_TOKEN_FILE` and `APPLICATION_ID`.
+    /// - `PORYGON_STAGING`.
+    /// - `RUST_LOG` and `PORYGON_LOG_FORMAT`.
+    /// - `PORYGON_DATA_DIR`.
+    /// - `STAFF_ROLE_<KEY>` and `ERROR_CHANNEL_<KEY>` for each controller key,
+    ///   such as `STAFF_ROLE_POKECOM`.
+    pub fn from_env(
+        vars: impl IntoIterator<Item = (String, String)>,
+        problems: &mut Vec<String>,
+    ) -> Self {
+        let mut layer = Self::default();
+
+        for (var, value) in vars {
+            match var.as_str() {
+                "DISCORD_TOKEN" => layer.discord.token = Some(Secret::new(value)),
+                "DISCORD_TOKEN_FILE" => layer.discord.token_file = Some(value.into()),
+                "APPLICATION_ID" => layer.discord.application_id = parse(&var, &value, problems),
+                "PORYGON_STAGING" => layer.staging = parse(&var, &value, problems),
+                "RUST_LOG" => layer.logging.filter = Some(value),
+                "PORYGON_LOG_FORMAT" => layer.logging.format = parse(&var, &value, problems),
+                "PORYGON_DATA_DIR" => layer.storage.data_dir = Some(value.into()),
+                _ => layer.controller_var(&var, &value, problems),
+            }
+        }
+
+        layer
+    }
+
+    /// Reads the flags the bot was started with.
+    pub fn from_args(args: &ConfigArgs) -> Self {
+        Self {
+            discord: DiscordLayer {
+                token: None,
+                token_file: args.token_file.clone(),
+                application_id: args.application_id,
+            },
+            staging: None,
+            logging: LoggingLayer {
+                filter: args.log.clone(),
+                format: args.log_format,
+            },
+            storage: StorageLayer {
+                data_dir: args.data_dir.clone(),
+            },
+            controllers: HashMap::new(),
+        }
+    }
+
+    /// Reads a `STAFF_ROLE_<KEY>` or `ERROR_CHANNEL_<KEY>` variable, ignoring
+    /// every other variable.
+    fn controller_var(&mut self, var: &str, value: &str, problems: &mut Vec<String>) {
+        let (key, staff) = match (
+            var.strip_prefix("STAFF_ROLE_"),
+            var.strip_prefix("ERROR_CHANNEL_"),
+        ) {
+            (Some(key), _) => (key, true),
+            (_, Some(key)) => (key, false),
+            _ => return,
+        };
+
+        if Controller::from_key(key).is_none() {
+            return problems.push(format!("`{var}` doesn't name a controller."));
+        }
+
+        let controller = self.controllers.entry(key.to_string()).or_default();
+
+        match staff {
+            true => controller.staff_role = parse::<u64>(var, value, problems).map(RoleId),
+            false => controller.error_channel = parse::<u64>(var, value, problems).map(ChannelId),
+        }
+    }
+
+    /// Merges layers, with each layer overriding those before it.
+    pub(super) fn merge(layers: impl IntoIterator<Item = Self>) -> Merged {
+        let mut merged = Merged::default();
+
+        for layer in layers {
+            let Self {
+                discord,
+                staging,
+                logging,
+                storage,
+                controllers,
+            } = layer;
+
+            // A token and a token file from the same layer can't both apply, so a
+            // layer giving either replaces both.
+            if discord.token.is_some() || discord.token_file.is_some() {
+                merged.token = discord.token;
+                merged.token_file = discord.token_file;
+            }
+
+            merged.application_id = discord.application_id.or(merged.application_id);
+            merged.staging = staging.or(merged.staging);
+            merged.filter = logging.filter.or(merged.filter);
+            merged.format = logging.format.or(merged.format);
+            merged.data_dir = storage.data_dir.or(merged.data_dir);
+
+            for (key, layer) in controllers {
+                let controller = match Controller::from_key(&key) {
+                    Some(controller) => controller,
+                    None => continue,
+                };
+
+                let (role, channel) = merged.controllers.entry(controller).or_default();
+                *role = layer.staff_role.or(*role);
+                *channel = layer.error_channel.or(*channel);
+            }
+        }
+
+        merged
+    }
+}
+
+/// Parses the value of an environment variable, noting the problem if it's
+/// invalid.
+fn parse<T: FromStr>(var: &str, value: &str, problems: &mut Vec<String>) -> Option<T> {
+    match value.trim().parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            problems.push(format!("`{var}` is invalid: `{value}`."));
+            None
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! The configuration of the bot.
+//!
+//! Read once at startup from, in increasing order of precedence:
+//!
+//! 1. A TOML file, given with `--config` or `PORYGON_CONFIG`, or `porygon.toml`
+//!    if it exists.
+//! 2. A `.env` file, if it exists.
+//! 3. Environment variables.
+//! 4. Command line flags. See `ConfigArgs`.
+//!
+//! Everything is validated up front, so a bad configuration stops the bot before
+//! it connects, with every problem listed at once. See `ConfigError`.
+//!
+//! Once loaded, the configuration is put on the client data, where anything that
+//! needs it fetches it with `Config::get`.
+
+use crate::core::controller::Controller;
+use dotenv::dotenv;
+use serde::Deserialize;
+use serenity::{
+    model::id::{ChannelId, RoleId},
+    prelude::{RwLock, TypeMap, TypeMapKey},
+};
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing_subscriber::EnvFilter;
+
+mod error;
+mod layer;
+mod secret;
+
+pub use error::*;
+pub use layer::*;
+pub use secret::*;
+
+/// The config file read when none is given, if it exists.
+const DEFAULT_FILE: &str = "porygon.toml";
+
+/// The configuration of the bot, validated and with defaults filled in.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub discord: DiscordConfig,
+
+    /// Whether the bot runs on the staging server. Must match the `staging`
+    /// feature it was built with, which is what actually moves the controllers;
+    /// this only catches a production config being used with a staging build, or
+    /// the other way around.
+    pub staging: bool,
+
+    pub logging: LoggingConfig,
+    pub storage: StorageConfig,
+
+    /// The configuration of each controller's guild. Controllers that aren't
+    /// configured are left out.
+    pub controllers: HashMap<Controller, ControllerConfig>,
+}
+
+/// How the bot connects to Discord.
+#[derive(Debug, Clone, Default)]
+pub struct DiscordConfig {
+    /// Read from `token_file` if one is given, so it can be a Docker secret.
+    pub token: Secret<String>,
+    pub application_id: u64,
+}
+
+/// What the bot logs, and how.
+#[derive(Debug, Clone)]
+pub struct LoggingConfig {
+    /// Which logs are shown, in `RUST_LOG` syntax.
+    pub filter: String,
+    pub format: LogFormat,
+}
+
+/// Where the bot keeps its data.
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    /// The directory of `core::data` and `core::storage`.
+    pub data_dir: PathBuf,
+}
+
+/// The configuration of a controller's guild.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ControllerConfig {
+    /// The role allowed to use staff commands. See `Command::staff`.
+    pub staff_role: Option<RoleId>,
+
+    /// The channel internal errors are posted to.
+    pub error_channel: Option<ChannelId>,
+}
+
+/// How logs are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Nested under the spans they happened in, which is easier to follow while
+    /// developing.
+    Tree,
+
+    /// One line per event, which suits log collectors better.
+    Compact,
+}
+
+impl Config {
+    /// Loads the configuration from every source, failing with every problem
+    /// found if it's invalid.
+    pub fn load(args: &ConfigArgs) -> Result<Self, ConfigError> {
+        dotenv().ok();
+
+        let mut problems = Vec::new();
+        let file = match Self::file(args) {
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(text) => Layer::from_toml(&path, &text, &mut problems),
+                Err(e) => {
+                    problems.push(format!("`{}` can't be read: {e}", path.display()));
+                    Layer::default()
+                }
+            },
+            None => Layer::default(),
+        };
+
+        let env = Layer::from_env(env::vars(), &mut problems);
+        let args = Layer::from_args(args);
+
+        Self::resolve([file, env, args], problems)
+    }
+
+    /// Merges layers, with each layer overriding those before it, and validates
+    /// the result. Problems already found while reading the layers are reported
+    /// along with any found here.
+    pub fn resolve(
+        layers: impl IntoIterator<Item = Layer>,
+        mut problems: Vec<String>,
+    ) -> Result<Self, ConfigError> {
+        let merged = Layer::merge(layers);
+        let default = Self::default();
+
+        let token = match (merged.token_file, merged.token) {
+            (Some(path), _) => match fs::read_to_string(&path) {
+                Ok(token) => Some(token.trim().to_string()),
+                Err(e) => {
+                    problems.push(format!(
+                        "The token file `{}` can't be read: {e}",
+                        path.display()
+                    ));
+                    None
+                }
+            },
+            (None, token) => token.map(|token| token.expose().trim().to_string()),
+        };
+
+        let token = match token {
+            Some(token) if token.is_empty() => {
+                problems.push("The Discord token is empty.".to_string());
+                String::new()
+            }
+            Some(token) => token,
+            None => {
+                problems.push(
+                    "No Discord token was given. Set `DISCORD_TOKEN` or `DISCORD_TOKEN_FILE`."
+                        .to_string(),
+                );
+                String::new()
+            }
+        };
+
+        let application_id = match merged.application_id {
+            Some(0) | None => {
+                problems.push("No application ID was given. Set `APPLICATION_ID`.".to_string());
+                0
+            }
+            Some(id) => id,
+        };
+
+        let staging = merged.staging.unwrap_or(default.staging);
+
+        if staging != default.staging {
+            problems.push(format!(
+                "`staging` is {staging}, but this build has the `staging` feature {}.",
+                if default.staging { "on" } else { "off" },
+            ));
+        }
+
+        let filter = merged.filter.unwrap_or(default.logging.filter);
+
+        if let Err(e) = EnvFilter::try_new(&filter) {
+            problems.push(format!("The log filter `{filter}` is invalid: {e}"));
+        }
+
+        if !problems.is_empty() {
+            return Err(ConfigError::new(problems));
+        }
+
+        let controllers = merged
+            .controllers
+            .into_iter()
+            .map(|(controller, (staff_role, error_channel))| {
+                let config = ControllerConfig {
+                    staff_role,
+                    error_channel,
+                };
+
+                (controller, config)
+            })
+            .collect();
+
+        Ok(Self {
+            discord: DiscordConfig {
+                token: Secret::new(token),
+                application_id,
+            },
+            staging,
+            logging: LoggingConfig {
+                filter,
+                format: merged.format.unwrap_or(default.logging.format),
+            },
+            storage: StorageConfig {
+                data_dir: merged.data_dir.unwrap_or(default.storage.data_dir),
+            },
+            controllers,
+        })
+    }
+
+    /// The config file to read, if any.
+    fn file(args: &ConfigArgs) -> Option<PathBuf> {
+        args.config
+            .clone()
+            .or_else(|| env::var_os("PORYGON_CONFIG").map(Into::into))
+            .or_else(|| Some(PathBuf::from(DEFAULT_FILE)).filter(|path| path.exists()))
+    }
+
+    /// Fetches the configuration from the client data. Panics if it isn't there,
+    /// since the client is always built with one, and quietly falling back to the
+    /// defaults would use the wrong data directory and staff roles.
+    pub async fn get(data: &RwLock<TypeMap>) -> Arc<Self> {
+        data.read()
+            .await
+            .get::<Self>()
+            .cloned()
+            .expect("the client was built without a `Config`")
+    }
+
+    /// The configuration of a controller's guild, which is empty if it isn't
+    /// configured.
+    pub fn controller(&self, controller: Controller) -> ControllerConfig {
+        self.controllers
+            .get(&controller)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl TypeMapKey for Config {
+    type Value = Arc<Self>;
+}
+
+impl Default for Config {
+    /// The defaults of everything optional. The Discord token and application ID
+    /// are left empty, so this is never valid to connect with.
+    fn default() -> Self {
+        Self {
+            discord: DiscordConfig::default(),
+            staging: cfg!(feature = "staging"),
+            logging: LoggingConfig {
+                filter: if cfg!(feature = "debug") {
+                    "debug"
+                } else {
+                    "info"
+                }
+                .to_string(),
+                format: LogFormat::Tree,
+            },
+            storage: StorageConfig {
+                data_dir: PathBuf::from("data"),
+            },
+            controllers: HashMap::new(),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tree" => Ok(Self::Tree),
+            "compact" => Ok(Self::Compact),
+            _ => Err(format!(
+                "`{s}` isn't a log format, expected `tree` or `compact`"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tree => f.write_str("tree"),
+            Self::Compact => f.write_str("compact"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
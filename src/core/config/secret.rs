@@ -0,0 +1,27 @@
+use serde::Deserialize;
+use std::fmt;
+
+/// A value that must never end up in logs, such as the Discord token. Its `Debug`
+/// output is redacted, and it has no `Display`, so reading it takes a deliberate
+/// call to `Secret::expose`.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wraps a value, hiding it from `Debug`.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// The secret value itself.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
@@ -0,0 +1,178 @@
+use super::{Config, ConfigArgs, ControllerConfig, Layer, LogFormat, Secret};
+use crate::core::controller::{POKECOM, POKECOM_STAFF};
+use serenity::model::id::{ChannelId, RoleId};
+use std::{env, fs, path::Path};
+
+fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter()
+        .map(|(var, value)| (var.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Resolves a config file, environment and flags, like `Config::load`.
+fn resolve(toml: &str, env: &[(&str, &str)], args: ConfigArgs) -> Result<Config, Vec<String>> {
+    let mut problems = Vec::new();
+    let file = Layer::from_toml(Path::new("porygon.toml"), toml, &mut problems);
+    let env = Layer::from_env(vars(env), &mut problems);
+    let args = Layer::from_args(&args);
+
+    Config::resolve([file, env, args], problems).map_err(|e| e.problems().to_vec())
+}
+
+const MINIMAL: &[(&str, &str)] = &[("DISCORD_TOKEN", "token"), ("APPLICATION_ID", "1")];
+
+#[test]
+fn later_sources_override_earlier_ones() {
+    let toml = r#"
+        [discord]
+        token = "from-file"
+        application_id = 1
+
+        [logging]
+        filter = "warn"
+        format = "compact"
+
+        [storage]
+        data_dir = "from-file"
+
+        [controllers.POKECOM]
+        staff_role = 10
+        error_channel = 20
+    "#;
+
+    let env = [
+        ("DISCORD_TOKEN", "from-env"),
+        ("PORYGON_DATA_DIR", "from-env"),
+        ("STAFF_ROLE_POKECOM", "11"),
+    ];
+
+    let args = ConfigArgs {
+        data_dir: Some("from-args".into()),
+        ..ConfigArgs::default()
+    };
+
+    let config = resolve(toml, &env, args).unwrap();
+
+    assert_eq!(config.discord.token.expose(), "from-env");
+    assert_eq!(config.discord.application_id, 1);
+    assert_eq!(config.logging.filter, "warn");
+    assert_eq!(config.logging.format, LogFormat::Compact);
+    assert_eq!(config.storage.data_dir, Path::new("from-args"));
+
+    // Each value of a controller is overridden on its own.
+    assert_eq!(
+        config.controller(POKECOM),
+        ControllerConfig {
+            staff_role: Some(RoleId(11)),
+            error_channel: Some(ChannelId(20)),
+        },
+    );
+    assert_eq!(
+        config.controller(POKECOM_STAFF),
+        ControllerConfig::default()
+    );
+}
+
+#[test]
+fn defaults_fill_in_what_is_missing() {
+    let config = resolve("", MINIMAL, ConfigArgs::default()).unwrap();
+    let default = Config::default();
+
+    assert_eq!(config.staging, default.staging);
+    assert_eq!(config.logging.filter, default.logging.filter);
+    assert_eq!(config.logging.format, LogFormat::Tree);
+    assert_eq!(config.storage.data_dir, Path::new("data"));
+    assert!(config.controllers.is_empty());
+}
+
+#[test]
+fn every_problem_is_reported_at_once() {
+    let staging = if cfg!(feature = "staging") {
+        "false"
+    } else {
+        "true"
+    };
+
+    let env = [
+        ("APPLICATION_ID", "porygon"),
+        ("PORYGON_STAGING", staging),
+        ("PORYGON_LOG_FORMAT", "fancy"),
+        ("RUST_LOG", "info,porygon=loud"),
+        ("STAFF_ROLE_POKEMON", "1"),
+        ("ERROR_CHANNEL_POKECOM", "general"),
+        ("UNRELATED", "ignored"),
+    ];
+
+    let problems = resolve("", &env, ConfigArgs::default()).unwrap_err();
+
+    assert_eq!(problems.len(), 8, "{problems:#?}");
+    assert!(problems.iter().any(|p| p.contains("`APPLICATION_ID`")));
+    assert!(problems.iter().any(|p| p.contains("`PORYGON_LOG_FORMAT`")));
+    assert!(problems.iter().any(|p| p.contains("`STAFF_ROLE_POKEMON`")));
+    assert!(problems
+        .iter()
+        .any(|p| p.contains("`ERROR_CHANNEL_POKECOM`")));
+    assert!(problems.iter().any(|p| p.contains("No Discord token")));
+    assert!(problems.iter().any(|p| p.contains("No application ID")));
+    assert!(problems.iter().any(|p| p.contains("`staging`")));
+    assert!(problems.iter().any(|p| p.contains("log filter")));
+}
+
+#[test]
+fn config_files_are_checked_strictly() {
+    let problems = resolve("[discord]\ntokn = \"typo\"", MINIMAL, ConfigArgs::default());
+    assert!(problems.unwrap_err()[0].contains("`porygon.toml` is invalid"));
+
+    let problems = resolve(
+        "[controllers.POKEMON]\nstaff_role = 1",
+        MINIMAL,
+        ConfigArgs::default(),
+    );
+    assert_eq!(
+        problems.unwrap_err(),
+        ["`controllers.POKEMON` in `porygon.toml` isn't a controller."],
+    );
+}
+
+#[test]
+fn the_token_can_be_read_from_a_file() {
+    let path = env::temp_dir().join(format!("porygon-test-token-{}", rand::random::<u64>()));
+    fs::write(&path, "from-secret\n").unwrap();
+
+    // The token file wins over a token from an earlier source.
+    let toml = format!(
+        "[discord]\ntoken_file = {:?}\napplication_id = 1",
+        path.display().to_string(),
+    );
+    let config = resolve(&toml, &[], ConfigArgs::default()).unwrap();
+    assert_eq!(config.discord.token.expose(), "from-secret");
+
+    let args = ConfigArgs {
+        token_file: Some(path.clone()),
+        ..ConfigArgs::default()
+    };
+    let config = resolve("", MINIMAL, args).unwrap();
+    assert_eq!(config.discord.token.expose(), "from-secret");
+
+    fs::remove_file(&path).unwrap();
+
+    let args = ConfigArgs {
+        token_file: Some(path),
+        ..ConfigArgs::default()
+    };
+    let problems = resolve("", MINIMAL, args).unwrap_err();
+    assert!(problems[0].starts_with("The token file"));
+}
+
+#[test]
+fn secrets_are_redacted() {
+    let config = resolve(
+        "",
+        &[("DISCORD_TOKEN", "hunter2"), ("APPLICATION_ID", "1")],
+        ConfigArgs::default(),
+    )
+    .unwrap();
+
+    assert!(!format!("{config:?}").contains("hunter2"));
+    assert_eq!(format!("{:?}", Secret::new("hunter2")), "[redacted]");
+}
@@ -1,6 +1,6 @@
 use super::upload::UploadInterface;
+use crate::core::api::DiscordApi;
 use crate::core::guild::nickname::{GuildNickname, PROPERTIES_STAGING};
-use serenity::http::client::Http;
 use serenity::model::{guild::PartialGuild, id::GuildId};
 use std::fmt;
 
@@ -18,10 +18,19 @@ pub enum ControllerBrain {
 }
 
 impl ControllerBrain {
+    /// Returns the stable key of the target. This is the same in and out of
+    /// `staging`, since it identifies the intended target rather than the real one.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Global => "GLOBAL",
+            Self::Guild(nick) => nick.key(),
+        }
+    }
+
     /// Returns whether Porygon can connect to the target. In staging, this is
     /// always assumed to be true.
     #[cfg(feature = "staging")]
-    pub async fn is_connected(&self, _: &Http) -> bool {
+    pub async fn is_connected(&self, _: &dyn DiscordApi) -> bool {
         true
     }
 
@@ -31,10 +40,10 @@ impl ControllerBrain {
     /// This is used to determine which setup steps should be run, as setup steps for
     /// un-loaded guilds are not needed.
     #[cfg(not(feature = "staging"))]
-    pub async fn is_connected(&self, http: &Http) -> bool {
+    pub async fn is_connected(&self, api: &dyn DiscordApi) -> bool {
         match self {
             Self::Global => true,
-            Self::Guild(_) => self.try_get_guild(http).await.is_some(),
+            Self::Guild(_) => self.try_get_guild(api).await.is_some(),
         }
     }
 
@@ -62,9 +71,9 @@ impl ControllerBrain {
     /// Fetches the `staging` guild. Despite being an `Option` return to be compatible
     /// with the contract in production, this method can never fail.
     #[cfg(feature = "staging")]
-    pub async fn try_get_guild(&self, http: &Http) -> Option<PartialGuild> {
+    pub async fn try_get_guild(&self, api: &dyn DiscordApi) -> Option<PartialGuild> {
         let guild = PROPERTIES_STAGING
-            .get(http)
+            .get(api)
             .await
             .expect("Staging guild *must* be connected in staging mode!");
 
@@ -74,10 +83,10 @@ impl ControllerBrain {
     /// Fetches the guild for this target. Will be `None` either if called on `Global`, or
     /// if the guild is not connected.
     #[cfg(not(feature = "staging"))]
-    pub async fn try_get_guild(&self, http: &Http) -> Option<PartialGuild> {
+    pub async fn try_get_guild(&self, api: &dyn DiscordApi) -> Option<PartialGuild> {
         match self {
             Self::Global => None,
-            Self::Guild(nick) => nick.get(http).await.ok(),
+            Self::Guild(nick) => nick.get(api).await.ok(),
         }
     }
 
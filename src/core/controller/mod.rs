@@ -1,6 +1,6 @@
-use crate::core::guild::nickname::GuildNickname;
+use crate::core::{api::DiscordApi, guild::nickname::GuildNickname};
 use brain::ControllerBrain;
-use serenity::http::client::Http;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serenity::model::{guild::PartialGuild, id::GuildId};
 use std::fmt;
 
@@ -17,10 +17,25 @@ pub struct Controller {
 }
 
 impl Controller {
+    /// Every controller, in declaration order.
+    pub const ALL: [Controller; 4] = [GLOBAL, POKECOM, POKECOM_STAFF, DUCK_COMMUNISM];
+
+    /// Returns the stable key of the controller, which is the name of its constant,
+    /// such as `POKECOM`. Unlike `Display`, this is never affected by `staging`, so
+    /// it's what gets persisted when a controller has to be stored.
+    pub fn key(&self) -> &'static str {
+        self.brain.key()
+    }
+
+    /// Looks up a controller by its key. See `Controller::key`.
+    pub fn from_key(key: &str) -> Option<Controller> {
+        Self::ALL.into_iter().find(|c| c.key() == key)
+    }
+
     /// Returns whether Porygon can connect to the controller's guild, if the
     /// controller describes a guild, or `true` if the controller is `GLOBAL`.
-    pub async fn is_connected(&self, http: &Http) -> bool {
-        self.brain.is_connected(http).await
+    pub async fn is_connected(&self, api: &dyn DiscordApi) -> bool {
+        self.brain.is_connected(api).await
     }
 
     /// Tests whether a hypothetical guild ID (or lack of one) matches a controller.
@@ -32,8 +47,8 @@ impl Controller {
 
     /// Tries to look up the guild for this controller. Will be `None` either if called
     /// on `GLOBAL` or if the guild is not connected.
-    pub async fn try_get_guild(&self, http: &Http) -> Option<PartialGuild> {
-        self.brain.try_get_guild(http).await
+    pub async fn try_get_guild(&self, api: &dyn DiscordApi) -> Option<PartialGuild> {
+        self.brain.try_get_guild(api).await
     }
 
     /// Returns the low-level upload interface to allow editing and fetching commands
@@ -49,6 +64,19 @@ impl fmt::Display for Controller {
     }
 }
 
+impl Serialize for Controller {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.key())
+    }
+}
+
+impl<'de> Deserialize<'de> for Controller {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        Self::from_key(&key).ok_or_else(|| de::Error::custom(format!("unknown controller `{key}`")))
+    }
+}
+
 impl fmt::Debug for Controller {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("Controller").field(&self.brain).finish()
@@ -1,17 +1,17 @@
+use crate::core::api::DiscordApi;
 use serde_json::value::Value;
-use serenity::http::client::Http;
 use serenity::model::{
+    application::command::Command as ApplicationCommand,
     id::{CommandId, GuildId},
-    interactions::application_command::ApplicationCommand,
 };
 use serenity::Result;
 
 /// A low-level interface for uploading commands. Controller brains
 /// provide `upload_iface` that will get you one of these.
 ///
-/// This type is extremely low-level and wraps Serenity's own low-level
-/// facilities for command uploading. There are higher-level versions of
-/// this in Serenity, but none of them do exactly what I want.
+/// This type is extremely low-level and wraps the command operations of
+/// `DiscordApi`, picking the global or guild variant of each.
+#[derive(Debug)]
 pub struct UploadInterface(UploadInterfaceInner);
 
 /// Inner type to prevent constructing an upload iface outside the parent scope.
@@ -34,40 +34,44 @@ impl UploadInterface {
         Self(Global)
     }
 
+    /// The guild commands are uploaded to, or `None` for global commands.
+    pub fn guild_id(&self) -> Option<GuildId> {
+        match self.0 {
+            Global => None,
+            Guild(guild_id) => Some(guild_id),
+        }
+    }
+
     /// Uploads the given command data. If `command_id` is provided, it will be edited.
     /// Otherwise, it will be uploaded as a new command.
     pub async fn upload(
         &self,
-        http: &Http,
+        api: &dyn DiscordApi,
         data: &Value,
         command_id: Option<CommandId>,
     ) -> Result<ApplicationCommand> {
-        match (&self.0, command_id) {
-            // Editing a global command.
-            (Global, Some(cid)) => http.edit_global_application_command(cid.0, data).await,
-
-            // Creating a new global command.
-            (Global, None) => http.create_global_application_command(data).await,
-
-            // Editing a guild command.
-            (Guild(gid), Some(cid)) => {
-                http.edit_guild_application_command(gid.0, cid.0, data)
-                    .await
-            }
-
-            // Creating a new guild command.
-            (Guild(gid), None) => http.create_guild_application_command(gid.0, data).await,
+        match command_id {
+            Some(command_id) => api.edit_command(self.guild_id(), command_id, data).await,
+            None => api.create_command(self.guild_id(), data).await,
         }
     }
 
     /// Gets a command with a specific ID.
-    pub async fn get(&self, http: &Http, command_id: CommandId) -> Result<ApplicationCommand> {
-        match &self.0 {
-            Global => http.get_global_application_command(command_id.0).await,
-            Guild(guild_id) => {
-                http.get_guild_application_command(guild_id.0, command_id.0)
-                    .await
-            }
-        }
+    pub async fn get(
+        &self,
+        api: &dyn DiscordApi,
+        command_id: CommandId,
+    ) -> Result<ApplicationCommand> {
+        api.get_command(self.guild_id(), command_id).await
+    }
+
+    /// Lists every command uploaded to the target.
+    pub async fn list(&self, api: &dyn DiscordApi) -> Result<Vec<ApplicationCommand>> {
+        api.get_commands(self.guild_id()).await
+    }
+
+    /// Deletes a command with a specific ID.
+    pub async fn delete(&self, api: &dyn DiscordApi, command_id: CommandId) -> Result<()> {
+        api.delete_command(self.guild_id(), command_id).await
     }
 }
@@ -0,0 +1,92 @@
+//! The on-disk data directory.
+//!
+//! Small pieces of state that need to survive a restart are
+//! stored here as JSON files, one per system. The directory is
+//! set with `storage.data_dir` in the `Config`, and passed to
+//! each function here.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Loads and deserializes a JSON file from the data directory. Returns `None`
+/// if the file does not exist yet.
+pub fn load<T: DeserializeOwned>(dir: &Path, file: &str) -> io::Result<Option<T>> {
+    let bytes = match fs::read(dir.join(file)) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    serde_json::from_slice(&bytes).map(Some).map_err(Into::into)
+}
+
+/// Serializes a value and saves it to a JSON file in the data directory,
+/// creating the directory if needed.
+///
+/// The value is written to a temporary file which is then moved into place,
+/// so a crash midway through never leaves a half-written file behind.
+pub fn save<T: Serialize + ?Sized>(dir: &Path, file: &str, value: &T) -> io::Result<()> {
+    let path = dir.join(file);
+    let tmp = path.with_extension("tmp");
+
+    fs::create_dir_all(dir)?;
+
+    fs::write(&tmp, serde_json::to_vec_pretty(value)?)?;
+    fs::rename(tmp, path)
+}
+
+/// Serializes a value and appends it as a single line to a JSON Lines file in
+/// the data directory, creating the file and directory if needed. Used for
+/// append-only logs.
+pub fn append<T: Serialize + ?Sized>(dir: &Path, file: &str, value: &T) -> io::Result<()> {
+    let path = dir.join(file);
+
+    fs::create_dir_all(dir)?;
+
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&line)
+}
+
+/// Loads and deserializes every line of a JSON Lines file in the data directory.
+/// Returns nothing if the file does not exist yet.
+///
+/// A last line that can't be parsed is dropped rather than failing the load,
+/// since that's what a crash in the middle of `append` leaves behind.
+pub fn load_lines<T: DeserializeOwned>(dir: &Path, file: &str) -> io::Result<Vec<T>> {
+    let text = match fs::read_to_string(dir.join(file)) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let lines = text.lines().collect::<Vec<_>>();
+    let mut values = Vec::with_capacity(lines.len());
+
+    for (index, line) in lines.iter().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(value) => values.push(value),
+            Err(e) if index + 1 == lines.len() => {
+                warn!(%e, file, "dropping a partially written line");
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(values)
+}
+
+/// Removes a file from the data directory, if it exists.
+pub fn remove(dir: &Path, file: &str) -> io::Result<()> {
+    match fs::remove_file(dir.join(file)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -1 +1,35 @@
+use crate::core::{command::Dispatcher, component::ComponentRouter, modal::ModalRouter};
+use serenity::{
+    client::{Context, EventHandler},
+    model::application::interaction::Interaction,
+};
 
+/// The single serenity event handler, which forwards each event to whichever
+/// part of `core` is responsible for it.
+#[derive(Debug)]
+pub struct EventProxy;
+
+#[async_trait]
+impl EventHandler for EventProxy {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::ApplicationCommand(interaction) => {
+                let dispatcher = Dispatcher::get(&ctx.data).await;
+                dispatcher.dispatch(ctx, interaction).await
+            }
+            Interaction::Autocomplete(interaction) => {
+                let dispatcher = Dispatcher::get(&ctx.data).await;
+                dispatcher.autocomplete(ctx, interaction).await
+            }
+            Interaction::MessageComponent(interaction) => {
+                let router = ComponentRouter::get(&ctx.data).await;
+                router.dispatch(ctx, interaction).await
+            }
+            Interaction::ModalSubmit(interaction) => {
+                let router = ModalRouter::get(&ctx.data).await;
+                router.dispatch(ctx, interaction).await
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use super::{FeatureError, FeatureToggles};
+use crate::core::command::{
+    add_fields, AutocompleteContext, CommandContext, CommandError, CommandResult, Handler, Reply,
+    Suggestion, MAX_FIELDS,
+};
+use porygon_macros::Command;
+use serenity::utils::Colour;
+
+/// Turn the features of this server on and off.
+#[derive(Command)]
+#[command(
+    staff,
+    default_permissions(manage_guild),
+    example = "/features enable feature: starboard"
+)]
+pub enum Features {
+    List(List),
+    Enable(Enable),
+    Disable(Disable),
+}
+
+/// List every feature and whether it's on.
+#[derive(Command)]
+pub struct List;
+
+/// Turn a feature on.
+#[derive(Command)]
+pub struct Enable {
+    /// The feature to turn on.
+    #[option(autocomplete = "feature_names")]
+    feature: String,
+}
+
+/// Turn a feature off.
+#[derive(Command)]
+pub struct Disable {
+    /// The feature to turn off.
+    #[option(autocomplete = "feature_names")]
+    feature: String,
+}
+
+#[async_trait]
+impl Handler for List {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let features = FeatureToggles::get(ctx.data())
+            .await
+            .registered(ctx.controller());
+
+        let fields = features
+            .into_iter()
+            .map(|(feature, enabled)| {
+                let state = match enabled {
+                    true => ctx.tr("features-on", &[]),
+                    false => ctx.tr("features-off", &[]),
+                };
+
+                (
+                    format!("`{}` — {state}", feature.name),
+                    feature.description.to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let reply = Reply::default().embed(|e| {
+            add_fields(ctx, e, fields, MAX_FIELDS);
+            e.title(ctx.tr("features-title", &[]))
+                .colour(Colour::BLURPLE)
+        });
+
+        ctx.reply(reply.ephemeral()).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Enable {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        toggle(ctx, &self.feature, true).await
+    }
+}
+
+#[async_trait]
+impl Handler for Disable {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        toggle(ctx, &self.feature, false).await
+    }
+}
+
+async fn toggle(ctx: &CommandContext, name: &str, enabled: bool) -> CommandResult {
+    let feature = FeatureToggles::get(ctx.data())
+        .await
+        .set(ctx.controller(), name, enabled, ctx.user().id)
+        .await
+        .map_err(|e| CommandError::refuse(e, |e| matches!(e, FeatureError::Storage(_))))?;
+
+    let key = match enabled {
+        true => "features-enabled",
+        false => "features-disabled",
+    };
+
+    ctx.reply(ctx.tr(key, &[("name", feature.name.into())]))
+        .await?;
+    Ok(())
+}
+
+/// Suggests the features registered here whose names contain what's been typed.
+async fn feature_names(ctx: &AutocompleteContext) -> Vec<Suggestion> {
+    let features = FeatureToggles::get(ctx.data())
+        .await
+        .registered(ctx.controller());
+
+    ctx.suggest_names(features.iter().map(|(feature, _)| feature.name))
+}
@@ -0,0 +1,309 @@
+//! Features that staff can turn on and off per guild, without a deploy.
+//!
+//! A feature is declared as a `Feature` constant, and everything its installer
+//! registers with `Setup::add_feature` belongs to it. Whether it's on in each
+//! controller is stored in the controller's database and cached on
+//! `FeatureToggles`. While a feature is off in a controller, the dispatchers
+//! refuse its commands, components and modals there, and `/help` doesn't list
+//! its commands.
+//!
+//! Tasks and jobs are gated per controller too: a task's runs are skipped while
+//! its feature is off, and a job stays queued until its feature is turned back
+//! on. Initializers are only gated once, at setup time, so one whose feature is
+//! off then doesn't run at all, and turning the feature on later won't run it
+//! until the next restart.
+//!
+//! Registering a feature under a controller also uploads `/features` there,
+//! which staff use to see and flip the features of their guild.
+
+use crate::core::{
+    command::{CommandError, CommandResult, Invocation},
+    controller::Controller,
+    setup::{ControllerCollection, Step, StepArgs},
+    storage::{Storage, StorageError},
+};
+use serenity::{
+    model::id::UserId,
+    prelude::{RwLock, TypeMap, TypeMapKey},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, RwLock as SyncRwLock};
+
+mod command;
+mod repository;
+
+pub use command::*;
+pub use repository::*;
+
+/// A part of the bot that can be turned on and off per guild:
+///
+/// ```ignore
+/// pub const STARBOARD: Feature =
+///     Feature::new("starboard", "Reposts popular messages.").disabled_by_default();
+/// ```
+#[derive(Debug)]
+pub struct Feature {
+    /// The name of the feature, as picked in `/features`, in kebab case.
+    pub name: &'static str,
+
+    /// What the feature does, shown by `/features list`.
+    pub description: &'static str,
+
+    /// Whether the feature is on in guilds where staff haven't toggled it.
+    pub enabled_by_default: bool,
+}
+
+impl Feature {
+    /// Creates a feature that's on until staff turn it off.
+    pub const fn new(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            description,
+            enabled_by_default: true,
+        }
+    }
+
+    /// Makes the feature off until staff turn it on, such as to roll it out to
+    /// one guild at a time.
+    pub const fn disabled_by_default(self) -> Self {
+        Self {
+            enabled_by_default: false,
+            ..self
+        }
+    }
+}
+
+/// Whether each feature is on in every controller it's registered under. Lives
+/// on the client data, where `FeatureStep` fills it in during setup.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureToggles {
+    controllers: Arc<SyncRwLock<HashMap<Controller, ControllerFeatures>>>,
+}
+
+/// The features of a single controller.
+#[derive(Debug)]
+struct ControllerFeatures {
+    repository: FeatureRepository,
+    features: BTreeMap<&'static str, &'static Feature>,
+
+    /// The features that have been toggled, by name.
+    toggles: HashMap<&'static str, bool>,
+}
+
+impl FeatureToggles {
+    /// Fetches the toggles from the client data, creating them if needed.
+    pub async fn get(data: &RwLock<TypeMap>) -> Self {
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(Self::default)
+            .clone()
+    }
+
+    /// Whether a feature is on in a controller, which is its default if it
+    /// hasn't been toggled, or if it isn't registered under the controller.
+    pub fn is_enabled(&self, controller: Controller, feature: &Feature) -> bool {
+        let controllers = self.controllers.read().unwrap_or_else(|e| e.into_inner());
+
+        controllers
+            .get(&controller)
+            .and_then(|features| features.toggles.get(feature.name))
+            .copied()
+            .unwrap_or(feature.enabled_by_default)
+    }
+
+    /// The features registered under a controller and whether they're on, sorted
+    /// by name.
+    pub fn registered(&self, controller: Controller) -> Vec<(&'static Feature, bool)> {
+        let features = self
+            .controllers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&controller)
+            .map(|features| features.features.values().copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        features
+            .into_iter()
+            .map(|feature| (feature, self.is_enabled(controller, feature)))
+            .collect()
+    }
+
+    /// Turns a feature on or off in a controller, returning the feature.
+    pub async fn set(
+        &self,
+        controller: Controller,
+        name: &str,
+        enabled: bool,
+        user: UserId,
+    ) -> Result<&'static Feature, FeatureError> {
+        let (repository, feature) = self.find(controller, name)?;
+
+        repository.set(feature.name, enabled, user).await?;
+
+        let mut controllers = self.controllers.write().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(features) = controllers.get_mut(&controller) {
+            features.toggles.insert(feature.name, enabled);
+        }
+
+        info!(name = feature.name, %controller, enabled, %user, "toggled feature");
+        Ok(feature)
+    }
+
+    fn find(
+        &self,
+        controller: Controller,
+        name: &str,
+    ) -> Result<(FeatureRepository, &'static Feature), FeatureError> {
+        let controllers = self.controllers.read().unwrap_or_else(|e| e.into_inner());
+
+        controllers
+            .get(&controller)
+            .and_then(|features| {
+                let feature = features.features.get(name)?;
+                Some((features.repository.clone(), *feature))
+            })
+            .ok_or_else(|| FeatureError::Unknown(name.to_string()))
+    }
+
+    fn insert(&self, controller: Controller, features: ControllerFeatures) {
+        let mut controllers = self.controllers.write().unwrap_or_else(|e| e.into_inner());
+        controllers.insert(controller, features);
+    }
+}
+
+impl TypeMapKey for FeatureToggles {
+    type Value = Self;
+}
+
+/// Whether something registered under a controller is usable, which it always
+/// is unless it belongs to a feature that's off there.
+pub async fn allows(
+    data: &RwLock<TypeMap>,
+    controller: Controller,
+    feature: Option<&'static Feature>,
+) -> bool {
+    match feature {
+        Some(feature) => FeatureToggles::get(data)
+            .await
+            .is_enabled(controller, feature),
+        None => true,
+    }
+}
+
+/// Refuses an interaction if it belongs to a feature that's off in the
+/// controller it was registered under.
+pub(crate) async fn check(
+    source: &impl Invocation,
+    feature: Option<&'static Feature>,
+) -> CommandResult {
+    match (
+        allows(source.data(), source.controller(), feature).await,
+        feature,
+    ) {
+        (false, Some(feature)) => {
+            let args = [("name", feature.name.into())];
+            Err(CommandError::user(source.tr("feature-disabled", &args)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Reasons a feature can't be toggled.
+#[derive(Debug)]
+pub enum FeatureError {
+    /// No feature with the name is registered under the controller.
+    Unknown(String),
+
+    Storage(StorageError),
+}
+
+impl fmt::Display for FeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(name) => write!(f, "There's no feature called `{name}`."),
+            Self::Storage(e) => write!(f, "Failed to store the feature: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FeatureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Storage(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<StorageError> for FeatureError {
+    fn from(error: StorageError) -> Self {
+        Self::Storage(error)
+    }
+}
+
+/// Setup step that loads which features registered under a controller have
+/// been toggled onto `FeatureToggles`.
+#[derive(Debug)]
+pub struct FeatureStep {
+    controller: Controller,
+    features: BTreeMap<&'static str, &'static Feature>,
+}
+
+#[async_trait]
+impl Step for FeatureStep {
+    type Operand = &'static Feature;
+    type Collection = ControllerCollection<Self>;
+
+    const NAME: &'static str = "Feature";
+
+    fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            features: BTreeMap::default(),
+        }
+    }
+
+    fn operand_count(&self) -> usize {
+        self.features.len()
+    }
+
+    fn append(&mut self, feature: &'static Feature) {
+        self.features.insert(feature.name, feature);
+    }
+
+    async fn execute<'a>(self, args: StepArgs<'a, Self>) -> serenity::Result<()> {
+        let storage = Storage::get(&args.client.data).await;
+        let repository = storage.repository::<FeatureRepository>(self.controller)?;
+
+        // Toggles of features that have since been removed are kept in storage
+        // but ignored, so they come back if the feature does.
+        let toggles = repository
+            .all()
+            .await
+            .map_err(serenity::Error::from)?
+            .into_iter()
+            .filter_map(|(name, enabled)| {
+                let feature = self.features.get(name.as_str())?;
+                Some((feature.name, enabled))
+            })
+            .collect();
+
+        let features = ControllerFeatures {
+            repository,
+            features: self.features,
+            toggles,
+        };
+
+        FeatureToggles::get(&args.client.data)
+            .await
+            .insert(self.controller, features);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
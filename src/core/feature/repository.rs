@@ -0,0 +1,59 @@
+use crate::core::storage::{Database, Migration, Repository, StorageError};
+use chrono::Utc;
+use rusqlite::params;
+use serenity::model::id::UserId;
+
+/// Which features staff have turned on or off in a controller, overriding
+/// their defaults.
+#[derive(Debug, Clone)]
+pub struct FeatureRepository(Database);
+
+impl Repository for FeatureRepository {
+    const MIGRATIONS: &'static [Migration] = &[Migration {
+        name: "features_create",
+        sql: "CREATE TABLE features (
+                  name TEXT PRIMARY KEY,
+                  enabled INTEGER NOT NULL,
+                  updated_by INTEGER NOT NULL,
+                  updated_at TEXT NOT NULL
+              );",
+    }];
+
+    fn new(database: Database) -> Self {
+        Self(database)
+    }
+}
+
+impl FeatureRepository {
+    /// Whether each feature that's been toggled is on, by name.
+    pub async fn all(&self) -> Result<Vec<(String, bool)>, StorageError> {
+        self.0
+            .call(|c| {
+                c.prepare("SELECT name, enabled FROM features")?
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect()
+            })
+            .await
+    }
+
+    /// Stores whether a feature is on, replacing any previous toggle.
+    pub async fn set(&self, name: &str, enabled: bool, user: UserId) -> Result<(), StorageError> {
+        let name = name.to_string();
+
+        self.0
+            .call(move |c| {
+                c.execute(
+                    "INSERT INTO features (name, enabled, updated_by, updated_at)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (name) DO UPDATE SET
+                         enabled = excluded.enabled,
+                         updated_by = excluded.updated_by,
+                         updated_at = excluded.updated_at",
+                    params![name, enabled, user.0, Utc::now().to_rfc3339()],
+                )
+            })
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use super::{Feature, FeatureRepository, FeatureToggles};
+use crate::core::{
+    command::{CommandContext, CommandResult, Handler},
+    controller::POKECOM,
+    init::{Init, InitArgs},
+    storage::Storage,
+    testing::{FakeGuild, FakeMember, Harness},
+};
+use porygon_macros::{init, Command};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+const STAFF: u64 = 10;
+const MOD: u64 = 100;
+
+const STARBOARD: Feature =
+    Feature::new("starboard", "Reposts popular messages.").disabled_by_default();
+
+const GREETER: Feature = Feature::new("greeter", "Greets people.");
+
+/// Stars a message.
+#[derive(Command)]
+struct Star;
+
+/// Greets you.
+#[derive(Command)]
+struct Greet;
+
+#[async_trait]
+impl Handler for Star {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.reply("Starred!").await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Greet {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.reply("Hello!").await?;
+        Ok(())
+    }
+}
+
+static STARBOARD_INITS: AtomicUsize = AtomicUsize::new(0);
+static GREETER_INITS: AtomicUsize = AtomicUsize::new(0);
+
+#[init]
+fn load_starboard(_: &InitArgs<'_>) {
+    STARBOARD_INITS.fetch_add(1, SeqCst);
+}
+
+#[init]
+fn load_greeter(_: &InitArgs<'_>) {
+    GREETER_INITS.fetch_add(1, SeqCst);
+}
+
+async fn harness() -> Harness {
+    let guild = FakeGuild::of(POKECOM)
+        .staff_role(STAFF)
+        .member(FakeMember::new(MOD, "mod").role(STAFF));
+
+    Harness::builder()
+        .guild(guild)
+        .start_with(|setup| {
+            setup
+                .add_feature(&STARBOARD, |setup| {
+                    setup
+                        .add_command::<Star>(POKECOM)
+                        .add_init(POKECOM, load_starboard)
+                })
+                .add_feature(&GREETER, |setup| {
+                    setup
+                        .add_command::<Greet>(POKECOM)
+                        .add_init(POKECOM, load_greeter)
+                })
+        })
+        .await
+}
+
+#[tokio::test]
+async fn refuses_commands_of_features_that_are_off() {
+    let harness = harness().await;
+    let outcome = harness.user(MOD).command("/star", json!({})).await;
+
+    assert!(outcome.reply().is_ephemeral());
+    assert_eq!(
+        outcome.reply().description(),
+        "`starboard` is turned off here."
+    );
+
+    let outcome = harness.user(MOD).command("/greet", json!({})).await;
+    assert_eq!(outcome.reply().content(), "Hello!");
+}
+
+#[tokio::test]
+async fn turns_features_on_and_stores_it() {
+    let harness = harness().await;
+    let moderator = harness.user(MOD);
+    let outcome = moderator
+        .command("/features enable", json!({ "feature": "starboard" }))
+        .await;
+
+    assert_eq!(outcome.reply().content(), "Turned `starboard` on.");
+    assert_eq!(
+        moderator
+            .command("/star", json!({}))
+            .await
+            .reply()
+            .content(),
+        "Starred!"
+    );
+
+    let stored = Storage::get(harness.data())
+        .await
+        .repository::<FeatureRepository>(POKECOM)
+        .unwrap()
+        .all()
+        .await
+        .unwrap();
+
+    assert_eq!(stored, [("starboard".to_string(), true)]);
+    assert!(FeatureToggles::get(harness.data())
+        .await
+        .is_enabled(POKECOM, &STARBOARD));
+}
+
+#[tokio::test]
+async fn hides_commands_of_features_that_are_off_from_help() {
+    let harness = harness().await;
+    let moderator = harness.user(MOD);
+
+    let help = moderator.command("/help", json!({})).await;
+    assert!(help.reply().description().contains("`/greet`"));
+    assert!(!help.reply().description().contains("`/star`"));
+
+    moderator
+        .command("/features disable", json!({ "feature": "greeter" }))
+        .await;
+
+    let help = moderator.command("/help", json!({})).await;
+    assert!(!help.reply().description().contains("`/greet`"));
+    assert!(help.reply().description().contains("`/help`"));
+}
+
+#[tokio::test]
+async fn lists_features_and_whether_they_are_on() {
+    let harness = harness().await;
+    let outcome = harness.user(MOD).command("/features list", json!({})).await;
+    let fields = &outcome.reply().embeds()[0]["fields"];
+
+    assert!(outcome.reply().is_ephemeral());
+    assert_eq!(fields[0]["name"], "`greeter` — on");
+    assert_eq!(fields[0]["value"], "Greets people.");
+    assert_eq!(fields[1]["name"], "`starboard` — off");
+}
+
+#[tokio::test]
+async fn refuses_unknown_features() {
+    let harness = harness().await;
+    let outcome = harness
+        .user(MOD)
+        .command("/features enable", json!({ "feature": "nope" }))
+        .await;
+
+    assert_eq!(
+        outcome.reply().description(),
+        "There's no feature called `nope`."
+    );
+}
+
+#[tokio::test]
+async fn skips_initializers_of_features_that_are_off() {
+    let _harness = harness().await;
+
+    assert_eq!(STARBOARD_INITS.load(SeqCst), 0);
+    assert!(GREETER_INITS.load(SeqCst) > 0);
+}
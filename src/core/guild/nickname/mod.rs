@@ -1,4 +1,4 @@
-use serenity::http::client::Http;
+use crate::core::api::DiscordApi;
 use serenity::model::{guild::PartialGuild, id::GuildId};
 use std::fmt;
 
@@ -22,8 +22,17 @@ impl GuildNickname {
         self.properties().name()
     }
 
-    pub async fn get(&self, http: &Http) -> serenity::Result<PartialGuild> {
-        self.properties().get(http).await
+    /// The key of the controller for this guild. See `Controller::key`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Pokecom => "POKECOM",
+            Self::PokecomStaff => "POKECOM_STAFF",
+            Self::DuckCommunism => "DUCK_COMMUNISM",
+        }
+    }
+
+    pub async fn get(&self, api: &dyn DiscordApi) -> serenity::Result<PartialGuild> {
+        self.properties().get(api).await
     }
 
     fn properties(&self) -> properties::GuildNicknameProperties {
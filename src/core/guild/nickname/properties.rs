@@ -1,8 +1,5 @@
-use serenity::http::client::Http;
-use serenity::model::{
-    guild::{Guild, PartialGuild},
-    id::GuildId,
-};
+use crate::core::api::DiscordApi;
+use serenity::model::{guild::PartialGuild, id::GuildId};
 use std::fmt;
 
 /// The properties of Porygon's member guilds, which are always hardcoded into the bot
@@ -57,8 +54,8 @@ impl GuildNicknameProperties {
         self.name
     }
 
-    pub async fn get(&self, http: &Http) -> serenity::Result<PartialGuild> {
-        Guild::get(http, self.id).await
+    pub async fn get(&self, api: &dyn DiscordApi) -> serenity::Result<PartialGuild> {
+        api.get_guild(self.id).await
     }
 }
 
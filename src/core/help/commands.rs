@@ -0,0 +1,278 @@
+use super::{docs, markdown, CommandDoc};
+use crate::core::{
+    command::{
+        add_fields, field_value, AutocompleteContext, CommandContext, CommandError, CommandResult,
+        Dispatcher, Handler, Invocation, Reply, Requirement, Suggestion, MAX_FIELDS,
+    },
+    component::{Component, ComponentContext, CustomIdError},
+};
+use porygon_macros::Command;
+use serde::{Deserialize, Serialize};
+use serenity::{
+    model::application::{command::CommandType, component::ButtonStyle},
+    utils::Colour,
+};
+
+/// How many commands are listed on each page.
+const PAGE_SIZE: usize = 10;
+
+/// Lists the commands you can use here, or explains one of them.
+#[derive(Command)]
+#[command(key = "help", example = "/help", example = "/help command: activity")]
+pub struct Help {
+    /// The command to explain.
+    #[option(autocomplete = "command_names")]
+    command: Option<String>,
+}
+
+/// Exports the reference of every command as Markdown, for the wiki.
+#[derive(Command)]
+#[command(name = "help-export", owner, default_permissions(administrator))]
+pub struct HelpExport;
+
+/// The buttons paging through the list of commands, holding the page they go to.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HelpPage(usize);
+
+#[async_trait]
+impl Handler for Help {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let docs = docs::available(ctx).await?;
+
+        let reply = match self.command {
+            Some(name) => {
+                let name = name.trim().trim_start_matches('/');
+                let doc = docs
+                    .iter()
+                    .find(|doc| doc.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        CommandError::user(ctx.tr("help-unknown", &[("name", name.into())]))
+                    })?;
+
+                details(ctx, doc)
+            }
+            None => page(ctx, &docs, 0)?,
+        };
+
+        ctx.reply(reply.ephemeral()).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for HelpExport {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let docs = docs::all(ctx).await;
+        let count = ctx.tr("help-exported", &[("count", docs.len().into())]);
+        let reply = Reply::text(count)
+            .attachment("commands.md", markdown::render(&docs))
+            .ephemeral();
+
+        ctx.reply(reply).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Component for HelpPage {
+    const NAMESPACE: &'static str = "help-page";
+
+    async fn handle(self, ctx: &ComponentContext) -> CommandResult {
+        let docs = docs::available(ctx).await?;
+        ctx.update(page(ctx, &docs, self.0)?).await?;
+        Ok(())
+    }
+}
+
+/// Suggests the slash commands available where the option is being typed.
+async fn command_names(ctx: &AutocompleteContext) -> Vec<Suggestion> {
+    let mut names = Dispatcher::get(ctx.data())
+        .await
+        .routes()
+        .await
+        .into_iter()
+        .filter(|(controller, route)| {
+            route.kind == CommandType::ChatInput && controller.matches_guild(ctx.guild_id())
+        })
+        .map(|(_, route)| route.name)
+        .collect::<Vec<_>>();
+
+    names.sort_unstable();
+    names.dedup();
+
+    ctx.suggest_names(names)
+}
+
+/// A page of the list of commands, with buttons to the pages around it if
+/// there's more than one.
+fn page(
+    source: &impl Invocation,
+    docs: &[CommandDoc],
+    page: usize,
+) -> Result<Reply, CustomIdError> {
+    let pages = docs.len().div_ceil(PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let lines = docs
+        .iter()
+        .skip(page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .map(|doc| format!("`{}` — {}", doc.title(), summary(source, doc)))
+        .collect::<Vec<_>>();
+
+    let description = match lines.is_empty() {
+        true => source.tr("help-empty", &[]),
+        false => lines.join("\n"),
+    };
+
+    let footer = source.tr(
+        "help-footer",
+        &[("page", (page + 1).into()), ("pages", pages.into())],
+    );
+
+    let reply = Reply::default().embed(|e| {
+        e.title(source.tr("help-title", &[]))
+            .description(description)
+            .footer(|f| f.text(footer))
+            .colour(Colour::BLURPLE)
+    });
+
+    if pages == 1 {
+        return Ok(reply);
+    }
+
+    // The buttons on the first and last pages are disabled rather than removed,
+    // so they don't jump around. Their IDs still differ, as Discord requires.
+    let previous = HelpPage(page.saturating_sub(1)).custom_id()?;
+    let next = HelpPage((page + 1).min(pages - 1)).custom_id()?;
+    let (previous_label, next_label) =
+        (source.tr("help-previous", &[]), source.tr("help-next", &[]));
+
+    Ok(reply.components(|c| {
+        c.create_action_row(|row| {
+            row.create_button(|b| {
+                b.custom_id(previous)
+                    .label(previous_label)
+                    .style(ButtonStyle::Secondary)
+                    .disabled(page == 0)
+            })
+            .create_button(|b| {
+                b.custom_id(next)
+                    .label(next_label)
+                    .style(ButtonStyle::Secondary)
+                    .disabled(page + 1 == pages)
+            })
+        })
+    }))
+}
+
+/// The description of a command, or what kind of command it is for context menu
+/// commands, which don't have one.
+fn summary(source: &impl Invocation, doc: &CommandDoc) -> String {
+    match doc.is_slash() {
+        true => doc.description.clone(),
+        false => source.tr("help-context-menu", &[("kind", kind(doc).into())]),
+    }
+}
+
+fn kind(doc: &CommandDoc) -> &'static str {
+    match doc.kind {
+        CommandType::User => "user",
+        _ => "message",
+    }
+}
+
+/// Explains a single command: how to use each of its subcommands and their
+/// options, examples, and who can use it.
+fn details(source: &impl Invocation, doc: &CommandDoc) -> Reply {
+    Reply::default().embed(|e| {
+        e.title(doc.title())
+            .description(summary(source, doc))
+            .colour(Colour::BLURPLE);
+
+        let usages = match doc.is_slash() {
+            true => doc.usages(),
+            false => Vec::new(),
+        };
+
+        let mut fields = Vec::new();
+
+        for usage in usages {
+            let mut lines = Vec::new();
+
+            if usage.name() != doc.title() {
+                lines.push(usage.description.to_string());
+            }
+
+            for option in usage.options {
+                lines.push(format!("`{}` — {}", option.name, option.description));
+            }
+
+            if lines.is_empty() {
+                lines.push(source.tr("help-no-options", &[]));
+            }
+
+            fields.push((format!("`{usage}`"), lines.join("\n")));
+        }
+
+        // Leaves room for the examples and requirements.
+        add_fields(source, e, fields, MAX_FIELDS - 2);
+
+        if !doc.examples.is_empty() {
+            let examples = doc
+                .examples
+                .iter()
+                .map(|example| format!("`{example}`"))
+                .collect::<Vec<_>>();
+
+            e.field(
+                source.tr("help-examples", &[]),
+                field_value(examples.join("\n")),
+                false,
+            );
+        }
+
+        let requirements = requirements(source, doc);
+
+        if !requirements.is_empty() {
+            e.field(
+                source.tr("help-requires", &[]),
+                field_value(requirements.join("\n")),
+                false,
+            );
+        }
+
+        e
+    })
+}
+
+/// Describes the default permissions and requirements of a command in the
+/// user's locale, apart from permission names, which Discord doesn't translate
+/// either.
+fn requirements(source: &impl Invocation, doc: &CommandDoc) -> Vec<String> {
+    let mut requirements = doc
+        .permissions
+        .map(|permissions| permissions.get_permission_names())
+        .unwrap_or_default()
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    for requirement in doc.requires {
+        requirements.push(match requirement {
+            Requirement::Staff => source.tr("help-staff", &[]),
+            Requirement::Owner => source.tr("help-owner", &[]),
+            Requirement::Channels(channels) => {
+                let channels = channels
+                    .iter()
+                    .map(|channel| format!("<#{channel}>"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                source.tr("help-channels", &[("channels", channels.into())])
+            }
+        });
+    }
+
+    requirements
+}
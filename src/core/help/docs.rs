@@ -0,0 +1,248 @@
+use crate::core::{
+    command::{self, CommandError, CommandRoute, Dispatcher, Invocation, Requirement},
+    controller::Controller,
+    feature::{self, Feature},
+};
+use serde::Deserialize;
+use serenity::model::{
+    application::command::{CommandOptionType, CommandType},
+    Permissions,
+};
+use std::fmt;
+
+/// The documentation of an uploaded command. Read back from the JSON it was
+/// uploaded with, so that it always matches what Discord shows.
+#[derive(Debug, Clone)]
+pub struct CommandDoc {
+    /// The controller the command was registered under.
+    pub controller: Controller,
+
+    /// Whether this is a slash command or a context menu command.
+    pub kind: CommandType,
+
+    pub name: String,
+
+    /// Empty for context menu commands, which Discord doesn't describe.
+    pub description: String,
+
+    /// The options of the command, or its subcommands if it has any.
+    pub options: Vec<OptionDoc>,
+
+    /// The permissions members need to see the command by default.
+    pub permissions: Option<Permissions>,
+
+    /// The rules users must meet to use the command.
+    pub requires: &'static [Requirement],
+
+    /// The feature the command belongs to, if it can be turned off.
+    pub feature: Option<&'static Feature>,
+
+    pub examples: Vec<&'static str>,
+}
+
+/// The documentation of an option, subcommand or subcommand group.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OptionDoc {
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(rename = "type")]
+    kind: u8,
+
+    #[serde(default)]
+    pub required: bool,
+
+    /// The options of a subcommand, or the subcommands of a group.
+    #[serde(default)]
+    pub options: Vec<OptionDoc>,
+}
+
+/// The fields of the uploaded JSON of a command that are documented.
+#[derive(Deserialize)]
+struct Data {
+    name: String,
+
+    #[serde(default)]
+    description: String,
+
+    #[serde(default)]
+    options: Vec<OptionDoc>,
+
+    #[serde(default)]
+    default_member_permissions: Option<String>,
+}
+
+/// One way of using a slash command: the command itself if it has plain
+/// options, or each of its subcommands otherwise.
+#[derive(Debug, Clone, Copy)]
+pub struct Usage<'a> {
+    /// The names leading to the subcommand, such as `["role", "add"]`.
+    path: [&'a str; 3],
+    depth: usize,
+
+    pub description: &'a str,
+    pub options: &'a [OptionDoc],
+}
+
+impl CommandDoc {
+    /// Documents a registered command.
+    pub fn new(controller: Controller, route: CommandRoute) -> Self {
+        let data = serde_json::from_value::<Data>(route.data())
+            .expect("commands are uploaded with valid JSON");
+
+        let permissions = data
+            .default_member_permissions
+            .and_then(|bits| bits.parse().ok())
+            .map(Permissions::from_bits_truncate);
+
+        Self {
+            controller,
+            kind: route.kind,
+            name: data.name,
+            description: data.description,
+            options: data.options,
+            permissions,
+            requires: route.requires,
+            feature: route.feature,
+            examples: route.examples(),
+        }
+    }
+
+    /// Whether this is a slash command, rather than a context menu command.
+    pub fn is_slash(&self) -> bool {
+        self.kind == CommandType::ChatInput
+    }
+
+    /// How the command is referred to, such as `/role` or `Warn`.
+    pub fn title(&self) -> String {
+        match self.is_slash() {
+            true => format!("/{}", self.name),
+            false => self.name.clone(),
+        }
+    }
+
+    /// Every way of using the command, with subcommands flattened.
+    pub fn usages(&self) -> Vec<Usage<'_>> {
+        let root = Usage {
+            path: [&self.name, "", ""],
+            depth: 1,
+            description: &self.description,
+            options: &self.options,
+        };
+
+        let mut usages = Vec::new();
+        root.flatten(&mut usages);
+        usages
+    }
+
+    /// Whether a member has the default permissions of the command. Members
+    /// with none, such as users in DMs, only see commands that need none.
+    fn permits(&self, permissions: Option<Permissions>) -> bool {
+        match (self.permissions, permissions) {
+            (None, _) => true,
+            (Some(required), _) if required.is_empty() => true,
+            (Some(required), Some(permissions)) => {
+                permissions.administrator() || permissions.contains(required)
+            }
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl OptionDoc {
+    fn is_subcommand(&self) -> bool {
+        self.kind == CommandOptionType::SubCommand as u8
+            || self.kind == CommandOptionType::SubCommandGroup as u8
+    }
+}
+
+impl fmt::Display for OptionDoc {
+    /// Formats the option as it appears in a usage, such as `<role>` if it's
+    /// required or `[reason]` if it isn't.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.required {
+            true => write!(f, "<{}>", self.name),
+            false => write!(f, "[{}]", self.name),
+        }
+    }
+}
+
+impl<'a> Usage<'a> {
+    /// Adds the usages below this one, or this one if it has no subcommands.
+    fn flatten(self, usages: &mut Vec<Usage<'a>>) {
+        if !self.options.iter().any(OptionDoc::is_subcommand) {
+            return usages.push(self);
+        }
+
+        for subcommand in self.options.iter().filter(|o| o.is_subcommand()) {
+            let mut path = self.path;
+            path[self.depth] = &subcommand.name;
+
+            Usage {
+                path,
+                depth: self.depth + 1,
+                description: &subcommand.description,
+                options: &subcommand.options,
+            }
+            .flatten(usages);
+        }
+    }
+
+    /// The full name of the subcommand, such as `/role add`.
+    pub fn name(&self) -> String {
+        format!("/{}", self.path[..self.depth].join(" "))
+    }
+}
+
+impl fmt::Display for Usage<'_> {
+    /// Formats the usage as it's typed, such as `/role add <role> [reason]`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())?;
+
+        for option in self.options {
+            write!(f, " {option}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Documents every uploaded command, sorted by controller, then with slash
+/// commands before context menu commands, then by name.
+pub async fn all(source: &impl Invocation) -> Vec<CommandDoc> {
+    let routes = Dispatcher::get(source.data()).await.routes().await;
+    let mut docs = routes
+        .into_iter()
+        .map(|(controller, route)| CommandDoc::new(controller, route))
+        .collect::<Vec<_>>();
+
+    docs.sort_by_key(|doc| (doc.controller.key(), !doc.is_slash(), doc.name.clone()));
+
+    docs
+}
+
+/// Documents the commands the user of an interaction can use where it was used,
+/// sorted with slash commands first, then by name.
+pub async fn available(source: &impl Invocation) -> Result<Vec<CommandDoc>, CommandError> {
+    let permissions = source.member().and_then(|member| member.permissions);
+    let mut docs = Vec::new();
+
+    for doc in all(source).await {
+        if !doc.controller.matches_guild(source.guild_id()) || !doc.permits(permissions) {
+            continue;
+        }
+
+        if !feature::allows(source.data(), doc.controller, doc.feature).await {
+            continue;
+        }
+
+        if command::allows(source, doc.controller, doc.requires).await? {
+            docs.push(doc);
+        }
+    }
+
+    docs.sort_by_key(|doc| (!doc.is_slash(), doc.name.clone()));
+    Ok(docs)
+}
@@ -0,0 +1,119 @@
+use super::{CommandDoc, Usage};
+use crate::core::command::Requirement;
+use serenity::model::application::command::CommandType;
+use std::fmt::Write;
+
+/// Renders a reference of commands as Markdown, with a section per controller.
+/// Written in English, since it's meant for the wiki rather than for users.
+pub fn render(docs: &[CommandDoc]) -> String {
+    let mut out = String::from("# Commands\n");
+    let mut controller = None;
+
+    for doc in docs {
+        if controller != Some(doc.controller.key()) {
+            controller = Some(doc.controller.key());
+            let _ = write!(out, "\n## {}\n", doc.controller);
+        }
+
+        command(&mut out, doc);
+    }
+
+    out
+}
+
+fn command(out: &mut String, doc: &CommandDoc) {
+    let _ = write!(out, "\n### `{}`\n\n", doc.title());
+
+    match doc.is_slash() {
+        true => {
+            let _ = writeln!(out, "{}", doc.description);
+        }
+        false => {
+            let _ = writeln!(out, "*{} context menu command.*", kind(doc));
+        }
+    }
+
+    if doc.is_slash() {
+        let usages = doc.usages();
+        let nested = usages.iter().any(|usage| usage.name() != doc.title());
+
+        for usage in usages {
+            self::usage(out, usage, nested);
+        }
+    }
+
+    if !doc.examples.is_empty() {
+        out.push_str("\n**Examples:**\n\n");
+
+        for example in &doc.examples {
+            let _ = writeln!(out, "- `{example}`");
+        }
+    }
+
+    let requirements = requirements(doc);
+
+    if !requirements.is_empty() {
+        let _ = write!(out, "\n**Requires:** {}\n", requirements.join(", "));
+    }
+}
+
+/// Renders a way of using a command, under its own heading if it's a subcommand.
+fn usage(out: &mut String, usage: Usage, nested: bool) {
+    if nested {
+        let _ = write!(out, "\n#### `{}`\n\n{}\n", usage.name(), usage.description);
+    }
+
+    let _ = write!(out, "\n```\n{usage}\n```\n");
+
+    if usage.options.is_empty() {
+        return;
+    }
+
+    out.push_str("\n| Option | Required | Description |\n| --- | --- | --- |\n");
+
+    for option in usage.options {
+        let required = if option.required { "Yes" } else { "No" };
+        let _ = writeln!(
+            out,
+            "| `{}` | {required} | {} |",
+            option.name,
+            option.description.replace('|', "\\|"),
+        );
+    }
+}
+
+fn kind(doc: &CommandDoc) -> &'static str {
+    match doc.kind {
+        CommandType::User => "User",
+        _ => "Message",
+    }
+}
+
+/// Describes the default permissions and requirements of a command.
+fn requirements(doc: &CommandDoc) -> Vec<String> {
+    let mut requirements = doc
+        .permissions
+        .map(|permissions| permissions.get_permission_names())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|name| format!("{name} permission"))
+        .collect::<Vec<_>>();
+
+    for requirement in doc.requires {
+        requirements.push(match requirement {
+            Requirement::Staff => "staff role".to_string(),
+            Requirement::Owner => "bot owner".to_string(),
+            Requirement::Channels(channels) => {
+                let channels = channels
+                    .iter()
+                    .map(|channel| format!("`{channel}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("used in {channels}")
+            }
+        });
+    }
+
+    requirements
+}
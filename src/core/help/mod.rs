@@ -0,0 +1,26 @@
+//! The built-in `/help` command.
+//!
+//! Commands document themselves: `/help` reads back the names, descriptions
+//! and options they're uploaded with, along with their requirements and the
+//! `example` attributes of the `Command` derive. Only the commands the user could
+//! use where they are get listed.
+//!
+//! The same reference is rendered as Markdown for the wiki by `/help-export`,
+//! or by `markdown::render` directly.
+
+use crate::core::{controller::GLOBAL, setup::Setup};
+
+mod commands;
+mod docs;
+pub mod markdown;
+
+pub use commands::*;
+pub use docs::*;
+
+/// Installs `/help`, `/help-export` and the buttons paging through `/help`.
+pub fn installer(setup: Setup) -> Setup {
+    setup
+        .add_command::<Help>(GLOBAL)
+        .add_command::<HelpExport>(GLOBAL)
+        .add_component::<HelpPage>(GLOBAL)
+}
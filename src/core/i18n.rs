@@ -0,0 +1,199 @@
+//! Localisation of commands and replies.
+//!
+//! Strings are looked up in the Fluent files in `locales/`, one per Discord
+//! locale, which are compiled into the bot. Lookups fall back to another locale
+//! of the same language, then to `DEFAULT_LOCALE`.
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentValue;
+
+/// The locale of the names and descriptions written in the code, which the others
+/// are translations of.
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// The resource file of each supported locale.
+const RESOURCES: &[(&str, &str)] = &[
+    ("en-US", include_str!("../../locales/en-US.ftl")),
+    ("es-ES", include_str!("../../locales/es-ES.ftl")),
+    ("pt-BR", include_str!("../../locales/pt-BR.ftl")),
+];
+
+type Bundle = FluentBundle<FluentResource>;
+
+static BUNDLES: OnceLock<Vec<(&'static str, Bundle)>> = OnceLock::new();
+
+/// Translates a message into a locale, such as that of the user of a command.
+/// Messages missing from every locale are logged and returned as their ID.
+pub fn tr(locale: &str, key: &str, args: &[(&str, FluentValue)]) -> String {
+    translate(bundles(), locale, key, args)
+}
+
+/// Translates a message like `tr`, from a given set of bundles.
+fn translate(
+    bundles: &[(&str, Bundle)],
+    locale: &str,
+    key: &str,
+    args: &[(&str, FluentValue)],
+) -> String {
+    let args = args.iter().cloned().collect::<FluentArgs>();
+
+    [bundle(bundles, locale), bundle(bundles, DEFAULT_LOCALE)]
+        .into_iter()
+        .find_map(|bundle| format(bundle, key, None, Some(&args)))
+        .unwrap_or_else(|| {
+            warn!(key, "missing translation");
+            key.to_string()
+        })
+}
+
+/// Adds the `name_localizations` and `description_localizations` of a command or
+/// option to its JSON, from the `name` and `description` attributes of the message
+/// with the given key. Either is left out if no locale translates it.
+pub fn add_localizations(data: &mut Map<String, Value>, key: &str) {
+    for attribute in ["name", "description"] {
+        let localizations = bundles()
+            .iter()
+            .filter(|(locale, _)| *locale != DEFAULT_LOCALE)
+            .filter_map(|(locale, bundle)| {
+                let text = format(bundle, key, Some(attribute), None)?;
+                Some((locale.to_string(), Value::String(text)))
+            })
+            .collect::<Map<_, _>>();
+
+        if !localizations.is_empty() {
+            data.insert(format!("{attribute}_localizations"), localizations.into());
+        }
+    }
+}
+
+/// Formats a message, or one of its attributes, if the bundle has it.
+fn format(
+    bundle: &Bundle,
+    key: &str,
+    attribute: Option<&str>,
+    args: Option<&FluentArgs>,
+) -> Option<String> {
+    let message = bundle.get_message(key)?;
+    let pattern = match attribute {
+        Some(attribute) => message.get_attribute(attribute)?.value(),
+        None => message.value()?,
+    };
+
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+
+    if !errors.is_empty() {
+        warn!(key, ?attribute, ?errors, "failed to format translation");
+    }
+
+    Some(text.into_owned())
+}
+
+/// The bundle for a locale, or for another locale of the same language if it isn't
+/// supported, or for the default locale if neither is.
+fn bundle<'a>(bundles: &'a [(&str, Bundle)], locale: &str) -> &'a Bundle {
+    let language = locale.split('-').next().unwrap_or(locale);
+
+    bundles
+        .iter()
+        .find(|(supported, _)| *supported == locale)
+        .or_else(|| {
+            bundles
+                .iter()
+                .find(|(supported, _)| supported.split('-').next() == Some(language))
+        })
+        .or_else(|| {
+            bundles
+                .iter()
+                .find(|(supported, _)| *supported == DEFAULT_LOCALE)
+        })
+        .map(|(_, bundle)| bundle)
+        .expect("the default locale is always supported")
+}
+
+/// Parses the resource files the first time they're needed. Syntax errors only
+/// lose the messages they're in, so they're logged rather than fatal.
+fn bundles() -> &'static [(&'static str, Bundle)] {
+    BUNDLES.get_or_init(|| load(RESOURCES))
+}
+
+/// Parses resource files into a bundle per locale.
+fn load(resources: &[(&'static str, &str)]) -> Vec<(&'static str, Bundle)> {
+    resources
+        .iter()
+        .map(|(locale, source)| {
+            let langid = locale
+                .parse::<LanguageIdentifier>()
+                .expect("supported locales are valid language identifiers");
+
+            let resource =
+                FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {
+                    error!(locale, ?errors, "failed to parse translations");
+                    resource
+                });
+
+            let mut bundle = Bundle::new_concurrent(vec![langid]);
+
+            // Discord would show the Unicode isolation marks around arguments.
+            bundle.set_use_isolating(false);
+
+            if let Err(errors) = bundle.add_resource(resource) {
+                error!(locale, ?errors, "failed to load translations");
+            }
+
+            (*locale, bundle)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bundles where only the default locale has every message.
+    fn bundles() -> Vec<(&'static str, Bundle)> {
+        load(&[
+            ("en-US", "hello = Hello!\nbye = Bye!"),
+            ("es-ES", "hello = ¡Hola!"),
+            ("pt-BR", "hello = Olá!"),
+        ])
+    }
+
+    #[test]
+    fn translates_into_the_exact_locale() {
+        assert_eq!(translate(&bundles(), "es-ES", "hello", &[]), "¡Hola!");
+        assert_eq!(translate(&bundles(), "en-US", "hello", &[]), "Hello!");
+    }
+
+    #[test]
+    fn falls_back_to_another_locale_of_the_language() {
+        assert_eq!(translate(&bundles(), "es-419", "hello", &[]), "¡Hola!");
+        assert_eq!(translate(&bundles(), "pt-PT", "hello", &[]), "Olá!");
+    }
+
+    #[test]
+    fn falls_back_to_the_default_locale() {
+        assert_eq!(translate(&bundles(), "fr", "hello", &[]), "Hello!");
+        assert_eq!(translate(&bundles(), "es-ES", "bye", &[]), "Bye!");
+    }
+
+    #[test]
+    fn missing_messages_are_their_id() {
+        assert_eq!(translate(&bundles(), "es-ES", "missing", &[]), "missing");
+    }
+
+    #[test]
+    fn the_shipped_locales_format_arguments() {
+        let secs = |secs: u64| [("secs", FluentValue::from(secs))];
+
+        assert_eq!(
+            tr("en-US", "cooldown", &secs(1)),
+            "Slow down! Try again in 1 second."
+        );
+        assert!(tr("es-ES", "cooldown", &secs(5)).contains("5 segundos"));
+    }
+}
@@ -1,30 +1,51 @@
 use crate::core::{
     controller::Controller,
+    feature::{self, Feature},
     setup::{ControllerCollection, Step, StepArgs},
 };
 use custom_debug::Debug;
-use serenity::{client::Client, http::client::Http, model::guild::PartialGuild};
+use serenity::{
+    client::Client,
+    model::guild::PartialGuild,
+    prelude::{RwLock, TypeMap},
+};
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 
 /// An initialization function that runs at the end of setup. Usually defined
 /// as a function, which the `init` macro transforms into a static struct.
 ///
-/// Most initializers are used to set event handlers.
+/// Most initializers are used to set event handlers. Initializers may be either
+/// sync or async functions.
 #[derive(Debug)]
 pub struct Init {
     /// The name of the initializer.
     pub name: &'static str,
 
+    /// The feature the initializer belongs to, if it can be turned off.
+    pub feature: Option<&'static Feature>,
+
     #[debug(skip)]
     /// The function pointer for the initializer. This is public
     /// so the `init` macro can write to it, but should not be
     /// messed with, use `Init::exec` to call it.
-    pub __exec: for<'a> fn(args: &'a InitArgs<'a>),
+    pub __exec: for<'a> fn(args: &'a InitArgs<'a>) -> InitFuture<'a>,
 }
 
+/// The future returned by an initializer's function pointer.
+pub type InitFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
 impl Init {
-    pub fn exec<'a>(&self, args: &'a InitArgs<'a>) {
-        (self.__exec)(args)
+    /// Makes the initializer belong to a feature, so it's skipped if the feature
+    /// is off when setup runs.
+    pub fn in_feature(self, feature: Option<&'static Feature>) -> Self {
+        Self { feature, ..self }
+    }
+
+    pub async fn exec<'a>(&self, args: &'a InitArgs<'a>) {
+        (self.__exec)(args).await
     }
 }
 
@@ -39,11 +60,22 @@ pub struct InitArgs<'a> {
 }
 
 impl<'a> InitArgs<'a> {
+    /// The shared data map of the client, where systems store state
+    /// that must outlive setup.
+    pub fn data(&self) -> &Arc<RwLock<TypeMap>> {
+        &self.client.data
+    }
+
+    /// The controller the initializer was registered under.
+    pub fn controller(&self) -> Controller {
+        self.controller
+    }
+
     async fn new(step_args: StepArgs<'a, InitStep>) -> InitArgs<'a> {
         Self {
             client: step_args.client,
             controller: step_args.scope,
-            guild: step_args.scope.try_get_guild(step_args.http).await,
+            guild: step_args.scope.try_get_guild(step_args.api).await,
         }
     }
 }
@@ -80,8 +112,13 @@ impl Step for InitStep {
     async fn execute<'a>(self, args: StepArgs<'a, Self>) -> serenity::Result<()> {
         let init_args = InitArgs::new(args).await;
 
-        for init in (&self.inits).values() {
-            init.exec(&init_args);
+        for init in self.inits.values() {
+            if !feature::allows(init_args.data(), self.controller, init.feature).await {
+                debug!(name = init.name, controller = %self.controller, "feature off, skipping initializer");
+                continue;
+            }
+
+            init.exec(&init_args).await;
         }
 
         Ok(())
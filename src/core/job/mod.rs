@@ -0,0 +1,295 @@
+use crate::core::{
+    controller::Controller,
+    feature::{self, Feature},
+    setup::{ControllerCollection, Step, StepArgs},
+    task::TaskArgs,
+};
+use chrono::Utc;
+use porygon_macros::task;
+use serde::de::DeserializeOwned;
+use serenity::{
+    http::client::Http,
+    prelude::{RwLock, TypeMap, TypeMapKey},
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::Instrument;
+
+mod queue;
+
+pub use queue::*;
+
+/// A handler for durable scheduled jobs. Usually defined as an async function,
+/// which the `job` macro transforms into a static struct.
+///
+/// Unlike a `Task`, which only lives in memory, each *scheduled* run of a job is
+/// stored on disk by the `JobQueue` together with its payload and controller, so
+/// it survives restarts. Jobs that came due while the bot was down are fired as
+/// soon as it's back up.
+///
+/// Delivery is at-least-once: a job is only removed from the queue once its handler
+/// succeeds, so handlers must tolerate running more than once for the same job.
+#[derive(Clone, Copy)]
+pub struct Job {
+    /// The name of the job, which scheduled jobs refer to it by.
+    pub name: &'static str,
+
+    /// How failed attempts are retried.
+    pub retry: RetryPolicy,
+
+    /// The feature the job belongs to, if it can be turned off.
+    pub feature: Option<&'static Feature>,
+
+    /// The function pointer for the job. This is public so the `job`
+    /// macro can write to it, but should not be messed with.
+    pub __exec: for<'a> fn(args: &'a JobArgs<'a>) -> JobFuture<'a>,
+}
+
+impl Job {
+    /// Makes the job belong to a feature, so jobs of its kind stay queued while
+    /// the feature is off.
+    pub fn in_feature(self, feature: Option<&'static Feature>) -> Self {
+        Self { feature, ..self }
+    }
+}
+
+impl fmt::Debug for Job {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Job")
+            .field("name", &self.name)
+            .field("retry", &self.retry)
+            .field("feature", &self.feature)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The result of running a job. Returning an error schedules a retry.
+pub type JobResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+/// The future returned by a job's function pointer.
+pub type JobFuture<'a> = Pin<Box<dyn Future<Output = JobResult> + Send + 'a>>;
+
+/// How a job is retried when it fails.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// How many times the job is attempted before it's moved to the dead-letter log.
+    pub max_attempts: u32,
+
+    /// The delay before the first retry, which doubles with each retry after.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// The policy used for any field not given to the `job` macro.
+    pub const DEFAULT: RetryPolicy = RetryPolicy {
+        max_attempts: 5,
+        backoff: Duration::from_secs(30),
+    };
+
+    /// The longest a retry will ever be pushed back.
+    const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60 * 24);
+
+    /// Returns the delay before retrying, given how many attempts have been made.
+    fn delay(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.backoff
+            .checked_mul(factor)
+            .map_or(Self::MAX_BACKOFF, |d| d.min(Self::MAX_BACKOFF))
+    }
+}
+
+/// Arguments given to a running job.
+#[derive(Debug)]
+pub struct JobArgs<'a> {
+    task: &'a TaskArgs,
+    job: &'a ScheduledJob,
+}
+
+impl<'a> JobArgs<'a> {
+    /// The controller the job was scheduled under.
+    pub fn controller(&self) -> Controller {
+        self.job.controller
+    }
+
+    /// The Serenity HTTP client.
+    pub fn http(&self) -> &Http {
+        self.task.http()
+    }
+
+    /// The shared data map of the client.
+    pub fn data(&self) -> &Arc<RwLock<TypeMap>> {
+        self.task.data()
+    }
+
+    /// The scheduled job being run, including its ID and attempt count.
+    pub fn job(&self) -> &ScheduledJob {
+        self.job
+    }
+
+    /// Deserializes the payload the job was scheduled with.
+    pub fn payload<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        T::deserialize(&self.job.payload)
+    }
+}
+
+/// The job handlers registered under every controller, keyed by controller and name.
+#[derive(Debug, Clone, Default)]
+struct JobRegistry(Arc<RwLock<HashMap<(Controller, &'static str), Job>>>);
+
+impl JobRegistry {
+    async fn get(data: &RwLock<TypeMap>) -> Self {
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(Self::default)
+            .clone()
+    }
+}
+
+impl TypeMapKey for JobRegistry {
+    type Value = Self;
+}
+
+/// Setup step that registers the job handlers under a controller, and makes sure
+/// the `JobQueue` has been loaded from disk.
+#[derive(Debug)]
+pub struct JobStep {
+    controller: Controller,
+    jobs: HashMap<&'static str, Job>,
+}
+
+#[async_trait]
+impl Step for JobStep {
+    type Operand = Job;
+    type Collection = ControllerCollection<Self>;
+
+    const NAME: &'static str = "Job";
+
+    fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            jobs: HashMap::default(),
+        }
+    }
+
+    fn operand_count(&self) -> usize {
+        self.jobs.len()
+    }
+
+    fn append(&mut self, job: Job) {
+        self.jobs.insert(job.name, job);
+    }
+
+    async fn execute<'a>(self, args: StepArgs<'a, Self>) -> serenity::Result<()> {
+        JobQueue::load_into(&args.client.data).await?;
+
+        let registry = JobRegistry::get(&args.client.data).await;
+        let mut registry = registry.0.write().await;
+
+        for (name, job) in self.jobs {
+            registry.insert((self.controller, name), job);
+        }
+
+        Ok(())
+    }
+}
+
+/// Built-in task that starts every job that has come due. Registered under `GLOBAL`
+/// by `Setup::new`, since the queue is shared between all controllers.
+///
+/// Each job runs in the background, so a slow job doesn't hold up the others,
+/// and is left out of later polls until its attempt finishes.
+#[task(every = "5s")]
+pub(crate) async fn poll_jobs(args: &TaskArgs) {
+    let queue = match JobQueue::get(args.data()).await {
+        Some(queue) => queue,
+        None => return,
+    };
+
+    let registry = JobRegistry::get(args.data()).await;
+
+    for job in queue.due(Utc::now()).await {
+        // Jobs for controllers that were skipped during setup have no handler,
+        // so they stay queued until the controller is connected again.
+        let handler = match registry.0.read().await.get(&(job.controller, &*job.kind)) {
+            Some(handler) => *handler,
+            None => continue,
+        };
+
+        // Likewise, jobs of features that are off wait until they're turned on.
+        if !feature::allows(args.data(), job.controller, handler.feature).await {
+            continue;
+        }
+
+        let claim = match queue.claim(job.id) {
+            Some(claim) => claim,
+            None => continue,
+        };
+
+        let (queue, args) = (queue.clone(), args.clone());
+
+        tokio::spawn(async move {
+            if let Err(e) = run(&queue, handler, job, &args).await {
+                error!(%e, "failed to update job queue");
+            }
+
+            drop(claim);
+        });
+    }
+}
+
+/// Runs a single attempt of a job and records the outcome in the queue.
+async fn run(
+    queue: &JobQueue,
+    handler: Job,
+    mut job: ScheduledJob,
+    task: &TaskArgs,
+) -> std::io::Result<()> {
+    // The attempt is recorded before running, so an attempt that crashes
+    // the bot still counts towards the retry limit.
+    job.attempts = match queue.begin_attempt(job.id).await? {
+        Some(attempts) => attempts,
+        None => {
+            debug!(id = job.id, "cancelled before it could run");
+            return Ok(());
+        }
+    };
+
+    let span = info_span!(
+        "job",
+        id = job.id,
+        kind = %job.kind,
+        controller = %job.controller,
+        attempt = job.attempts,
+    );
+
+    let args = JobArgs { task, job: &job };
+    let result = (handler.__exec)(&args).instrument(span.clone()).await;
+
+    match result {
+        Ok(()) => {
+            span.in_scope(|| debug!("completed"));
+            queue.complete(job.id).await
+        }
+        Err(e) if job.attempts >= handler.retry.max_attempts => {
+            span.in_scope(|| error!(%e, "failed for the last time, dead-lettering"));
+            queue.dead_letter(job.id, e.to_string()).await
+        }
+        Err(e) => {
+            let delay = handler.retry.delay(job.attempts);
+            span.in_scope(|| warn!(%e, ?delay, "failed, retrying"));
+
+            let due = Utc::now()
+                + chrono::Duration::from_std(delay).unwrap_or_else(|_| chrono::Duration::zero());
+            queue.retry(job.id, due, e.to_string()).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,364 @@
+use super::Job;
+use crate::core::{config::Config, controller::Controller, data};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serenity::prelude::{Mutex, RwLock, TypeMap, TypeMapKey};
+use std::collections::HashSet;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex as SyncMutex};
+
+/// Name of the file in the data directory that snapshots of the queue are
+/// persisted to.
+const FILE: &str = "jobs.json";
+
+/// Name of the JSON Lines file in the data directory that every change since the
+/// last snapshot is appended to.
+const JOURNAL_FILE: &str = "jobs.journal.jsonl";
+
+/// Once the journal has this many changes, they're folded into a new snapshot.
+const COMPACT_AFTER: usize = 512;
+
+/// Name of the JSON Lines file in the data directory that jobs which ran out of
+/// attempts are appended to.
+const DEAD_LETTER_FILE: &str = "jobs.dead.jsonl";
+
+/// A single scheduled run of a `Job`, as stored on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    /// Unique ID of the scheduled job, which can be used to cancel it.
+    pub id: u64,
+
+    /// The name of the `Job` that handles this.
+    pub kind: String,
+
+    /// The controller the job was scheduled under, whose handler runs it.
+    pub controller: Controller,
+
+    /// Arbitrary data for the handler, such as which user to unmute.
+    pub payload: Value,
+
+    /// When the job should next run.
+    pub due: DateTime<Utc>,
+
+    /// How many attempts have been made so far.
+    pub attempts: u32,
+
+    /// The error from the last failed attempt, if any.
+    pub last_error: Option<String>,
+}
+
+/// An entry in the dead-letter log.
+#[derive(Debug, Serialize)]
+struct DeadLetter<'a> {
+    job: &'a ScheduledJob,
+    failed_at: DateTime<Utc>,
+}
+
+/// What's actually persisted to the jobs file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct QueueState {
+    next_id: u64,
+    jobs: Vec<ScheduledJob>,
+}
+
+/// A change to the queue, as appended to the journal. Each one carries the whole
+/// job rather than a delta, so replaying a change that's already in the
+/// snapshot is harmless.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+enum Change {
+    /// A job was scheduled or updated.
+    Put { job: ScheduledJob },
+
+    /// A job completed, was cancelled or was dead-lettered.
+    Remove { id: u64 },
+}
+
+impl QueueState {
+    fn apply(&mut self, change: Change) {
+        match change {
+            Change::Put { job } => {
+                self.next_id = self.next_id.max(job.id + 1);
+
+                match self.jobs.iter_mut().find(|j| j.id == job.id) {
+                    Some(existing) => *existing = job,
+                    None => self.jobs.push(job),
+                }
+            }
+            Change::Remove { id } => self.jobs.retain(|job| job.id != id),
+        }
+    }
+
+    fn get(&self, id: u64) -> Option<&ScheduledJob> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+}
+
+/// The queue, along with where it's persisted.
+#[derive(Debug)]
+struct Store {
+    state: QueueState,
+
+    /// The data directory the queue is persisted to.
+    dir: PathBuf,
+
+    /// How many changes have been journaled since the last snapshot.
+    journaled: usize,
+}
+
+impl Store {
+    /// Appends a change to the journal, and only applies it once that succeeds,
+    /// so the queue in memory never gets ahead of the one on disk.
+    fn commit(&mut self, change: Change) -> io::Result<()> {
+        data::append(&self.dir, JOURNAL_FILE, &change)?;
+        self.state.apply(change);
+        self.journaled += 1;
+
+        if self.journaled >= COMPACT_AFTER {
+            // The change is already durable, so a failed compaction only means
+            // the journal keeps growing until the next one.
+            if let Err(e) = self.compact() {
+                warn!(%e, "failed to compact job journal");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes a snapshot of the whole queue, then starts a new journal. A crash in
+    /// between leaves the old journal to be replayed over the new snapshot, which
+    /// changes nothing.
+    fn compact(&mut self) -> io::Result<()> {
+        data::save(&self.dir, FILE, &self.state)?;
+        data::remove(&self.dir, JOURNAL_FILE)?;
+        self.journaled = 0;
+        Ok(())
+    }
+}
+
+/// The persistent queue of scheduled jobs. Every change is appended to a journal
+/// in the data directory before it's acknowledged, so nothing is lost on a
+/// restart, and the journal is folded into a snapshot of the whole queue every so
+/// often.
+///
+/// Lives on the client data once setup has started, whether or not any `Job` has
+/// been registered.
+#[derive(Debug, Clone)]
+pub struct JobQueue {
+    store: Arc<Mutex<Store>>,
+
+    /// The jobs with an attempt in flight, which aren't persisted.
+    running: Arc<SyncMutex<HashSet<u64>>>,
+}
+
+/// Marks a job as running until dropped. See `JobQueue::claim`.
+#[derive(Debug)]
+pub(super) struct Claim {
+    id: u64,
+    running: Arc<SyncMutex<HashSet<u64>>>,
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.id);
+    }
+}
+
+impl JobQueue {
+    /// Fetches the job queue from the client data. Only `None` before setup has
+    /// loaded it.
+    pub async fn get(data: &RwLock<TypeMap>) -> Option<Self> {
+        data.read().await.get::<Self>().cloned()
+    }
+
+    /// Loads the queue from disk into the client data, unless that's already
+    /// been done. Fails if the jobs file exists but can't be read, so that a
+    /// corrupt file is never overwritten with an empty queue.
+    pub(super) async fn load_into(data: &RwLock<TypeMap>) -> io::Result<()> {
+        let dir = Config::get(data).await.storage.data_dir.clone();
+        let mut data = data.write().await;
+
+        if data.contains_key::<Self>() {
+            return Ok(());
+        }
+
+        let queue = Self::load(dir)?;
+        let pending = queue.pending().await.len();
+        info!(pending, "loaded job queue");
+
+        data.insert::<Self>(queue);
+        Ok(())
+    }
+
+    /// Loads the last snapshot and replays the journal over it, then writes a
+    /// fresh snapshot so the journal starts out empty.
+    pub(super) fn load(dir: PathBuf) -> io::Result<Self> {
+        let mut state: QueueState = data::load(&dir, FILE)?.unwrap_or_default();
+
+        for change in data::load_lines(&dir, JOURNAL_FILE)? {
+            state.apply(change);
+        }
+
+        let mut store = Store {
+            state,
+            dir,
+            journaled: 0,
+        };
+
+        store.compact()?;
+
+        Ok(Self {
+            store: Arc::new(Mutex::new(store)),
+            running: Arc::default(),
+        })
+    }
+
+    /// Schedules a run of a job under a controller, with a payload that will be given
+    /// to the handler. If `due` is in the past, the job runs as soon as possible.
+    pub async fn schedule(
+        &self,
+        controller: Controller,
+        job: &Job,
+        due: DateTime<Utc>,
+        payload: impl Serialize,
+    ) -> io::Result<u64> {
+        let payload = serde_json::to_value(payload)?;
+        let mut store = self.store.lock().await;
+        let id = store.state.next_id;
+
+        store.commit(Change::Put {
+            job: ScheduledJob {
+                id,
+                kind: job.name.to_string(),
+                controller,
+                payload,
+                due,
+                attempts: 0,
+                last_error: None,
+            },
+        })?;
+
+        Ok(id)
+    }
+
+    /// Cancels a scheduled job. Returns whether it was still pending.
+    pub async fn cancel(&self, id: u64) -> io::Result<bool> {
+        let mut store = self.store.lock().await;
+
+        if store.state.get(id).is_none() {
+            return Ok(false);
+        }
+
+        store.commit(Change::Remove { id })?;
+        Ok(true)
+    }
+
+    /// Returns every pending job.
+    pub async fn pending(&self) -> Vec<ScheduledJob> {
+        self.store.lock().await.state.jobs.clone()
+    }
+
+    /// Returns every job that is due at the given time and has no attempt in
+    /// flight, oldest first.
+    pub(super) async fn due(&self, now: DateTime<Utc>) -> Vec<ScheduledJob> {
+        let store = self.store.lock().await;
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        let mut due = store
+            .state
+            .jobs
+            .iter()
+            .filter(|job| job.due <= now && !running.contains(&job.id))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        due.sort_by_key(|job| job.due);
+        due
+    }
+
+    /// Marks a job as running until the returned claim is dropped, so `due`
+    /// leaves it out while an attempt is in flight. Returns `None` if it's
+    /// already running.
+    pub(super) fn claim(&self, id: u64) -> Option<Claim> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+
+        running.insert(id).then(|| Claim {
+            id,
+            running: self.running.clone(),
+        })
+    }
+
+    /// Records that an attempt is starting, and returns the new attempt count, or
+    /// `None` if the job has been cancelled since it was found to be due.
+    pub(super) async fn begin_attempt(&self, id: u64) -> io::Result<Option<u32>> {
+        self.update(id, |job| job.attempts += 1)
+            .await
+            .map(|job| job.map(|job| job.attempts))
+    }
+
+    /// Removes a job that ran successfully.
+    pub(super) async fn complete(&self, id: u64) -> io::Result<()> {
+        self.cancel(id).await.map(|_| ())
+    }
+
+    /// Pushes a failed job back to be retried later.
+    pub(super) async fn retry(&self, id: u64, due: DateTime<Utc>, error: String) -> io::Result<()> {
+        self.update(id, |job| {
+            job.due = due;
+            job.last_error = Some(error);
+        })
+        .await
+        .map(|_| ())
+    }
+
+    /// Moves a job that ran out of attempts from the queue to the dead-letter log.
+    pub(super) async fn dead_letter(&self, id: u64, error: String) -> io::Result<()> {
+        let mut store = self.store.lock().await;
+        let mut job = match store.state.get(id) {
+            Some(job) => job.clone(),
+            None => return Ok(()),
+        };
+
+        job.last_error = Some(error);
+
+        // The dead letter is written first, so a crash in between at worst
+        // leaves the job in both places rather than in neither.
+        data::append(
+            &store.dir,
+            DEAD_LETTER_FILE,
+            &DeadLetter {
+                job: &job,
+                failed_at: Utc::now(),
+            },
+        )?;
+
+        store.commit(Change::Remove { id })
+    }
+
+    /// Applies a change to a copy of a pending job and persists it, returning the
+    /// updated job if it exists.
+    async fn update(
+        &self,
+        id: u64,
+        f: impl FnOnce(&mut ScheduledJob),
+    ) -> io::Result<Option<ScheduledJob>> {
+        let mut store = self.store.lock().await;
+        let mut job = match store.state.get(id) {
+            Some(job) => job.clone(),
+            None => return Ok(None),
+        };
+
+        f(&mut job);
+        store.commit(Change::Put { job: job.clone() })?;
+
+        Ok(Some(job))
+    }
+}
+
+impl TypeMapKey for JobQueue {
+    type Value = Self;
+}
@@ -0,0 +1,216 @@
+use super::{run, JobArgs, JobQueue, JobResult, RetryPolicy};
+use crate::core::{controller::POKECOM, data, task::TaskArgs};
+use chrono::{Duration as ChronoDuration, Utc};
+use porygon_macros::job;
+use serde_json::Value;
+use serenity::{client::ClientBuilder, http::HttpBuilder, prelude::GatewayIntents};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use std::{env, fs};
+
+/// A data directory of its own, removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> Self {
+        Self(env::temp_dir().join(format!("porygon-jobs-{}", rand::random::<u64>())))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    fn queue(&self) -> JobQueue {
+        JobQueue::load(self.0.clone()).unwrap()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Arguments for jobs that don't touch Discord. Building a client doesn't
+/// connect to anything.
+async fn args() -> TaskArgs {
+    let http = HttpBuilder::new("token").build();
+    let client = ClientBuilder::new_with_http(http, GatewayIntents::empty())
+        .await
+        .unwrap();
+
+    TaskArgs::new(&client, POKECOM)
+}
+
+#[job]
+async fn succeeds(_: &JobArgs<'_>) -> JobResult {
+    Ok(())
+}
+
+/// How many times `counted` has run.
+static COUNTED_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+#[job]
+async fn counted(_: &JobArgs<'_>) -> JobResult {
+    COUNTED_RUNS.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+#[job(max_attempts = 2, backoff = "1m")]
+async fn fails(_: &JobArgs<'_>) -> JobResult {
+    Err("always fails".into())
+}
+
+#[test]
+fn backoff_doubles_with_each_attempt_up_to_a_day() {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        backoff: Duration::from_secs(30),
+    };
+
+    assert_eq!(policy.delay(1), Duration::from_secs(30));
+    assert_eq!(policy.delay(2), Duration::from_secs(60));
+    assert_eq!(policy.delay(3), Duration::from_secs(120));
+    assert_eq!(policy.delay(40), RetryPolicy::MAX_BACKOFF);
+}
+
+#[tokio::test]
+async fn successful_jobs_leave_the_queue() {
+    let dir = TempDir::new();
+    let queue = dir.queue();
+
+    queue
+        .schedule(POKECOM, &succeeds, Utc::now(), ())
+        .await
+        .unwrap();
+
+    let job = queue.due(Utc::now()).await.remove(0);
+    run(&queue, succeeds, job, &args().await).await.unwrap();
+
+    assert!(queue.pending().await.is_empty());
+}
+
+#[tokio::test]
+async fn jobs_cancelled_once_due_never_run() {
+    let dir = TempDir::new();
+    let queue = dir.queue();
+
+    queue
+        .schedule(POKECOM, &counted, Utc::now(), ())
+        .await
+        .unwrap();
+
+    let job = queue.due(Utc::now()).await.remove(0);
+    queue.cancel(job.id).await.unwrap();
+    run(&queue, counted, job, &args().await).await.unwrap();
+
+    assert_eq!(COUNTED_RUNS.load(Ordering::SeqCst), 0);
+    assert!(queue.pending().await.is_empty());
+}
+
+#[tokio::test]
+async fn failed_jobs_are_retried_after_the_backoff() {
+    let dir = TempDir::new();
+    let queue = dir.queue();
+
+    queue
+        .schedule(POKECOM, &fails, Utc::now(), ())
+        .await
+        .unwrap();
+
+    let job = queue.due(Utc::now()).await.remove(0);
+    run(&queue, fails, job, &args().await).await.unwrap();
+
+    let job = queue.pending().await.remove(0);
+    assert_eq!(job.attempts, 1);
+    assert_eq!(job.last_error.as_deref(), Some("always fails"));
+    assert!(job.due > Utc::now() + ChronoDuration::seconds(50));
+    assert!(queue.due(Utc::now()).await.is_empty());
+}
+
+#[tokio::test]
+async fn jobs_are_dead_lettered_after_their_last_attempt() {
+    let dir = TempDir::new();
+    let queue = dir.queue();
+    let args = args().await;
+
+    queue
+        .schedule(POKECOM, &fails, Utc::now(), "payload")
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        let job = queue.pending().await.remove(0);
+        run(&queue, fails, job, &args).await.unwrap();
+    }
+
+    assert!(queue.pending().await.is_empty());
+
+    let dead = data::load_lines::<Value>(dir.path(), "jobs.dead.jsonl").unwrap();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0]["job"]["payload"], "payload");
+    assert_eq!(dead[0]["job"]["attempts"], 2);
+    assert_eq!(dead[0]["job"]["last_error"], "always fails");
+}
+
+#[tokio::test]
+async fn changes_survive_a_restart() {
+    let dir = TempDir::new();
+    let queue = dir.queue();
+    let due = Utc::now() + ChronoDuration::hours(1);
+
+    let kept = queue.schedule(POKECOM, &fails, due, 1).await.unwrap();
+    let cancelled = queue.schedule(POKECOM, &fails, due, 2).await.unwrap();
+
+    queue.cancel(cancelled).await.unwrap();
+    queue.begin_attempt(kept).await.unwrap();
+    drop(queue);
+
+    let queue = dir.queue();
+    let pending = queue.pending().await;
+
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, kept);
+    assert_eq!(pending[0].attempts, 1);
+
+    // IDs are never reused, even for cancelled jobs.
+    let next = queue.schedule(POKECOM, &fails, due, 3).await.unwrap();
+    assert!(next > cancelled);
+}
+
+#[tokio::test]
+async fn a_partially_written_change_is_dropped() {
+    let dir = TempDir::new();
+    let queue = dir.queue();
+
+    queue
+        .schedule(POKECOM, &fails, Utc::now(), ())
+        .await
+        .unwrap();
+    drop(queue);
+
+    let journal = dir.path().join("jobs.journal.jsonl");
+    let mut text = fs::read_to_string(&journal).unwrap();
+    text.push_str(r#"{"change":"put","job":{"id":"#);
+    fs::write(&journal, text).unwrap();
+
+    assert_eq!(dir.queue().pending().await.len(), 1);
+}
+
+#[tokio::test]
+async fn claimed_jobs_are_not_due_again_until_released() {
+    let dir = TempDir::new();
+    let queue = dir.queue();
+    let id = queue
+        .schedule(POKECOM, &succeeds, Utc::now(), ())
+        .await
+        .unwrap();
+
+    let claim = queue.claim(id).unwrap();
+    assert!(queue.claim(id).is_none());
+    assert!(queue.due(Utc::now()).await.is_empty());
+
+    drop(claim);
+    assert_eq!(queue.due(Utc::now()).await.len(), 1);
+}
@@ -5,11 +5,26 @@
 //! specific commands.
 //!
 
+pub mod api;
 pub mod command;
+pub mod component;
+pub mod config;
 pub mod context;
 pub mod controller;
+pub mod data;
 pub mod event;
+pub mod feature;
 pub mod guild;
+pub mod help;
+pub mod i18n;
 pub mod init;
+pub mod job;
+pub mod modal;
 pub mod prelude;
+pub mod settings;
 pub mod setup;
+pub mod storage;
+pub mod task;
+
+#[cfg(test)]
+pub mod testing;
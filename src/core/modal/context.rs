@@ -0,0 +1,14 @@
+use crate::core::command::InteractionContext;
+use serenity::{model::application::interaction::modal::ModalSubmitInteraction, Result};
+
+/// Everything a modal handler has access to while handling a submission.
+pub type ModalContext = InteractionContext<ModalSubmitInteraction>;
+
+impl ModalContext {
+    /// Acknowledges the submission and shows a loading message, giving the
+    /// handler up to 15 minutes to reply. Does nothing if the submission has
+    /// already been acknowledged.
+    pub async fn defer(&self, ephemeral: bool) -> Result<()> {
+        self.acknowledge(ephemeral).await
+    }
+}
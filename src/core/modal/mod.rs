@@ -0,0 +1,147 @@
+//! Modal forms.
+//!
+//! A modal is a struct deriving `Modal`, where each field is a text input. It's
+//! shown with `CommandContext::show_modal` or `ComponentContext::show_modal`, and
+//! the submission is parsed back into the struct and passed to its
+//! `ModalHandler`:
+//!
+//! ```ignore
+//! /// Open a ticket
+//! #[derive(Modal)]
+//! struct Ticket {
+//!     /// Subject
+//!     #[input(max_length = 100)]
+//!     subject: String,
+//!
+//!     /// Anything else we should know?
+//!     #[input(paragraph)]
+//!     details: Option<String>,
+//! }
+//!
+//! #[async_trait]
+//! impl ModalHandler for Ticket {
+//!     async fn handle(self, ctx: &ModalContext) -> CommandResult { ... }
+//! }
+//! ```
+
+use crate::core::command::CommandResult;
+use serenity::{
+    builder::{CreateComponents, CreateInteractionResponse},
+    model::application::{
+        component::{ActionRow, ActionRowComponent},
+        interaction::InteractionResponseType,
+    },
+};
+use std::{error::Error, fmt};
+
+mod context;
+mod router;
+
+pub use context::*;
+pub use porygon_macros::Modal;
+pub use router::*;
+
+/// A modal form. The implementing type is what the submission is parsed into.
+///
+/// Usually implemented with the `Modal` derive, which builds the text inputs from
+/// the struct's fields and doc comments, and validates them against Discord's
+/// limits at compile time. Fields are required if they're a `String`, and
+/// optional if they're an `Option<String>`.
+pub trait Modal: Sized + Send + 'static {
+    /// The custom ID of the modal, which submissions are routed by.
+    const ID: &'static str;
+
+    /// The title shown at the top of the modal.
+    const TITLE: &'static str;
+
+    /// Adds the text inputs to the modal.
+    fn inputs(components: &mut CreateComponents) -> &mut CreateComponents;
+
+    /// Parses a submission.
+    fn from_inputs(inputs: &[ActionRow]) -> Result<Self, ModalError>;
+}
+
+/// Handles the submission of a modal. Registered with `Setup::add_modal`.
+#[async_trait]
+pub trait ModalHandler: Modal {
+    async fn handle(self, ctx: &ModalContext) -> CommandResult;
+}
+
+/// A type that can be parsed from the value of a text input.
+pub trait FromInput: Sized {
+    /// Whether the input must be filled in.
+    const REQUIRED: bool;
+
+    /// Parses the value of the input, which is `None` if it wasn't submitted at
+    /// all.
+    fn from_input(value: Option<&str>) -> Option<Self>;
+}
+
+impl FromInput for String {
+    const REQUIRED: bool = true;
+
+    fn from_input(value: Option<&str>) -> Option<Self> {
+        value.map(ToString::to_string)
+    }
+}
+
+/// Optional inputs that are left empty are submitted as empty strings.
+impl FromInput for Option<String> {
+    const REQUIRED: bool = false;
+
+    fn from_input(value: Option<&str>) -> Option<Self> {
+        Some(
+            value
+                .filter(|value| !value.is_empty())
+                .map(ToString::to_string),
+        )
+    }
+}
+
+/// Fills in the response showing a modal.
+pub(crate) fn build_response<'a, 'b, M: Modal>(
+    response: &'b mut CreateInteractionResponse<'a>,
+) -> &'b mut CreateInteractionResponse<'a> {
+    response
+        .kind(InteractionResponseType::Modal)
+        .interaction_response_data(|data| {
+            data.custom_id(M::ID).title(M::TITLE).components(M::inputs)
+        })
+}
+
+/// Parses a text input from a submission by its custom ID.
+pub fn parse_input<T: FromInput>(
+    inputs: &[ActionRow],
+    name: &'static str,
+) -> Result<T, ModalError> {
+    let value =
+        inputs
+            .iter()
+            .flat_map(|row| &row.components)
+            .find_map(|component| match component {
+                ActionRowComponent::InputText(input) if input.custom_id == name => {
+                    Some(input.value.as_str())
+                }
+                _ => None,
+            });
+
+    T::from_input(value).ok_or(ModalError::Missing { name })
+}
+
+/// Reasons a submission can fail to parse. Discord validates submissions against
+/// the modal that was shown, so these only happen if it's been changed since.
+#[derive(Debug, Clone)]
+pub enum ModalError {
+    /// A required input was not submitted.
+    Missing { name: &'static str },
+}
+
+impl Error for ModalError {}
+
+impl fmt::Display for ModalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { name } => write!(f, "Missing required input `{name}`."),
+        }
+    }
+}
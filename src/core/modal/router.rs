@@ -0,0 +1,217 @@
+use super::{ModalContext, ModalHandler};
+use crate::core::{
+    command::{CommandError, CommandFuture, CommandResult, Reply, DEFER_AFTER},
+    controller::Controller,
+    feature::{self, Feature},
+    setup::{ControllerCollection, Step, StepArgs},
+};
+use serenity::{
+    client::Context,
+    model::application::interaction::modal::ModalSubmitInteraction,
+    prelude::{RwLock, TypeMap, TypeMapKey},
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A modal registered with `Setup::add_modal`, with its type erased so that
+/// modals of different types can be stored together.
+#[derive(Clone, Copy)]
+pub struct ModalRoute {
+    /// The custom ID of the modal.
+    pub id: &'static str,
+
+    /// The feature the modal belongs to, if it can be turned off.
+    pub feature: Option<&'static Feature>,
+
+    exec: for<'a> fn(&'a ModalContext) -> CommandFuture<'a>,
+}
+
+impl fmt::Debug for ModalRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModalRoute")
+            .field("id", &self.id)
+            .field("feature", &self.feature)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ModalRoute {
+    /// Creates the route for a modal.
+    pub fn of<M: ModalHandler>() -> Self {
+        Self {
+            id: M::ID,
+            feature: None,
+            exec: exec::<M>,
+        }
+    }
+
+    /// Makes the modal belong to a feature, so it's refused while the feature
+    /// is off.
+    pub fn in_feature(self, feature: Option<&'static Feature>) -> Self {
+        Self { feature, ..self }
+    }
+
+    /// Parses the submission and runs the handler.
+    pub async fn exec(&self, ctx: &ModalContext) -> CommandResult {
+        (self.exec)(ctx).await
+    }
+}
+
+fn exec<M: ModalHandler>(ctx: &ModalContext) -> CommandFuture<'_> {
+    Box::pin(async move {
+        let inputs = &ctx.interaction().data.components;
+        let modal = M::from_inputs(inputs).map_err(CommandError::internal)?;
+        modal.handle(ctx).await
+    })
+}
+
+/// Routes incoming modal submissions to their handlers, by the custom ID of the
+/// modal. Lives on the client data, where `ModalStep` fills it in during setup.
+///
+/// Like components, the same modal can be registered under several controllers.
+#[derive(Debug, Clone, Default)]
+pub struct ModalRouter {
+    routes: Arc<RwLock<Routes>>,
+}
+
+/// The modals of each custom ID, with the controllers they're registered under.
+type Routes = HashMap<&'static str, Vec<(Controller, ModalRoute)>>;
+
+impl ModalRouter {
+    /// Fetches the router from the client data, creating it if needed.
+    pub async fn get(data: &RwLock<TypeMap>) -> Self {
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(Self::default)
+            .clone()
+    }
+
+    async fn insert(&self, controller: Controller, route: ModalRoute) {
+        self.routes
+            .write()
+            .await
+            .entry(route.id)
+            .or_default()
+            .push((controller, route));
+    }
+
+    /// Handles a modal submission, logging rather than returning any failure,
+    /// since there's nobody to return it to.
+    #[instrument(skip_all, fields(custom_id = %interaction.data.custom_id))]
+    pub async fn dispatch(&self, ctx: Context, interaction: ModalSubmitInteraction) {
+        let routes = self
+            .routes
+            .read()
+            .await
+            .get(interaction.data.custom_id.as_str())
+            .cloned();
+
+        let routes = match routes {
+            Some(routes) => routes,
+            None => return warn!("no handler for modal"),
+        };
+
+        let route = routes
+            .iter()
+            .find(|(controller, _)| controller.matches_guild(interaction.guild_id))
+            .copied();
+
+        let (controller, route) = match route {
+            Some(route) => route,
+            None => {
+                warn!(guild = ?interaction.guild_id, "modal submitted outside its guild");
+
+                let ctx = ModalContext::new(ctx, interaction, routes[0].0);
+                let reply = Reply::text(ctx.tr("component-unavailable", &[])).ephemeral();
+
+                if let Err(e) = ctx.reply(reply).await {
+                    error!(%e, "failed to reject modal");
+                }
+
+                return;
+            }
+        };
+
+        let ctx = ModalContext::new(ctx, interaction, controller);
+        let result = match feature::check(&ctx, route.feature).await {
+            Ok(()) => run(&ctx, route).await,
+            Err(e) => Err(e),
+        };
+
+        // Whatever happened, the submission must be acknowledged, or Discord
+        // shows the user that it failed.
+        let reply = match result {
+            Ok(()) if ctx.has_responded().await => return,
+            Ok(()) => {
+                warn!("modal finished without replying");
+                Reply::text(ctx.tr("done", &[])).ephemeral()
+            }
+            Err(e) => e.report(&ctx).await,
+        };
+
+        if let Err(e) = ctx.reply(reply).await {
+            error!(%e, "failed to acknowledge modal");
+        }
+    }
+}
+
+/// Runs a modal's handler, deferring the submission if it's slow to reply.
+async fn run(ctx: &ModalContext, route: ModalRoute) -> CommandResult {
+    let handler = route.exec(ctx);
+    tokio::pin!(handler);
+
+    tokio::select! {
+        result = &mut handler => result,
+        _ = tokio::time::sleep(DEFER_AFTER) => {
+            ctx.auto_defer().await;
+            handler.await
+        }
+    }
+}
+
+impl TypeMapKey for ModalRouter {
+    type Value = Self;
+}
+
+/// Setup step that records the modals registered under a controller with the
+/// `ModalRouter`.
+#[derive(Debug)]
+pub struct ModalStep {
+    controller: Controller,
+    modals: HashMap<&'static str, ModalRoute>,
+}
+
+#[async_trait]
+impl Step for ModalStep {
+    type Operand = ModalRoute;
+    type Collection = ControllerCollection<Self>;
+
+    const NAME: &'static str = "Modal";
+
+    fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            modals: HashMap::default(),
+        }
+    }
+
+    fn operand_count(&self) -> usize {
+        self.modals.len()
+    }
+
+    fn append(&mut self, modal: ModalRoute) {
+        self.modals.insert(modal.id, modal);
+    }
+
+    async fn execute<'a>(self, args: StepArgs<'a, Self>) -> serenity::Result<()> {
+        let router = ModalRouter::get(&args.client.data).await;
+
+        for route in self.modals.into_values() {
+            router.insert(self.controller, route).await;
+        }
+
+        Ok(())
+    }
+}
@@ -4,6 +4,13 @@
 //! `core` modules should preferably avoid depending on
 //! this themselves, and just import things directly.
 
+pub use super::command::{
+    AutocompleteContext, Command, CommandContext, CommandError, CommandResult, Handler,
+    MessageCommand, Reply, Suggestion, UserCommand,
+};
+pub use super::component::Component;
 pub use super::controller::*;
 pub use super::init::{Init, InitArgs};
+pub use super::modal::ModalHandler;
 pub use super::setup::Setup;
+pub use super::task::TaskArgs;
@@ -0,0 +1,119 @@
+use super::{Settings, SettingsError};
+use crate::core::command::{
+    add_fields, AutocompleteContext, CommandContext, CommandError, CommandResult, Handler, Reply,
+    Suggestion, MAX_FIELDS,
+};
+use porygon_macros::Command;
+use serenity::utils::Colour;
+
+/// View and change the settings of this server.
+#[derive(Command)]
+#[command(
+    staff,
+    default_permissions(manage_guild),
+    example = "/config set setting: log-channel value: #mod-log"
+)]
+pub enum Config {
+    View(View),
+    Set(Set),
+    Reset(Reset),
+}
+
+/// List every setting and its current value.
+#[derive(Command)]
+pub struct View;
+
+/// Change the value of a setting.
+#[derive(Command)]
+pub struct Set {
+    /// The setting to change.
+    #[option(autocomplete = "setting_names")]
+    setting: String,
+
+    /// The new value, such as a channel, a role, `on` or `off`.
+    value: String,
+}
+
+/// Change a setting back to its default.
+#[derive(Command)]
+pub struct Reset {
+    /// The setting to reset.
+    #[option(autocomplete = "setting_names")]
+    setting: String,
+}
+
+#[async_trait]
+impl Handler for View {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let settings = Settings::get(ctx.data()).await;
+        let registered = settings.registered(ctx.controller());
+
+        if registered.is_empty() {
+            ctx.reply(Reply::text(ctx.tr("config-empty", &[])).ephemeral())
+                .await?;
+            return Ok(());
+        }
+
+        let fields = registered
+            .into_iter()
+            .filter_map(|setting| {
+                let (value, changed) = settings.display(ctx.controller(), setting.name())?;
+                let value = match changed {
+                    true => value,
+                    false => ctx.tr("config-default", &[("value", value.into())]),
+                };
+
+                Some((
+                    format!("`{}`", setting.name()),
+                    format!("{}\n{value}", setting.description()),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let reply = Reply::default().embed(|e| {
+            add_fields(ctx, e, fields, MAX_FIELDS);
+            e.title(ctx.tr("config-title", &[])).colour(Colour::BLURPLE)
+        });
+
+        ctx.reply(reply.ephemeral()).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Set {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let value = Settings::get(ctx.data())
+            .await
+            .set(ctx.controller(), &self.setting, &self.value, ctx.user().id)
+            .await
+            .map_err(|e| CommandError::refuse(e, |e| matches!(e, SettingsError::Storage(_))))?;
+
+        let args = [("name", self.setting.into()), ("value", value.into())];
+        ctx.reply(ctx.tr("config-set", &args)).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Reset {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let value = Settings::get(ctx.data())
+            .await
+            .reset(ctx.controller(), &self.setting)
+            .await
+            .map_err(|e| CommandError::refuse(e, |e| matches!(e, SettingsError::Storage(_))))?;
+
+        let args = [("name", self.setting.into()), ("value", value.into())];
+        ctx.reply(ctx.tr("config-reset", &args)).await?;
+        Ok(())
+    }
+}
+
+/// Suggests the settings registered here whose names contain what's been typed.
+async fn setting_names(ctx: &AutocompleteContext) -> Vec<Suggestion> {
+    let settings = Settings::get(ctx.data()).await;
+    let registered = settings.registered(ctx.controller());
+
+    ctx.suggest_names(registered.iter().map(|setting| setting.name()))
+}
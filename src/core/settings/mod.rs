@@ -0,0 +1,279 @@
+//! Per-guild settings.
+//!
+//! Features declare the knobs they need as typed `Setting` constants, with a
+//! default and an optional validation, and register them under a controller
+//! with `Setup::add_setting`. Each controller's values are stored in its
+//! database and cached on `Settings`, so handlers read them without waiting on
+//! storage.
+//!
+//! Registering a setting under a controller also uploads `/config` there,
+//! which staff use to view, change and reset the settings of their guild.
+
+use crate::core::{
+    controller::Controller,
+    setup::{ControllerCollection, Step, StepArgs},
+    storage::{Storage, StorageError},
+};
+use serde_json::Value;
+use serenity::{
+    model::id::UserId,
+    prelude::{RwLock, TypeMap, TypeMapKey},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, RwLock as SyncRwLock};
+
+mod config;
+mod repository;
+mod setting;
+
+pub use config::*;
+pub use repository::*;
+pub use setting::*;
+
+/// The settings registered under every controller, and their values. Lives on
+/// the client data, where `SettingStep` fills it in during setup.
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    controllers: Arc<SyncRwLock<HashMap<Controller, ControllerSettings>>>,
+}
+
+/// The settings of a single controller.
+#[derive(Debug)]
+struct ControllerSettings {
+    repository: SettingsRepository,
+    settings: BTreeMap<&'static str, &'static dyn AnySetting>,
+
+    /// The values that have been changed from their defaults, by setting name.
+    values: HashMap<&'static str, Value>,
+}
+
+impl Settings {
+    /// Fetches the settings from the client data, creating them if needed.
+    pub async fn get(data: &RwLock<TypeMap>) -> Self {
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(Self::default)
+            .clone()
+    }
+
+    /// Reads the value of a setting in a controller, which is its default if it
+    /// hasn't been changed, or if it isn't registered under the controller.
+    pub fn read<T: SettingValue>(&self, controller: Controller, setting: &Setting<T>) -> T {
+        let controllers = self.controllers.read().unwrap_or_else(|e| e.into_inner());
+        let value = controllers
+            .get(&controller)
+            .and_then(|settings| settings.values.get(setting.name));
+
+        match value {
+            Some(value) => setting.decode(value).unwrap_or_else(|e| {
+                error!(name = setting.name, %controller, %e, "stored setting is invalid");
+                setting.default_value()
+            }),
+            None => setting.default_value(),
+        }
+    }
+
+    /// The settings registered under a controller, sorted by name.
+    pub fn registered(&self, controller: Controller) -> Vec<&'static dyn AnySetting> {
+        let controllers = self.controllers.read().unwrap_or_else(|e| e.into_inner());
+
+        controllers
+            .get(&controller)
+            .map(|settings| settings.settings.values().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Shows the value of a registered setting in a message, and whether it's
+    /// been changed from its default.
+    pub fn display(&self, controller: Controller, name: &str) -> Option<(String, bool)> {
+        let controllers = self.controllers.read().unwrap_or_else(|e| e.into_inner());
+        let settings = controllers.get(&controller)?;
+        let setting = settings.settings.get(name)?;
+        let value = settings.values.get(name);
+
+        Some((setting.display(value), value.is_some()))
+    }
+
+    /// Changes the value of a setting to what a user typed, returning how the new
+    /// value is shown.
+    pub async fn set(
+        &self,
+        controller: Controller,
+        name: &str,
+        input: &str,
+        user: UserId,
+    ) -> Result<String, SettingsError> {
+        let (repository, setting) = self.find(controller, name)?;
+        let value = setting.parse(input).map_err(SettingsError::Invalid)?;
+
+        repository.set(setting.name(), &value, user).await?;
+
+        let display = setting.display(Some(&value));
+        self.update(controller, setting, Some(value));
+
+        info!(name = setting.name(), %controller, %user, "changed setting");
+        Ok(display)
+    }
+
+    /// Changes a setting back to its default, returning how the default is shown.
+    pub async fn reset(&self, controller: Controller, name: &str) -> Result<String, SettingsError> {
+        let (repository, setting) = self.find(controller, name)?;
+
+        repository.remove(setting.name()).await?;
+        self.update(controller, setting, None);
+
+        info!(name = setting.name(), %controller, "reset setting");
+        Ok(setting.display(None))
+    }
+
+    fn find(
+        &self,
+        controller: Controller,
+        name: &str,
+    ) -> Result<(SettingsRepository, &'static dyn AnySetting), SettingsError> {
+        let controllers = self.controllers.read().unwrap_or_else(|e| e.into_inner());
+
+        controllers
+            .get(&controller)
+            .and_then(|settings| {
+                let setting = settings.settings.get(name)?;
+                Some((settings.repository.clone(), *setting))
+            })
+            .ok_or_else(|| SettingsError::Unknown(name.to_string()))
+    }
+
+    fn update(
+        &self,
+        controller: Controller,
+        setting: &'static dyn AnySetting,
+        value: Option<Value>,
+    ) {
+        let mut controllers = self.controllers.write().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(settings) = controllers.get_mut(&controller) {
+            match value {
+                Some(value) => settings.values.insert(setting.name(), value),
+                None => settings.values.remove(setting.name()),
+            };
+        }
+    }
+
+    fn insert(&self, controller: Controller, settings: ControllerSettings) {
+        let mut controllers = self.controllers.write().unwrap_or_else(|e| e.into_inner());
+        controllers.insert(controller, settings);
+    }
+}
+
+impl TypeMapKey for Settings {
+    type Value = Self;
+}
+
+/// Reasons a setting can't be changed.
+#[derive(Debug)]
+pub enum SettingsError {
+    /// No setting with the name is registered under the controller.
+    Unknown(String),
+
+    /// The value was refused by the setting, with the message shown to the user.
+    Invalid(String),
+
+    Storage(StorageError),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(name) => write!(f, "There's no setting called `{name}`."),
+            Self::Invalid(message) => write!(f, "{message}"),
+            Self::Storage(e) => write!(f, "Failed to store the setting: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Storage(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<StorageError> for SettingsError {
+    fn from(error: StorageError) -> Self {
+        Self::Storage(error)
+    }
+}
+
+/// Setup step that loads the stored values of the settings registered under a
+/// controller onto `Settings`. Runs right after migrations, so everything else
+/// reads the stored values rather than the defaults.
+#[derive(Debug)]
+pub struct SettingStep {
+    controller: Controller,
+    settings: BTreeMap<&'static str, &'static dyn AnySetting>,
+}
+
+#[async_trait]
+impl Step for SettingStep {
+    type Operand = &'static dyn AnySetting;
+    type Collection = ControllerCollection<Self>;
+
+    const NAME: &'static str = "Setting";
+
+    fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            settings: BTreeMap::default(),
+        }
+    }
+
+    fn operand_count(&self) -> usize {
+        self.settings.len()
+    }
+
+    fn append(&mut self, setting: &'static dyn AnySetting) {
+        self.settings.insert(setting.name(), setting);
+    }
+
+    async fn execute<'a>(self, args: StepArgs<'a, Self>) -> serenity::Result<()> {
+        let storage = Storage::get(&args.client.data).await;
+        let repository = storage.repository::<SettingsRepository>(self.controller)?;
+        let mut values = HashMap::new();
+
+        for (name, value) in repository.all().await.map_err(serenity::Error::from)? {
+            let setting = match self.settings.get(name.as_str()) {
+                Some(setting) => *setting,
+                None => continue,
+            };
+
+            // Stored values that no longer fit, because the setting changed, are
+            // kept in storage but ignored, so they aren't lost to a bad deploy.
+            match setting.check(&value) {
+                Ok(()) => {
+                    values.insert(setting.name(), value);
+                }
+                Err(e) => {
+                    warn!(%name, controller = %self.controller, %e, "ignoring stored setting")
+                }
+            }
+        }
+
+        let settings = ControllerSettings {
+            repository,
+            settings: self.settings,
+            values,
+        };
+
+        Settings::get(&args.client.data)
+            .await
+            .insert(self.controller, settings);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
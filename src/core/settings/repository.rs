@@ -0,0 +1,77 @@
+use crate::core::storage::{Database, Migration, Repository, StorageError};
+use chrono::Utc;
+use rusqlite::params;
+use serde_json::Value;
+use serenity::model::id::UserId;
+
+/// The values of the settings a controller has changed from their defaults.
+#[derive(Debug, Clone)]
+pub struct SettingsRepository(Database);
+
+impl Repository for SettingsRepository {
+    const MIGRATIONS: &'static [Migration] = &[Migration {
+        name: "settings_create",
+        sql: "CREATE TABLE settings (
+                  name TEXT PRIMARY KEY,
+                  value TEXT NOT NULL,
+                  updated_by INTEGER NOT NULL,
+                  updated_at TEXT NOT NULL
+              );",
+    }];
+
+    fn new(database: Database) -> Self {
+        Self(database)
+    }
+}
+
+impl SettingsRepository {
+    /// Every stored value, by the name of its setting. Values that aren't valid
+    /// JSON are left out, along with their setting.
+    pub async fn all(&self) -> Result<Vec<(String, Value)>, StorageError> {
+        let rows = self
+            .0
+            .call(|c| {
+                c.prepare("SELECT name, value FROM settings")?
+                    .query_map([], |row| Ok((row.get(0)?, row.get::<_, String>(1)?)))?
+                    .collect::<Result<Vec<(String, String)>, _>>()
+            })
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, serde_json::from_str(&value).ok()?)))
+            .collect())
+    }
+
+    /// Stores the value of a setting, replacing any previous one.
+    pub async fn set(&self, name: &str, value: &Value, user: UserId) -> Result<(), StorageError> {
+        let (name, value) = (name.to_string(), value.to_string());
+
+        self.0
+            .call(move |c| {
+                c.execute(
+                    "INSERT INTO settings (name, value, updated_by, updated_at)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (name) DO UPDATE SET
+                         value = excluded.value,
+                         updated_by = excluded.updated_by,
+                         updated_at = excluded.updated_at",
+                    params![name, value, user.0, Utc::now().to_rfc3339()],
+                )
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes the value of a setting, so it goes back to its default.
+    pub async fn remove(&self, name: &str) -> Result<(), StorageError> {
+        let name = name.to_string();
+
+        self.0
+            .call(move |c| c.execute("DELETE FROM settings WHERE name = ?1", params![name]))
+            .await?;
+
+        Ok(())
+    }
+}
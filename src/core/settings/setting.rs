@@ -0,0 +1,220 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use serenity::model::id::{ChannelId, RoleId};
+use std::fmt;
+
+/// A typed setting that a feature reads from the handlers of a controller, such
+/// as the channel it logs to. Declared as a constant and registered with
+/// `Setup::add_setting` under every controller that uses it:
+///
+/// ```ignore
+/// pub const WELCOME_MESSAGE: Setting<String> = Setting::new(
+///     "welcome-message",
+///     "The message new members are greeted with.",
+///     || "Welcome!".into(),
+/// )
+/// .validate(|message| match message.len() {
+///     1..=2000 => Ok(()),
+///     _ => Err("Messages must be between 1 and 2000 characters.".into()),
+/// });
+/// ```
+pub struct Setting<T> {
+    /// The name of the setting, as picked in `/config`, in kebab case.
+    pub name: &'static str,
+
+    /// What the setting does, shown by `/config view`.
+    pub description: &'static str,
+
+    default: fn() -> T,
+    validate: fn(&T) -> Result<(), String>,
+}
+
+impl<T> fmt::Debug for Setting<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Setting")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: SettingValue> Setting<T> {
+    /// Creates a setting that accepts any value of its type.
+    pub const fn new(name: &'static str, description: &'static str, default: fn() -> T) -> Self {
+        Self {
+            name,
+            description,
+            default,
+            validate: accept::<T>,
+        }
+    }
+
+    /// Sets the check values must pass before they're stored, which returns the
+    /// message shown to the user if they don't.
+    pub const fn validate(self, validate: fn(&T) -> Result<(), String>) -> Self {
+        Self { validate, ..self }
+    }
+
+    /// The value of the setting in a controller that hasn't changed it.
+    pub fn default_value(&self) -> T {
+        (self.default)()
+    }
+
+    /// Reads a stored value, which may have been stored by an older version of the
+    /// setting, so it's validated again.
+    pub(super) fn decode(&self, value: &Value) -> Result<T, String> {
+        let value = T::deserialize(value).map_err(|e| e.to_string())?;
+        (self.validate)(&value)?;
+        Ok(value)
+    }
+}
+
+fn accept<T>(_: &T) -> Result<(), String> {
+    Ok(())
+}
+
+/// A setting with its type erased, so settings of different types can be
+/// registered together. Values are passed around as the JSON they're stored as.
+pub trait AnySetting: fmt::Debug + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// Parses and validates what a user typed into `/config set`.
+    fn parse(&self, input: &str) -> Result<Value, String>;
+
+    /// Whether a stored value is still valid for the setting.
+    fn check(&self, value: &Value) -> Result<(), String>;
+
+    /// Shows a value in a message, or the default if there's none.
+    fn display(&self, value: Option<&Value>) -> String;
+}
+
+impl<T: SettingValue> AnySetting for Setting<T> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        let value = T::parse(input.trim())?;
+        (self.validate)(&value)?;
+        serde_json::to_value(value).map_err(|e| e.to_string())
+    }
+
+    fn check(&self, value: &Value) -> Result<(), String> {
+        self.decode(value).map(|_| ())
+    }
+
+    fn display(&self, value: Option<&Value>) -> String {
+        value
+            .and_then(|value| self.decode(value).ok())
+            .unwrap_or_else(|| self.default_value())
+            .display()
+    }
+}
+
+/// A type settings can hold. Implemented for flags, integers, text, channels,
+/// roles, and `Option<T>` for settings that can be unset.
+pub trait SettingValue: Serialize + DeserializeOwned + fmt::Debug + Send + Sync + 'static {
+    /// Parses a value as typed by a user, returning the message shown to them if
+    /// it's invalid.
+    fn parse(input: &str) -> Result<Self, String>;
+
+    /// Shows the value in a message.
+    fn display(&self) -> String;
+}
+
+impl SettingValue for bool {
+    fn parse(input: &str) -> Result<Self, String> {
+        match input.to_lowercase().as_str() {
+            "true" | "yes" | "on" | "enabled" => Ok(true),
+            "false" | "no" | "off" | "disabled" => Ok(false),
+            _ => Err(format!("`{input}` isn't on or off.")),
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            true => "on".into(),
+            false => "off".into(),
+        }
+    }
+}
+
+impl SettingValue for i64 {
+    fn parse(input: &str) -> Result<Self, String> {
+        input
+            .parse()
+            .map_err(|_| format!("`{input}` isn't a whole number."))
+    }
+
+    fn display(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl SettingValue for String {
+    fn parse(input: &str) -> Result<Self, String> {
+        Ok(input.to_string())
+    }
+
+    fn display(&self) -> String {
+        format!("`{self}`")
+    }
+}
+
+impl SettingValue for ChannelId {
+    fn parse(input: &str) -> Result<Self, String> {
+        mention(input, "<#")
+            .map(ChannelId)
+            .ok_or_else(|| format!("`{input}` isn't a channel."))
+    }
+
+    fn display(&self) -> String {
+        format!("<#{self}>")
+    }
+}
+
+impl SettingValue for RoleId {
+    fn parse(input: &str) -> Result<Self, String> {
+        mention(input, "<@&")
+            .map(RoleId)
+            .ok_or_else(|| format!("`{input}` isn't a role."))
+    }
+
+    fn display(&self) -> String {
+        format!("<@&{self}>")
+    }
+}
+
+/// Values of optional settings are cleared by setting them to `none`.
+impl<T: SettingValue> SettingValue for Option<T> {
+    fn parse(input: &str) -> Result<Self, String> {
+        match input.eq_ignore_ascii_case("none") {
+            true => Ok(None),
+            false => T::parse(input).map(Some),
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            Some(value) => value.display(),
+            None => "none".into(),
+        }
+    }
+}
+
+/// Reads the ID out of a mention with a given prefix, such as `<#123>`, or a bare
+/// ID, which is what users can copy from Discord.
+fn mention(input: &str, prefix: &str) -> Option<u64> {
+    let id = input
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.strip_suffix('>'))
+        .unwrap_or(input);
+
+    id.parse().ok()
+}
@@ -0,0 +1,163 @@
+use super::{Setting, Settings, SettingsRepository};
+use crate::core::{
+    controller::POKECOM,
+    storage::Storage,
+    testing::{FakeGuild, FakeMember, Harness},
+};
+use serde_json::json;
+use serenity::model::id::ChannelId;
+
+const STAFF: u64 = 10;
+const MOD: u64 = 100;
+
+const LOG_CHANNEL: Setting<Option<ChannelId>> =
+    Setting::new("log-channel", "Where moderation is logged.", || None);
+
+const GREETING: Setting<String> =
+    Setting::new("greeting", "What new members are greeted with.", || {
+        "Welcome!".into()
+    })
+    .validate(|greeting| match greeting.len() {
+        1..=20 => Ok(()),
+        _ => Err("Greetings must be between 1 and 20 characters.".into()),
+    });
+
+async fn harness() -> Harness {
+    let guild = FakeGuild::of(POKECOM)
+        .staff_role(STAFF)
+        .member(FakeMember::new(MOD, "mod").role(STAFF));
+
+    Harness::builder()
+        .guild(guild)
+        .start_with(|setup| {
+            setup
+                .add_setting(POKECOM, &LOG_CHANNEL)
+                .add_setting(POKECOM, &GREETING)
+        })
+        .await
+}
+
+async fn settings(harness: &Harness) -> Settings {
+    Settings::get(harness.data()).await
+}
+
+#[tokio::test]
+async fn reads_defaults_until_changed() {
+    let harness = harness().await;
+
+    assert_eq!(
+        settings(&harness).await.read(POKECOM, &GREETING),
+        "Welcome!"
+    );
+    assert_eq!(settings(&harness).await.read(POKECOM, &LOG_CHANNEL), None);
+}
+
+#[tokio::test]
+async fn changes_settings_through_config() {
+    let harness = harness().await;
+    let options = json!({ "setting": "log-channel", "value": "<#42>" });
+    let outcome = harness.user(MOD).command("/config set", options).await;
+
+    assert_eq!(outcome.reply().content(), "Set `log-channel` to <#42>.");
+    assert_eq!(
+        settings(&harness).await.read(POKECOM, &LOG_CHANNEL),
+        Some(ChannelId(42)),
+    );
+
+    let stored = Storage::get(harness.data())
+        .await
+        .repository::<SettingsRepository>(POKECOM)
+        .unwrap()
+        .all()
+        .await
+        .unwrap();
+
+    assert_eq!(stored, [("log-channel".to_string(), json!("42"))]);
+}
+
+#[tokio::test]
+async fn refuses_invalid_values() {
+    let harness = harness().await;
+    let moderator = harness.user(MOD);
+
+    let options = json!({ "setting": "greeting", "value": "a".repeat(21) });
+    let outcome = moderator.command("/config set", options).await;
+
+    assert!(outcome.reply().is_ephemeral());
+    assert_eq!(
+        outcome.reply().description(),
+        "Greetings must be between 1 and 20 characters.",
+    );
+
+    let options = json!({ "setting": "log-channel", "value": "general" });
+    let outcome = moderator.command("/config set", options).await;
+
+    assert_eq!(outcome.reply().description(), "`general` isn't a channel.");
+
+    let options = json!({ "setting": "nope", "value": "on" });
+    let outcome = moderator.command("/config set", options).await;
+
+    assert_eq!(
+        outcome.reply().description(),
+        "There's no setting called `nope`."
+    );
+    assert_eq!(
+        settings(&harness).await.read(POKECOM, &GREETING),
+        "Welcome!"
+    );
+}
+
+#[tokio::test]
+async fn resets_settings_to_their_defaults() {
+    let harness = harness().await;
+    let moderator = harness.user(MOD);
+
+    let options = json!({ "setting": "greeting", "value": "Hi!" });
+    moderator.command("/config set", options).await;
+    assert_eq!(settings(&harness).await.read(POKECOM, &GREETING), "Hi!");
+
+    let options = json!({ "setting": "greeting" });
+    let outcome = moderator.command("/config reset", options).await;
+
+    assert_eq!(outcome.reply().content(), "Reset `greeting` to `Welcome!`.");
+    assert_eq!(
+        settings(&harness).await.read(POKECOM, &GREETING),
+        "Welcome!"
+    );
+}
+
+#[tokio::test]
+async fn lists_settings_with_their_values() {
+    let harness = harness().await;
+    let moderator = harness.user(MOD);
+
+    let options = json!({ "setting": "greeting", "value": "Hi!" });
+    moderator.command("/config set", options).await;
+
+    let outcome = moderator.command("/config view", json!({})).await;
+    let fields = &outcome.reply().embeds()[0]["fields"];
+
+    assert!(outcome.reply().is_ephemeral());
+    assert_eq!(fields[0]["name"], "`greeting`");
+    assert_eq!(
+        fields[0]["value"],
+        "What new members are greeted with.\n`Hi!`"
+    );
+    assert_eq!(fields[1]["name"], "`log-channel`");
+    assert_eq!(
+        fields[1]["value"],
+        "Where moderation is logged.\nnone (default)"
+    );
+}
+
+#[tokio::test]
+async fn suggests_setting_names() {
+    let harness = harness().await;
+    let options = json!({ "setting": "LOG" });
+    let outcome = harness
+        .user(MOD)
+        .autocomplete("/config reset", options, "setting")
+        .await;
+
+    assert_eq!(outcome.suggestions(), ["log-channel"]);
+}
@@ -1,14 +1,28 @@
 use crate::core::{
-    controller::Controller,
+    api::DiscordApi,
+    command::{CommandRoute, CommandStep, Handler, MessageCommand, UserCommand},
+    component::{Component, ComponentRoute, ComponentStep},
+    controller::{Controller, GLOBAL},
+    feature::{Feature, FeatureRepository, FeatureStep, Features},
+    help,
     init::{Init, InitStep},
+    job::{self, Job, JobStep},
+    modal::{ModalHandler, ModalRoute, ModalStep},
+    settings::{Config, Setting, SettingStep, SettingValue, SettingsRepository},
+    storage::{MigrationStep, Repository},
+    task::{Task, TaskStep},
 };
+use serde_json::Value;
 use serenity::Client;
+use std::collections::BTreeMap;
 use tokio::try_join;
 
+mod plan;
 mod scope;
 mod skip;
 mod step;
 
+pub use plan::*;
 pub use scope::*;
 pub use skip::*;
 pub use step::*;
@@ -17,15 +31,45 @@ pub use step::*;
 /// have been registered, and manages their execution.
 #[derive(Debug)]
 pub struct Setup {
+    commands: <CommandStep as Step>::Collection,
+    components: <ComponentStep as Step>::Collection,
+    features: <FeatureStep as Step>::Collection,
     inits: <InitStep as Step>::Collection,
+    jobs: <JobStep as Step>::Collection,
+    migrations: <MigrationStep as Step>::Collection,
+    modals: <ModalStep as Step>::Collection,
+    settings: <SettingStep as Step>::Collection,
+    tasks: <TaskStep as Step>::Collection,
+
+    /// The feature being installed by `add_feature`, which everything registered
+    /// meanwhile belongs to.
+    feature: Option<&'static Feature>,
 }
 
 impl Setup {
-    /// Creates a builder for the setup process.
+    /// Creates a builder for the setup process, with the built-in
+    /// job poller and `/help` already registered.
     pub fn new() -> Self {
-        Self {
+        let mut setup = Self {
+            commands: CommandStep::collection(),
+            components: ComponentStep::collection(),
+            features: FeatureStep::collection(),
             inits: InitStep::collection(),
-        }
+            jobs: JobStep::collection(),
+            migrations: MigrationStep::collection(),
+            modals: ModalStep::collection(),
+            settings: SettingStep::collection(),
+            tasks: TaskStep::collection(),
+            feature: None,
+        };
+
+        // The job step under `GLOBAL` loads the `JobQueue`, so it always exists,
+        // even if no jobs are registered.
+        setup.jobs.factory(GLOBAL);
+
+        setup
+            .add_task(GLOBAL, job::poll_jobs)
+            .add_from(help::installer)
     }
 
     /// Passes the setup builder to a callback which can customize
@@ -36,22 +80,242 @@ impl Setup {
         f(self)
     }
 
+    /// Passes the setup builder to a callback like `add_from`, making everything
+    /// it registers belong to a feature, which staff can turn on and off per
+    /// guild with `/features`. See `Feature`.
+    ///
+    /// While the feature is off, its commands, components and modals are refused,
+    /// its tasks skip their runs and its jobs stay queued. Its initializers only
+    /// run if it's on when setup runs.
+    pub fn add_feature(mut self, feature: &'static Feature, f: impl FnOnce(Self) -> Self) -> Self {
+        let outer = self.feature.replace(feature);
+        let mut setup = f(self);
+
+        setup.feature = outer;
+        setup
+    }
+
+    /// Registers a command under a given controller, which is uploaded to the
+    /// controller's guild and routed to the command's `Handler`.
+    pub fn add_command<C: Handler + 'static>(mut self, controller: Controller) -> Self {
+        let feature = self.track_feature(controller);
+
+        self.commands
+            .factory(controller)
+            .append(CommandRoute::of::<C>().in_feature(feature));
+        self
+    }
+
+    /// Registers a user context menu command under a given controller.
+    pub fn add_user_command<C: UserCommand>(mut self, controller: Controller) -> Self {
+        let feature = self.track_feature(controller);
+
+        self.commands
+            .factory(controller)
+            .append(CommandRoute::user::<C>().in_feature(feature));
+        self
+    }
+
+    /// Registers a message context menu command under a given controller.
+    pub fn add_message_command<C: MessageCommand>(mut self, controller: Controller) -> Self {
+        let feature = self.track_feature(controller);
+
+        self.commands
+            .factory(controller)
+            .append(CommandRoute::message::<C>().in_feature(feature));
+        self
+    }
+
+    /// Registers a button or select menu under a given controller, which handles
+    /// its interactions in the controller's guild. See `Component`.
+    pub fn add_component<C: Component>(mut self, controller: Controller) -> Self {
+        let feature = self.track_feature(controller);
+
+        self.components
+            .factory(controller)
+            .append(ComponentRoute::of::<C>().in_feature(feature));
+        self
+    }
+
+    /// Registers a modal under a given controller, which handles its submissions
+    /// in the controller's guild. See `Modal`.
+    pub fn add_modal<M: ModalHandler>(mut self, controller: Controller) -> Self {
+        let feature = self.track_feature(controller);
+
+        self.modals
+            .factory(controller)
+            .append(ModalRoute::of::<M>().in_feature(feature));
+        self
+    }
+
     /// Registers an initializer under a given controller. See `Init`.
     pub fn add_init(mut self, controller: Controller, init: Init) -> Self {
-        self.inits.factory(controller).append(init);
+        let feature = self.track_feature(controller);
+
+        self.inits
+            .factory(controller)
+            .append(init.in_feature(feature));
+        self
+    }
+
+    /// Registers a persistent job handler under a given controller. See `Job`.
+    pub fn add_job(mut self, controller: Controller, job: Job) -> Self {
+        let feature = self.track_feature(controller);
+
+        self.jobs
+            .factory(controller)
+            .append(job.in_feature(feature));
+        self
+    }
+
+    /// Registers a repository under a given controller, so its migrations are
+    /// applied to the controller's database and it can be opened with
+    /// `Storage::repository`. See `Repository`.
+    pub fn add_repository<R: Repository>(mut self, controller: Controller) -> Self {
+        self.append_migrations::<R>(controller);
+        self
+    }
+
+    /// Registers a setting under a given controller, along with the storage its
+    /// values are kept in and `/config` to change them. See `Setting`.
+    pub fn add_setting<T: SettingValue>(
+        mut self,
+        controller: Controller,
+        setting: &'static Setting<T>,
+    ) -> Self {
+        self.settings.factory(controller).append(setting);
+        self.append_migrations::<SettingsRepository>(controller);
+
+        // Added directly, since `/config` never belongs to a feature.
+        self.commands
+            .factory(controller)
+            .append(CommandRoute::of::<Config>());
+        self
+    }
+
+    /// Registers a background task under a given controller. See `Task`.
+    pub fn add_task(mut self, controller: Controller, task: Task) -> Self {
+        let feature = self.track_feature(controller);
+
+        self.tasks
+            .factory(controller)
+            .append(task.in_feature(feature));
         self
     }
 
+    /// Renders the JSON of the commands registered under each controller, exactly
+    /// as setup would upload them, by controller key. Controllers without any
+    /// commands are left out.
+    pub fn command_data(&self) -> BTreeMap<&'static str, Vec<Value>> {
+        (&self.commands)
+            .into_iter()
+            .map(|(controller, step)| (controller.key(), step.data()))
+            .collect()
+    }
+
+    /// Lists the setup steps that `setup` would execute, in order, without
+    /// executing any of them. Steps of controllers that turn out to be
+    /// disconnected are listed too, since that takes Discord to know.
+    pub fn plan(&self) -> Vec<PlannedStep> {
+        let mut plan = Vec::new();
+
+        Self::plan_step(&self.migrations, &mut plan);
+        Self::plan_step(&self.settings, &mut plan);
+        Self::plan_step(&self.features, &mut plan);
+        Self::plan_step(&self.commands, &mut plan);
+        Self::plan_step(&self.components, &mut plan);
+        Self::plan_step(&self.inits, &mut plan);
+        Self::plan_step(&self.jobs, &mut plan);
+        Self::plan_step(&self.modals, &mut plan);
+        Self::plan_step(&self.tasks, &mut plan);
+
+        plan
+    }
+
+    /// Uploads the commands registered under each controller, skipping every
+    /// other setup step. Used to deploy commands without running the bot.
+    pub async fn sync_commands(
+        self,
+        client: &Client,
+        api: &dyn DiscordApi,
+    ) -> serenity::Result<()> {
+        Self::setup_step::<CommandStep>(self.commands, client, api).await
+    }
+
     /// Executes all setup steps and drops the arena. Calling this function
     /// marks the end of the setup process.
     ///
     /// If setup steps need their data to persist somehow (either in the same
     /// form or transformed by setup) they can stick it on `Client::data`,
     /// which is passed mutably to `Step::execute`.
-    #[instrument(skip(client))]
     pub async fn setup(self, client: &Client) -> serenity::Result<()> {
+        self.setup_with(client, client.cache_and_http.http.as_ref())
+            .await
+    }
+
+    /// Executes all setup steps like `setup`, but talking to Discord through a
+    /// given API rather than the client's HTTP client.
+    #[instrument(skip_all)]
+    pub async fn setup_with(self, client: &Client, api: &dyn DiscordApi) -> serenity::Result<()> {
         info!("Starting setup!");
-        try_join!(Self::setup_step::<InitStep>(self.inits, client)).map(|_| ())
+
+        // Migrations are applied first, so every other step can use storage, then
+        // settings and features are loaded so every other step reads their stored
+        // values.
+        Self::setup_step::<MigrationStep>(self.migrations, client, api).await?;
+        Self::setup_step::<SettingStep>(self.settings, client, api).await?;
+        Self::setup_step::<FeatureStep>(self.features, client, api).await?;
+
+        try_join!(
+            Self::setup_step::<CommandStep>(self.commands, client, api),
+            Self::setup_step::<ComponentStep>(self.components, client, api),
+            Self::setup_step::<InitStep>(self.inits, client, api),
+            Self::setup_step::<JobStep>(self.jobs, client, api),
+            Self::setup_step::<ModalStep>(self.modals, client, api),
+        )?;
+
+        // Tasks are started last, once everything they may depend on is set up.
+        Self::setup_step::<TaskStep>(self.tasks, client, api).await
+    }
+
+    /// Appends the migrations of a repository under a controller.
+    fn append_migrations<R: Repository>(&mut self, controller: Controller) {
+        let step = self.migrations.factory(controller);
+
+        for migration in R::MIGRATIONS {
+            step.append(*migration);
+        }
+    }
+
+    /// Registers the feature being installed under a controller, if there is
+    /// one, along with the storage its toggles are kept in and `/features` to
+    /// flip them. Returns the feature, for what is being registered.
+    fn track_feature(&mut self, controller: Controller) -> Option<&'static Feature> {
+        let feature = self.feature?;
+
+        self.features.factory(controller).append(feature);
+        self.append_migrations::<FeatureRepository>(controller);
+        self.commands
+            .factory(controller)
+            .append(CommandRoute::of::<Features>());
+
+        Some(feature)
+    }
+
+    /// Lists the steps of a given type, with controllers in declaration order.
+    fn plan_step<S: Step<Collection = ControllerCollection<S>>>(
+        collection: &ControllerCollection<S>,
+        plan: &mut Vec<PlannedStep>,
+    ) {
+        let mut steps = collection.into_iter().collect::<Vec<_>>();
+        steps.sort_by_key(|(controller, _)| Controller::ALL.iter().position(|c| c == *controller));
+
+        for (controller, step) in steps {
+            plan.push(PlannedStep {
+                name: step.name_in(controller),
+                operands: step.operand_count(),
+            });
+        }
     }
 
     /// Runs a given type of setup step.
@@ -61,20 +325,15 @@ impl Setup {
     async fn setup_step<S: Step>(
         collection: S::Collection,
         client: &Client,
+        api: &dyn DiscordApi,
     ) -> serenity::Result<()> {
-        let http = client.cache_and_http.http.as_ref();
-
         for (scope, step) in collection {
-            if scope.try_skip(http).await.should_skip() {
+            if scope.try_skip(api).await.should_skip() {
                 continue;
             }
 
             let name = step.name_in(&scope);
-            let args = StepArgs {
-                scope,
-                client,
-                http,
-            };
+            let args = StepArgs { scope, client, api };
 
             step.execute(args).await?;
 
@@ -84,3 +343,6 @@ impl Setup {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests;
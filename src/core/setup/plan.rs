@@ -0,0 +1,17 @@
+use std::fmt;
+
+/// A setup step as it would be executed, listed by `Setup::plan`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedStep {
+    /// The name of the step and its scope, as in the setup logs.
+    pub name: String,
+
+    /// How many operands were registered with the step.
+    pub operands: usize,
+}
+
+impl fmt::Display for PlannedStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.operands)
+    }
+}
@@ -1,16 +1,18 @@
 use super::super::{Skip, Step};
 use super::{Collection, Scope, __seal_collection, __seal_scope};
-use crate::core::controller::Controller;
-use serenity::http::client::Http;
+use crate::core::{api::DiscordApi, controller::Controller};
 use std::borrow::Cow;
-use std::collections::{hash_map::IntoIter, HashMap};
+use std::collections::{
+    hash_map::{IntoIter, Iter},
+    HashMap,
+};
 use std::fmt;
 
 #[sealed]
 #[async_trait]
 impl Scope for Controller {
-    async fn try_skip(&self, http: &Http) -> Skip {
-        if self.is_connected(http).await {
+    async fn try_skip(&self, api: &dyn DiscordApi) -> Skip {
+        if self.is_connected(api).await {
             return Skip::Proceed;
         }
 
@@ -52,6 +54,15 @@ impl<S: Step> IntoIterator for ControllerCollection<S> {
     }
 }
 
+impl<'a, S: Step> IntoIterator for &'a ControllerCollection<S> {
+    type Item = (&'a Controller, &'a S);
+    type IntoIter = Iter<'a, Controller, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
 impl<S: Step> fmt::Debug for ControllerCollection<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (controller, step) in &self.0 {
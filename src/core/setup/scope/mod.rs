@@ -1,5 +1,5 @@
 use super::{Skip, Step};
-use serenity::http::client::Http;
+use crate::core::api::DiscordApi;
 use std::borrow::Cow;
 use std::hash::Hash;
 
@@ -19,7 +19,7 @@ pub trait Scope: PartialEq + Eq + Hash + Send + Sync + 'static {
     /// should be skipped during the setup process. Unconditionally
     /// returns `Proceed` by default, but `Controller` overrides it
     /// by checking if the associated guild is connected.
-    async fn try_skip(&self, _http: &Http) -> Skip {
+    async fn try_skip(&self, _api: &dyn DiscordApi) -> Skip {
         Skip::Proceed
     }
 
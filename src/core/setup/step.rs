@@ -1,6 +1,7 @@
 use super::{Collection, Scope};
+use crate::core::api::DiscordApi;
 use custom_debug::Debug;
-use serenity::{client::Client, http::Http};
+use serenity::client::Client;
 
 /// A `Step` is a type-specific component of the setup process. Implementers
 /// are responsible for the setup process of a specific type of operand, such as
@@ -77,9 +78,10 @@ pub struct StepArgs<'a, S: Step> {
     #[debug(skip)]
     pub client: &'a Client,
 
-    /// The Serenity HTTP client. Can be retrieved from `client` but
-    /// separated here because it's very commonly used.
-    pub http: &'a Http,
+    /// The Discord API to set up against. Normally the HTTP client of
+    /// `client`, but tests swap in a `FakeApi`.
+    #[debug(skip)]
+    pub api: &'a dyn DiscordApi,
 
     /// The registration scope of the setup step. This is either `()`,
     /// in which case you don't care about it, or a `Controller`, in
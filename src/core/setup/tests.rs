@@ -0,0 +1,269 @@
+use super::Setup;
+use crate::core::{
+    api::{Call, Failure, FakeApi},
+    command::{CommandContext, CommandResult, Dispatcher, Handler},
+    component::{Component, ComponentContext},
+    config::Config,
+    controller::{Controller, GLOBAL, POKECOM},
+    task::{Schedule, Task, TaskArgs},
+};
+use porygon_macros::{task, Command};
+use serde::{Deserialize, Serialize};
+use serenity::{
+    client::{Client, ClientBuilder},
+    http::HttpBuilder,
+    model::id::GuildId,
+    prelude::GatewayIntents,
+};
+use std::{env, sync::Arc};
+
+/// Replies with pong.
+#[derive(Command)]
+struct Ping;
+
+#[async_trait]
+impl Handler for Ping {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.reply("Pong!").await?;
+        Ok(())
+    }
+}
+
+/// Dismisses a message.
+#[derive(Serialize, Deserialize)]
+struct Dismiss;
+
+/// Closes a message, with the same namespace as `Dismiss`.
+#[derive(Serialize, Deserialize)]
+struct Close;
+
+#[async_trait]
+impl Component for Dismiss {
+    const NAMESPACE: &'static str = "dismiss";
+
+    async fn handle(self, _: &ComponentContext) -> CommandResult {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Component for Close {
+    const NAMESPACE: &'static str = "dismiss";
+
+    async fn handle(self, _: &ComponentContext) -> CommandResult {
+        Ok(())
+    }
+}
+
+#[task(every = "1h")]
+async fn hourly(_: &TaskArgs) {}
+
+/// A client that can't reach Discord. Its HTTP client goes through a proxy that
+/// refuses connections, so building it doesn't wait on the network.
+async fn client() -> Client {
+    let http = HttpBuilder::new("token")
+        .proxy("http://127.0.0.1:9")
+        .expect("the proxy URL is valid")
+        .build();
+
+    let mut config = Config::default();
+    config.storage.data_dir =
+        env::temp_dir().join(format!("porygon-setup-{}", rand::random::<u64>()));
+
+    ClientBuilder::new_with_http(http, GatewayIntents::empty())
+        .type_map_insert::<Config>(Arc::new(config))
+        .await
+        .expect("building a client doesn't need Discord")
+}
+
+/// Where a controller's commands are uploaded to.
+fn target(controller: Controller) -> Option<GuildId> {
+    controller.upload_iface().guild_id()
+}
+
+/// A fake API where the bot is in the guild of `POKECOM`.
+fn connected() -> FakeApi {
+    FakeApi::new().with_guild(target(POKECOM).unwrap())
+}
+
+fn status(error: &serenity::Error) -> Option<u16> {
+    match error {
+        serenity::Error::Http(error) => error.status_code().map(|status| status.as_u16()),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn uploads_commands_under_each_controller() {
+    let (client, api) = (client().await, connected());
+
+    Setup::new()
+        .add_command::<Ping>(POKECOM)
+        .setup_with(&client, &api)
+        .await
+        .unwrap();
+
+    let global = api.commands(target(GLOBAL));
+    let guild = api.commands(target(POKECOM));
+
+    assert!(global.contains_key("help"));
+    assert!(global.contains_key("help-export"));
+    assert_eq!(guild["ping"]["description"], "Replies with pong.");
+}
+
+#[tokio::test]
+async fn records_uploaded_commands_with_the_dispatcher() {
+    let (client, api) = (client().await, connected());
+
+    Setup::new()
+        .add_command::<Ping>(POKECOM)
+        .setup_with(&client, &api)
+        .await
+        .unwrap();
+
+    let mut routes = Dispatcher::get(&client.data)
+        .await
+        .routes()
+        .await
+        .into_iter()
+        .map(|(controller, route)| (controller.key(), route.name))
+        .collect::<Vec<_>>();
+
+    routes.sort_unstable();
+
+    assert_eq!(
+        routes,
+        [
+            ("GLOBAL", "help"),
+            ("GLOBAL", "help-export"),
+            ("POKECOM", "ping"),
+        ],
+    );
+}
+
+#[tokio::test]
+async fn fails_when_an_upload_fails() {
+    let (client, api) = (client().await, connected());
+
+    api.fail(
+        |call| matches!(call, Call::CreateCommand(_, name) if name == "ping"),
+        Failure::Status(500),
+    );
+
+    let error = Setup::new()
+        .add_command::<Ping>(POKECOM)
+        .setup_with(&client, &api)
+        .await
+        .unwrap_err();
+
+    assert_eq!(status(&error), Some(500));
+    assert!(!api.commands(target(POKECOM)).contains_key("ping"));
+}
+
+#[tokio::test]
+async fn surfaces_rate_limits() {
+    let (client, api) = (client().await, connected());
+
+    api.fail(
+        |call| matches!(call, Call::CreateCommand(..)),
+        Failure::RateLimited { retry_after: 1.5 },
+    );
+
+    let error = Setup::new().setup_with(&client, &api).await.unwrap_err();
+
+    assert_eq!(status(&error), Some(429));
+}
+
+#[cfg(not(feature = "staging"))]
+#[tokio::test]
+async fn skips_controllers_whose_guild_is_unavailable() {
+    let (client, api) = (client().await, FakeApi::new());
+
+    Setup::new()
+        .add_command::<Ping>(POKECOM)
+        .setup_with(&client, &api)
+        .await
+        .unwrap();
+
+    assert!(api
+        .calls()
+        .contains(&Call::GetGuild(target(POKECOM).unwrap())));
+    assert!(api.commands(target(POKECOM)).is_empty());
+    assert!(api.commands(target(GLOBAL)).contains_key("help"));
+}
+
+#[cfg(feature = "staging")]
+#[tokio::test]
+async fn uploads_everything_to_the_staging_guild() {
+    let (client, api) = (client().await, connected());
+
+    Setup::new()
+        .add_command::<Ping>(POKECOM)
+        .setup_with(&client, &api)
+        .await
+        .unwrap();
+
+    let uploads = api
+        .calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            Call::CreateCommand(guild, _) => Some(guild),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(uploads.len(), 3);
+    assert!(uploads.iter().all(|guild| *guild == target(POKECOM)));
+    assert_eq!(target(GLOBAL), target(POKECOM));
+}
+
+#[tokio::test]
+async fn rejects_components_with_the_same_namespace() {
+    let (client, api) = (client().await, connected());
+
+    let error = Setup::new()
+        .add_component::<Dismiss>(POKECOM)
+        .add_component::<Close>(POKECOM)
+        .setup_with(&client, &api)
+        .await
+        .unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains("both have the namespace `dismiss`"));
+}
+
+#[tokio::test]
+async fn rejects_tasks_with_the_same_name() {
+    let (client, api) = (client().await, connected());
+
+    let error = Setup::new()
+        .add_task(POKECOM, hourly)
+        .add_task(POKECOM, hourly)
+        .setup_with(&client, &api)
+        .await
+        .unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains("More than one task is called `hourly`"));
+}
+
+#[tokio::test]
+async fn rejects_tasks_with_invalid_cron_expressions() {
+    let (client, api) = (client().await, connected());
+    let task = Task {
+        schedule: Schedule::Cron("whenever"),
+        ..hourly
+    };
+
+    let error = Setup::new()
+        .add_task(POKECOM, task)
+        .setup_with(&client, &api)
+        .await
+        .unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains("Task `hourly` has an invalid cron expression"));
+}
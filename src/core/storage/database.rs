@@ -0,0 +1,126 @@
+use super::{Migration, StorageError};
+use crate::core::controller::Controller;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// How long a query waits for another connection to release a lock, such as
+/// one opened with the `sqlite3` shell while the bot is running.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The database of a controller. Cheap to clone, with every clone sharing the
+/// same connection.
+#[derive(Debug, Clone)]
+pub struct Database {
+    controller: Controller,
+    connection: Arc<Mutex<Connection>>,
+
+    /// The names of the migrations that have been applied to the database.
+    applied: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Database {
+    /// Opens the database at a path, creating it and its directory if needed.
+    pub(super) fn open(controller: Controller, path: &Path) -> Result<Self, StorageError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(StorageError::Io)?;
+        }
+
+        let connection = Connection::open(path)?;
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA foreign_keys = ON;
+             CREATE TABLE IF NOT EXISTS _migrations (
+                 name TEXT PRIMARY KEY,
+                 applied_at TEXT NOT NULL
+             );",
+        )?;
+
+        let applied = connection
+            .prepare("SELECT name FROM _migrations")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            controller,
+            connection: Arc::new(Mutex::new(connection)),
+            applied: Arc::new(Mutex::new(applied)),
+        })
+    }
+
+    /// Runs a function against the connection on a blocking thread, so queries
+    /// don't hold up the runtime. Calls are run one at a time.
+    pub async fn call<T, F>(&self, f: F) -> Result<T, StorageError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let connection = self.connection.clone();
+        let result = tokio::task::spawn_blocking(move || f(&mut lock(&connection))).await;
+
+        match result {
+            Ok(result) => result.map_err(Into::into),
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    /// Applies the migrations that haven't been applied yet, in order. Each is
+    /// applied in its own transaction, so one that fails leaves no trace, and
+    /// none after it are attempted.
+    pub async fn migrate(&self, migrations: Vec<Migration>) -> Result<(), StorageError> {
+        let pending = {
+            let applied = lock(&self.applied);
+            migrations
+                .into_iter()
+                .filter(|migration| !applied.contains(migration.name))
+                .collect::<Vec<_>>()
+        };
+
+        for migration in pending {
+            let result = self
+                .call(move |connection| {
+                    let transaction = connection.transaction()?;
+                    transaction.execute_batch(migration.sql)?;
+                    transaction.execute(
+                        "INSERT INTO _migrations (name, applied_at) VALUES (?1, ?2)",
+                        params![migration.name, Utc::now().to_rfc3339()],
+                    )?;
+                    transaction.commit()
+                })
+                .await;
+
+            match result {
+                Ok(()) => {
+                    info!(name = migration.name, controller = %self.controller, "applied migration");
+                    lock(&self.applied).insert(migration.name.to_string());
+                }
+                Err(StorageError::Sqlite(error)) => {
+                    return Err(StorageError::Migration {
+                        name: migration.name,
+                        error,
+                    })
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether all of a set of migrations have been applied.
+    pub fn has_applied(&self, migrations: &[Migration]) -> bool {
+        let applied = lock(&self.applied);
+        migrations
+            .iter()
+            .all(|migration| applied.contains(migration.name))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
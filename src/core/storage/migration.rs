@@ -0,0 +1,72 @@
+use super::{Database, Storage};
+use crate::core::{
+    controller::Controller,
+    setup::{ControllerCollection, Step, StepArgs},
+};
+
+/// A change to the schema of a database, applied once and recorded so it's never
+/// applied again. Once deployed, a migration must never be edited; add another
+/// one after it instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    /// Identifies the migration, so must be unique across every repository.
+    /// Conventionally starts with the table it changes, such as `tags_create`.
+    pub name: &'static str,
+
+    /// The SQL to run, which may hold several statements.
+    pub sql: &'static str,
+}
+
+/// A typed API over the tables of a feature, in the database of one controller.
+/// Implementers wrap the `Database` they're given, and expose methods that run
+/// their queries through `Database::call`.
+pub trait Repository: Sized + Send + Sync + 'static {
+    /// The migrations creating and updating the tables of the repository, in
+    /// the order they're applied.
+    const MIGRATIONS: &'static [Migration];
+
+    fn new(database: Database) -> Self;
+}
+
+/// Setup step that applies the migrations of the repositories registered under
+/// a controller to its database. Runs before every other step, so that inits
+/// can use storage.
+#[derive(Debug)]
+pub struct MigrationStep {
+    controller: Controller,
+    migrations: Vec<Migration>,
+}
+
+#[async_trait]
+impl Step for MigrationStep {
+    type Operand = Migration;
+    type Collection = ControllerCollection<Self>;
+
+    const NAME: &'static str = "Migration";
+
+    fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            migrations: Vec::new(),
+        }
+    }
+
+    fn operand_count(&self) -> usize {
+        self.migrations.len()
+    }
+
+    /// Adds a migration, unless a repository registered twice already added it.
+    fn append(&mut self, migration: Migration) {
+        if !self.migrations.contains(&migration) {
+            self.migrations.push(migration);
+        }
+    }
+
+    async fn execute<'a>(self, args: StepArgs<'a, Self>) -> serenity::Result<()> {
+        let storage = Storage::get(&args.client.data).await;
+        let database = storage.database(self.controller)?;
+
+        database.migrate(self.migrations).await?;
+        Ok(())
+    }
+}
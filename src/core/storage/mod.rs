@@ -0,0 +1,143 @@
+//! Durable storage for features.
+//!
+//! Each controller has its own SQLite database in the data directory, such as
+//! `storage/POKECOM.db`, so features registered under different controllers
+//! never see each other's rows. `Storage` lives on the client data and opens
+//! the databases as they're needed.
+//!
+//! Features don't run SQL against a `Database` directly from their handlers.
+//! Instead each defines a `Repository`, a typed API over the tables it owns,
+//! along with the `Migration`s that create them. Registering the repository
+//! under a controller with `Setup::add_repository` runs its migrations during
+//! setup, before anything else, and lets it be opened for that controller with
+//! `Storage::repository`.
+
+mod database;
+mod migration;
+
+pub use database::*;
+pub use migration::*;
+
+use crate::core::{config::Config, controller::Controller};
+use serenity::prelude::{RwLock, TypeMap, TypeMapKey};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::{any, fmt, io};
+
+/// The databases of every controller, opened the first time they're used.
+#[derive(Debug, Clone)]
+pub struct Storage {
+    databases: Arc<Mutex<HashMap<Controller, Database>>>,
+
+    /// The data directory the databases are kept in.
+    dir: PathBuf,
+}
+
+impl Storage {
+    /// Fetches the storage from the client data, creating it if needed.
+    pub async fn get(data: &RwLock<TypeMap>) -> Self {
+        let config = Config::get(data).await;
+
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(|| Self {
+                databases: Arc::default(),
+                dir: config.storage.data_dir.clone(),
+            })
+            .clone()
+    }
+
+    /// The database of a controller, which is created if it doesn't exist yet.
+    pub fn database(&self, controller: Controller) -> Result<Database, StorageError> {
+        let mut databases = self.databases.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(database) = databases.get(&controller) {
+            return Ok(database.clone());
+        }
+
+        let path = self.dir.join(format!("storage/{}.db", controller.key()));
+        let database = Database::open(controller, &path)?;
+
+        databases.insert(controller, database.clone());
+        Ok(database)
+    }
+
+    /// Opens a repository on the database of a controller. Fails if the
+    /// repository wasn't registered under the controller, since its tables may
+    /// not exist.
+    pub fn repository<R: Repository>(&self, controller: Controller) -> Result<R, StorageError> {
+        let database = self.database(controller)?;
+
+        if !database.has_applied(R::MIGRATIONS) {
+            return Err(StorageError::Unregistered {
+                repository: any::type_name::<R>(),
+                controller,
+            });
+        }
+
+        Ok(R::new(database))
+    }
+}
+
+impl TypeMapKey for Storage {
+    type Value = Self;
+}
+
+/// Reasons storage can fail.
+#[derive(Debug)]
+pub enum StorageError {
+    /// The database directory couldn't be created.
+    Io(io::Error),
+
+    Sqlite(rusqlite::Error),
+
+    /// A migration failed to apply, leaving the database as it was before it.
+    Migration {
+        name: &'static str,
+        error: rusqlite::Error,
+    },
+
+    /// A repository was opened for a controller it wasn't registered under.
+    Unregistered {
+        repository: &'static str,
+        controller: Controller,
+    },
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to create the storage directory: {e}"),
+            Self::Sqlite(e) => write!(f, "Storage failed: {e}"),
+            Self::Migration { name, error } => write!(f, "Migration `{name}` failed: {error}"),
+            Self::Unregistered {
+                repository,
+                controller,
+            } => write!(f, "`{repository}` isn't registered under {controller}."),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::Sqlite(error)
+    }
+}
+
+impl From<StorageError> for serenity::Error {
+    /// Serenity can only return its own errors from setup, and failing to store
+    /// something is closest to failing to write a file.
+    fn from(error: StorageError) -> Self {
+        match error {
+            StorageError::Io(e) => Self::Io(e),
+            e => Self::Io(io::Error::other(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
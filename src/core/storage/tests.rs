@@ -0,0 +1,161 @@
+use super::{Database, Migration, Repository, Storage, StorageError};
+use crate::core::{
+    controller::{DUCK_COMMUNISM, POKECOM},
+    testing::{FakeGuild, Harness},
+};
+use rusqlite::params;
+use std::{env, fs, path::PathBuf};
+
+/// A repository of plain text notes.
+struct Notes(Database);
+
+impl Repository for Notes {
+    const MIGRATIONS: &'static [Migration] = &[
+        Migration {
+            name: "notes_create",
+            sql: "CREATE TABLE notes (id INTEGER PRIMARY KEY, text TEXT NOT NULL);",
+        },
+        Migration {
+            name: "notes_add_pinned",
+            sql: "ALTER TABLE notes ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;",
+        },
+    ];
+
+    fn new(database: Database) -> Self {
+        Self(database)
+    }
+}
+
+impl Notes {
+    async fn add(&self, text: &str) -> Result<(), StorageError> {
+        let text = text.to_string();
+        self.0
+            .call(move |c| c.execute("INSERT INTO notes (text) VALUES (?1)", params![text]))
+            .await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<String>, StorageError> {
+        self.0
+            .call(|c| {
+                c.prepare("SELECT text FROM notes ORDER BY id")?
+                    .query_map([], |row| row.get(0))?
+                    .collect()
+            })
+            .await
+    }
+}
+
+/// A database file of its own, removed when dropped.
+struct TempDatabase(PathBuf);
+
+impl TempDatabase {
+    fn new() -> Self {
+        let dir = env::temp_dir().join(format!(
+            "porygon-storage-{}-{}",
+            std::process::id(),
+            rand::random::<u64>(),
+        ));
+
+        Self(dir.join("test.db"))
+    }
+
+    fn open(&self) -> Database {
+        Database::open(POKECOM, &self.0).unwrap()
+    }
+}
+
+impl Drop for TempDatabase {
+    fn drop(&mut self) {
+        if let Some(dir) = self.0.parent() {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+async fn tables(database: &Database) -> Vec<String> {
+    database
+        .call(|c| {
+            c.prepare("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")?
+                .query_map([], |row| row.get(0))?
+                .collect()
+        })
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn applies_migrations_once() {
+    let file = TempDatabase::new();
+    let database = file.open();
+
+    database.migrate(Notes::MIGRATIONS.to_vec()).await.unwrap();
+    Notes::new(database.clone()).add("hello").await.unwrap();
+
+    // Applying them again would fail, since the table already exists.
+    database.migrate(Notes::MIGRATIONS.to_vec()).await.unwrap();
+
+    let reopened = file.open();
+    reopened.migrate(Notes::MIGRATIONS.to_vec()).await.unwrap();
+
+    assert!(reopened.has_applied(Notes::MIGRATIONS));
+    assert_eq!(Notes::new(reopened).list().await.unwrap(), ["hello"]);
+}
+
+#[tokio::test]
+async fn rolls_back_failed_migrations() {
+    let file = TempDatabase::new();
+    let database = file.open();
+
+    let migrations = vec![
+        Migration {
+            name: "broken",
+            sql: "CREATE TABLE broken (id INTEGER); INSERT INTO missing VALUES (1);",
+        },
+        Notes::MIGRATIONS[0],
+    ];
+
+    let error = database.migrate(migrations).await.unwrap_err();
+
+    assert!(matches!(
+        error,
+        StorageError::Migration { name: "broken", .. }
+    ));
+    assert_eq!(tables(&database).await, ["_migrations"]);
+    assert!(!database.has_applied(&Notes::MIGRATIONS[..1]));
+}
+
+#[tokio::test]
+async fn migrates_registered_repositories_during_setup() {
+    let harness = Harness::builder()
+        .guild(FakeGuild::of(POKECOM))
+        .start_with(|setup| setup.add_repository::<Notes>(POKECOM))
+        .await;
+
+    let storage = Storage::get(harness.data()).await;
+    let notes = storage.repository::<Notes>(POKECOM).unwrap();
+
+    notes.add("hello").await.unwrap();
+
+    assert_eq!(notes.list().await.unwrap(), ["hello"]);
+}
+
+#[tokio::test]
+async fn refuses_repositories_registered_under_other_controllers() {
+    let harness = Harness::builder()
+        .guild(FakeGuild::of(POKECOM))
+        .start_with(|setup| setup.add_repository::<Notes>(POKECOM))
+        .await;
+
+    let storage = Storage::get(harness.data()).await;
+    let error = storage.repository::<Notes>(DUCK_COMMUNISM).err().unwrap();
+
+    assert!(matches!(
+        error,
+        StorageError::Unregistered { controller, .. } if controller == DUCK_COMMUNISM
+    ));
+    assert!(tables(&storage.database(DUCK_COMMUNISM).unwrap())
+        .await
+        .iter()
+        .all(|table| table != "notes"));
+}
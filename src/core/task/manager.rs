@@ -0,0 +1,160 @@
+use super::{Schedule, Task, TaskArgs};
+use crate::core::feature;
+use chrono::Utc;
+use rand::{thread_rng, Rng};
+use serenity::prelude::{Mutex, RwLock, TypeMap, TypeMapKey};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+
+/// Owns every running task, and cancels them all on shutdown. Lives on the
+/// client data, where `TaskStep` puts it the first time a task is started.
+#[derive(Debug, Clone, Default)]
+pub struct TaskManager {
+    cancel: CancellationToken,
+    handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl TaskManager {
+    /// Fetches the task manager from the client data, creating it if needed.
+    pub async fn get(data: &RwLock<TypeMap>) -> Self {
+        data.write()
+            .await
+            .entry::<Self>()
+            .or_insert_with(Self::default)
+            .clone()
+    }
+
+    /// Starts running a task in the background.
+    pub async fn spawn(&self, task: Task, args: TaskArgs) {
+        let cancel = self.cancel.clone();
+        let handle = tokio::spawn(run(task, args, cancel));
+
+        self.handles.lock().await.push(handle);
+    }
+
+    /// Cancels every task without waiting for them to stop, for when there's
+    /// nothing to wait from, such as in `Drop`.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Cancels every task and waits for them to stop. A task that is in the
+    /// middle of a run is interrupted at its next `.await`.
+    pub async fn shutdown(&self) {
+        self.cancel();
+
+        for handle in self.handles.lock().await.drain(..) {
+            let _ = handle.await;
+        }
+
+        info!("tasks stopped");
+    }
+}
+
+impl TypeMapKey for TaskManager {
+    type Value = Self;
+}
+
+/// The loop that drives a single task until it's cancelled or its
+/// schedule runs out.
+pub(super) async fn run(task: Task, args: TaskArgs, cancel: CancellationToken) {
+    let mut timer = Timer::new(task.schedule);
+    let mut count = 0u64;
+
+    loop {
+        let wait = tokio::select! {
+            _ = cancel.cancelled() => break,
+            wait = timer.next() => match wait {
+                Some(wait) => wait + jitter(task.jitter),
+                None => break,
+            }
+        };
+
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = time::sleep(wait) => {}
+        }
+
+        // The schedule keeps ticking while the feature is off, so the task picks
+        // up where it would have been once it's turned back on.
+        if !feature::allows(args.data(), args.controller, task.feature).await {
+            debug!(name = task.name, controller = %args.controller, "feature off, skipping run");
+            continue;
+        }
+
+        count += 1;
+
+        let span = info_span!("task", name = task.name, controller = %args.controller, run = count);
+        let exec = task.__exec;
+        let args = args.clone();
+
+        // Each run is its own tokio task so that a panic only fails that run
+        // rather than killing the task for good.
+        let mut handle = tokio::spawn(async move { exec(&args).await }.instrument(span.clone()));
+
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                handle.abort();
+                break;
+            }
+            result = &mut handle => match result {
+                Ok(()) => span.in_scope(|| debug!("completed")),
+                Err(e) => span.in_scope(|| error!(%e, "failed")),
+            }
+        }
+    }
+}
+
+/// Picks a random delay between zero and the maximum jitter.
+pub(super) fn jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return max;
+    }
+
+    thread_rng().gen_range(Duration::ZERO..=max)
+}
+
+/// Works out how long to wait before each run of a schedule.
+pub(super) enum Timer {
+    Interval(time::Interval),
+    Cron(Box<cron::Schedule>),
+    Delay(Option<Duration>),
+}
+
+impl Timer {
+    pub(super) fn new(schedule: Schedule) -> Self {
+        match schedule {
+            Schedule::Interval(period) => {
+                let mut interval = time::interval_at(Instant::now() + period, period);
+                interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+                Self::Interval(interval)
+            }
+            Schedule::Cron(expr) => {
+                let schedule = Schedule::cron(expr).expect("setup checks cron expressions");
+                Self::Cron(Box::new(schedule))
+            }
+            Schedule::Delay(delay) => Self::Delay(Some(delay)),
+        }
+    }
+
+    /// Returns how long to wait before the next run, or `None` if the schedule
+    /// has no more runs.
+    pub(super) async fn next(&mut self) -> Option<Duration> {
+        match self {
+            // The interval does the waiting itself, so no extra delay is needed.
+            Self::Interval(interval) => {
+                interval.tick().await;
+                Some(Duration::ZERO)
+            }
+            Self::Cron(schedule) => {
+                let next = schedule.upcoming(Utc).next()?;
+                Some((next - Utc::now()).to_std().unwrap_or_default())
+            }
+            Self::Delay(delay) => delay.take(),
+        }
+    }
+}
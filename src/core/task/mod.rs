@@ -0,0 +1,254 @@
+use crate::core::{
+    controller::Controller,
+    feature::Feature,
+    setup::{ControllerCollection, Step, StepArgs},
+};
+use serenity::{
+    client::{bridge::gateway::ShardManager, Client},
+    http::client::Http,
+    prelude::{Mutex, RwLock, TypeMap},
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod manager;
+
+pub use manager::TaskManager;
+
+/// A named piece of background work that runs on a schedule once setup has
+/// finished. Usually defined as an async function, which the `task` macro
+/// transforms into a static struct.
+///
+/// Runs of a single task never overlap: the next run is only scheduled once the
+/// previous one has finished. All tasks are cancelled when the bot shuts down.
+pub struct Task {
+    /// The name of the task.
+    pub name: &'static str,
+
+    /// When the task runs.
+    pub schedule: Schedule,
+
+    /// The maximum random delay added before each run, so that tasks on the
+    /// same schedule don't all fire at once.
+    pub jitter: Duration,
+
+    /// The feature the task belongs to, if it can be turned off.
+    pub feature: Option<&'static Feature>,
+
+    /// The function pointer for the task. This is public so the `task`
+    /// macro can write to it, but should not be messed with.
+    pub __exec: fn(args: &TaskArgs) -> TaskFuture<'_>,
+}
+
+impl Task {
+    /// Makes the task belong to a feature, so its runs are skipped while the
+    /// feature is off.
+    pub fn in_feature(self, feature: Option<&'static Feature>) -> Self {
+        Self { feature, ..self }
+    }
+}
+
+impl fmt::Debug for Task {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Task")
+            .field("name", &self.name)
+            .field("schedule", &self.schedule)
+            .field("jitter", &self.jitter)
+            .field("feature", &self.feature)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The future returned by a task's function pointer.
+pub type TaskFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// When a `Task` runs.
+#[derive(Debug, Clone, Copy)]
+pub enum Schedule {
+    /// Runs repeatedly with a fixed duration between runs. Ticks that are missed
+    /// because a run took too long are skipped rather than run back to back.
+    Interval(Duration),
+
+    /// Runs whenever a cron expression (with a leading seconds field) fires, in UTC.
+    /// The `task` macro validates the expression at compile time.
+    Cron(&'static str),
+
+    /// Runs once, a fixed duration after setup finishes.
+    Delay(Duration),
+}
+
+impl Schedule {
+    /// Parses a cron schedule. The `task` macro rules out invalid expressions
+    /// for tasks defined through it, and setup refuses to start any other task
+    /// with one.
+    fn cron(expr: &str) -> Result<cron::Schedule, cron::error::Error> {
+        cron::Schedule::from_str(expr)
+    }
+}
+
+/// Arguments given to a running task. Cheap to clone, as every field is shared.
+#[derive(Clone)]
+pub struct TaskArgs {
+    controller: Controller,
+    http: Arc<Http>,
+    data: Arc<RwLock<TypeMap>>,
+    shard_manager: Arc<Mutex<ShardManager>>,
+}
+
+impl TaskArgs {
+    /// Arguments for tasks registered under a controller, sharing the client's
+    /// HTTP client, data and shards.
+    pub(crate) fn new(client: &Client, controller: Controller) -> Self {
+        Self {
+            controller,
+            http: client.cache_and_http.http.clone(),
+            data: client.data.clone(),
+            shard_manager: client.shard_manager.clone(),
+        }
+    }
+
+    /// The controller this task was registered under.
+    pub fn controller(&self) -> Controller {
+        self.controller
+    }
+
+    /// The Serenity HTTP client.
+    pub fn http(&self) -> &Http {
+        &self.http
+    }
+
+    /// The shared data map of the client.
+    pub fn data(&self) -> &Arc<RwLock<TypeMap>> {
+        &self.data
+    }
+
+    /// The shard manager, for tasks that need to talk to the gateway. Note that
+    /// shards may not have connected yet when a task first runs.
+    pub fn shard_manager(&self) -> &Arc<Mutex<ShardManager>> {
+        &self.shard_manager
+    }
+}
+
+impl fmt::Debug for TaskArgs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskArgs")
+            .field("controller", &self.controller)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Why the tasks registered under a controller couldn't be started.
+#[derive(Debug)]
+pub enum TaskError {
+    /// More than one task was registered under the same name.
+    Duplicate {
+        name: &'static str,
+        controller: Controller,
+    },
+
+    /// A task was given a cron expression that doesn't parse.
+    InvalidCron {
+        name: &'static str,
+        error: cron::error::Error,
+    },
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate { name, controller } => {
+                write!(
+                    f,
+                    "More than one task is called `{name}` under {controller}."
+                )
+            }
+            Self::InvalidCron { name, error } => {
+                write!(f, "Task `{name}` has an invalid cron expression: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TaskError {}
+
+impl From<TaskError> for serenity::Error {
+    /// Serenity can only return its own errors from setup, and a task that
+    /// can't be started is closest to being given invalid input.
+    fn from(error: TaskError) -> Self {
+        Self::Io(io::Error::new(io::ErrorKind::InvalidInput, error))
+    }
+}
+
+/// Setup step that starts the tasks registered under a controller. This runs
+/// after every other step, so tasks can rely on state set up by initializers.
+#[derive(Debug)]
+pub struct TaskStep {
+    controller: Controller,
+    tasks: HashMap<&'static str, Task>,
+
+    /// The name of a task that was registered more than once, which is only
+    /// reported once the step executes, as appending can't fail.
+    duplicate: Option<&'static str>,
+}
+
+#[async_trait]
+impl Step for TaskStep {
+    type Operand = Task;
+    type Collection = ControllerCollection<Self>;
+
+    const NAME: &'static str = "Task";
+
+    fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            tasks: HashMap::default(),
+            duplicate: None,
+        }
+    }
+
+    fn operand_count(&self) -> usize {
+        self.tasks.len()
+    }
+
+    fn append(&mut self, task: Task) {
+        if let Some(previous) = self.tasks.insert(task.name, task) {
+            self.duplicate.get_or_insert(previous.name);
+        }
+    }
+
+    async fn execute<'a>(self, args: StepArgs<'a, Self>) -> serenity::Result<()> {
+        let task_args = TaskArgs::new(args.client, self.controller);
+        let manager = TaskManager::get(&args.client.data).await;
+
+        if let Some(name) = self.duplicate {
+            let controller = self.controller;
+            return Err(TaskError::Duplicate { name, controller }.into());
+        }
+
+        // Every schedule is checked before any task starts, so a bad one doesn't
+        // leave the others running.
+        for task in self.tasks.values() {
+            if let Schedule::Cron(expr) = task.schedule {
+                Schedule::cron(expr).map_err(|error| TaskError::InvalidCron {
+                    name: task.name,
+                    error,
+                })?;
+            }
+        }
+
+        for task in self.tasks.into_values() {
+            manager.spawn(task, task_args.clone()).await;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,161 @@
+use super::{
+    manager::{jitter, run, Timer},
+    Schedule, TaskArgs,
+};
+use crate::core::{controller::GLOBAL, feature::Feature};
+use porygon_macros::task;
+use serenity::{client::ClientBuilder, http::HttpBuilder, prelude::GatewayIntents};
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use std::time::Duration;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+/// Arguments for tasks that don't touch Discord. Building a client doesn't
+/// connect to anything.
+async fn args() -> TaskArgs {
+    let http = HttpBuilder::new("token").build();
+    let client = ClientBuilder::new_with_http(http, GatewayIntents::empty())
+        .await
+        .unwrap();
+
+    TaskArgs::new(&client, GLOBAL)
+}
+
+static DELAYED_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+#[task(after = "5s")]
+async fn delayed(_: &TaskArgs) {
+    DELAYED_RUNS.fetch_add(1, SeqCst);
+}
+
+#[tokio::test(start_paused = true)]
+async fn delayed_tasks_run_once_after_the_delay() {
+    let handle = tokio::spawn(run(delayed, args().await, CancellationToken::new()));
+
+    time::sleep(Duration::from_millis(4900)).await;
+    assert_eq!(DELAYED_RUNS.load(SeqCst), 0);
+
+    handle.await.unwrap();
+    assert_eq!(DELAYED_RUNS.load(SeqCst), 1);
+}
+
+static INTERVAL_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+#[task(every = "10s")]
+async fn interval(_: &TaskArgs) {
+    INTERVAL_RUNS.fetch_add(1, SeqCst);
+}
+
+#[tokio::test(start_paused = true)]
+async fn interval_tasks_wait_a_period_before_each_run_until_cancelled() {
+    let cancel = CancellationToken::new();
+    let handle = tokio::spawn(run(interval, args().await, cancel.clone()));
+
+    time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(INTERVAL_RUNS.load(SeqCst), 0);
+
+    time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(INTERVAL_RUNS.load(SeqCst), 3);
+
+    cancel.cancel();
+    handle.await.unwrap();
+
+    time::sleep(Duration::from_secs(30)).await;
+    assert_eq!(INTERVAL_RUNS.load(SeqCst), 3);
+}
+
+static SLOW_RUNNING: AtomicUsize = AtomicUsize::new(0);
+static SLOW_MOST_RUNNING: AtomicUsize = AtomicUsize::new(0);
+static SLOW_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+#[task(every = "1s")]
+async fn slow(_: &TaskArgs) {
+    let running = SLOW_RUNNING.fetch_add(1, SeqCst) + 1;
+    SLOW_MOST_RUNNING.fetch_max(running, SeqCst);
+
+    time::sleep(Duration::from_secs(3)).await;
+
+    SLOW_RUNNING.fetch_sub(1, SeqCst);
+    SLOW_RUNS.fetch_add(1, SeqCst);
+}
+
+#[tokio::test(start_paused = true)]
+async fn runs_never_overlap_and_missed_ticks_are_skipped() {
+    let cancel = CancellationToken::new();
+    let handle = tokio::spawn(run(slow, args().await, cancel.clone()));
+
+    time::sleep(Duration::from_millis(12500)).await;
+    cancel.cancel();
+    handle.await.unwrap();
+
+    assert_eq!(SLOW_MOST_RUNNING.load(SeqCst), 1);
+    assert_eq!(SLOW_RUNS.load(SeqCst), 3);
+}
+
+static PANICKING_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+#[task(every = "1s")]
+async fn panicking(_: &TaskArgs) {
+    if PANICKING_RUNS.fetch_add(1, SeqCst) == 0 {
+        panic!("the first run fails");
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_panic_only_fails_the_current_run() {
+    let cancel = CancellationToken::new();
+    let handle = tokio::spawn(run(panicking, args().await, cancel.clone()));
+
+    time::sleep(Duration::from_millis(3500)).await;
+    cancel.cancel();
+    handle.await.unwrap();
+
+    assert_eq!(PANICKING_RUNS.load(SeqCst), 3);
+}
+
+#[tokio::test]
+async fn cron_schedules_wait_until_the_next_match() {
+    let mut timer = Timer::new(Schedule::Cron("* * * * * *"));
+    let wait = timer.next().await.unwrap();
+
+    assert!(wait <= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn delay_schedules_run_out_after_one_run() {
+    let mut timer = Timer::new(Schedule::Delay(Duration::from_secs(5)));
+
+    assert_eq!(timer.next().await, Some(Duration::from_secs(5)));
+    assert_eq!(timer.next().await, None);
+}
+
+#[test]
+fn jitter_stays_within_the_maximum() {
+    assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+
+    for _ in 0..100 {
+        assert!(jitter(Duration::from_secs(60)) <= Duration::from_secs(60));
+    }
+}
+
+const NEVER: Feature = Feature::new("never", "Never turned on.").disabled_by_default();
+
+static GATED_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+#[task(every = "1s")]
+async fn gated(_: &TaskArgs) {
+    GATED_RUNS.fetch_add(1, SeqCst);
+}
+
+#[tokio::test(start_paused = true)]
+async fn tasks_of_features_that_are_off_skip_their_runs() {
+    let cancel = CancellationToken::new();
+    let task = gated.in_feature(Some(&NEVER));
+    let handle = tokio::spawn(run(task, args().await, cancel.clone()));
+
+    time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(GATED_RUNS.load(SeqCst), 0);
+
+    cancel.cancel();
+    handle.await.unwrap();
+}
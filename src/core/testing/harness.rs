@@ -0,0 +1,659 @@
+use super::{
+    json,
+    server::FakeDiscord,
+    world::{Effect, FakeGuild, Request, Sent, World},
+};
+use crate::{
+    app,
+    core::{config::Config, event::EventProxy, setup::Setup, task::TaskManager},
+};
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use serde_json::{json, Map, Value};
+use serenity::{
+    client::{bridge::gateway::ShardMessenger, Client, ClientBuilder, Context, EventHandler},
+    gateway::InterMessage,
+    http::HttpBuilder,
+    model::{
+        application::{command::CommandOptionType, interaction::InteractionResponseType},
+        event::Event,
+        id::{ChannelId, GuildId, MessageId, RoleId, UserId},
+    },
+    prelude::{GatewayIntents, RwLock, TypeMap},
+};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The user who owns the bot unless told otherwise, who isn't in any guild.
+const DEFAULT_OWNER: u64 = 2;
+
+/// Builds a `Harness`.
+#[derive(Debug)]
+pub struct HarnessBuilder {
+    guilds: BTreeMap<GuildId, FakeGuild>,
+    owner: UserId,
+}
+
+/// A bot that's been set up against `FakeDiscord`, ready for interactions and
+/// gateway events to be delivered to it.
+pub struct Harness {
+    client: Client,
+    discord: FakeDiscord,
+
+    /// Messages to the shard are sent here and never read.
+    shard: (
+        UnboundedSender<InterMessage>,
+        UnboundedReceiver<InterMessage>,
+    ),
+
+    data_dir: PathBuf,
+
+    /// The tasks started by setup, which are cancelled when the harness is dropped.
+    tasks: TaskManager,
+}
+
+/// A user of the bot, interacting with it from a guild channel or their DMs.
+#[derive(Debug, Clone)]
+pub struct Actor<'a> {
+    harness: &'a Harness,
+    user_id: UserId,
+    guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+    locale: String,
+}
+
+/// Everything the bot did while handling an interaction or event.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub effects: Vec<Effect>,
+}
+
+impl HarnessBuilder {
+    /// Adds a guild the bot is in.
+    pub fn guild(mut self, guild: FakeGuild) -> Self {
+        match self.guilds.get_mut(&guild.id) {
+            Some(existing) => existing.merge(guild),
+            None => {
+                self.guilds.insert(guild.id, guild);
+            }
+        }
+
+        self
+    }
+
+    /// Sets the user who owns the bot.
+    pub fn owner(mut self, id: u64) -> Self {
+        self.owner = UserId(id);
+        self
+    }
+
+    /// Runs the full setup of the bot, with everything `app` installs.
+    pub async fn start(self) -> Harness {
+        self.start_with(app::installer).await
+    }
+
+    /// Runs the full setup of the bot, with only what an installer adds on top
+    /// of the built-in steps.
+    pub async fn start_with(self, installer: impl FnOnce(Setup) -> Setup) -> Harness {
+        let data_dir = env::temp_dir().join(format!(
+            "porygon-test-{}-{}",
+            std::process::id(),
+            rand::random::<u64>(),
+        ));
+
+        let mut config = Config::default();
+        config.storage.data_dir = data_dir.clone();
+
+        for guild in self.guilds.values() {
+            for controller in &guild.controllers {
+                let controller = config.controllers.entry(*controller).or_default();
+                controller.staff_role = guild.staff_role;
+                controller.error_channel = guild.error_channel;
+            }
+        }
+
+        let discord = FakeDiscord::start(World::new(self.guilds, self.owner));
+        let http = HttpBuilder::new("token")
+            .application_id(json::APPLICATION_ID)
+            .proxy(discord.url())
+            .expect("the fake's URL is valid")
+            .ratelimiter_disabled(true)
+            .build();
+
+        let client = ClientBuilder::new_with_http(http, GatewayIntents::empty())
+            .type_map_insert::<Config>(Arc::new(config))
+            .await
+            .expect("building a client doesn't need Discord");
+
+        Setup::new()
+            .add_from(installer)
+            .setup(&client)
+            .await
+            .expect("setup succeeds against the fake");
+
+        let tasks = TaskManager::get(&client.data).await;
+
+        Harness {
+            client,
+            discord,
+            shard: mpsc::unbounded(),
+            data_dir,
+            tasks,
+        }
+    }
+}
+
+impl Harness {
+    pub fn builder() -> HarnessBuilder {
+        HarnessBuilder {
+            guilds: BTreeMap::new(),
+            owner: UserId(DEFAULT_OWNER),
+        }
+    }
+
+    pub fn data(&self) -> &Arc<RwLock<TypeMap>> {
+        &self.client.data
+    }
+
+    /// The data directory of the bot, which is removed when the harness is dropped.
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// A context like the one serenity passes to event handlers.
+    pub fn context(&self) -> Context {
+        Context {
+            data: self.client.data.clone(),
+            shard: ShardMessenger::new(self.shard.0.clone()),
+            shard_id: 0,
+            http: self.client.cache_and_http.http.clone(),
+        }
+    }
+
+    /// A user interacting with the bot. They do so from the default channel of
+    /// the first guild they're a member of, or from their DMs if there's none.
+    pub fn user(&self, id: u64) -> Actor<'_> {
+        let user_id = UserId(id);
+        let guild_id = self
+            .discord
+            .world()
+            .guilds
+            .values()
+            .find(|guild| guild.get_member(user_id).is_some())
+            .map(FakeGuild::id);
+
+        let mut actor = Actor {
+            harness: self,
+            user_id,
+            guild_id: None,
+            channel_id: ChannelId(id),
+            locale: "en-US".to_string(),
+        };
+
+        if let Some(guild_id) = guild_id {
+            actor = actor.in_guild(guild_id);
+        }
+
+        actor
+    }
+
+    /// Delivers a gateway event to the bot, as if it came from a shard.
+    pub async fn deliver(&self, event: Event) -> Outcome {
+        let start = self.discord.world().effects.len();
+        let (proxy, ctx) = (EventProxy, self.context());
+
+        match event {
+            Event::Ready(event) => proxy.ready(ctx, event.ready).await,
+            Event::MessageCreate(event) => proxy.message(ctx, event.message).await,
+            Event::GuildMemberAdd(event) => proxy.guild_member_addition(ctx, event.member).await,
+            Event::GuildMemberUpdate(event) => proxy.guild_member_update(ctx, event).await,
+            Event::GuildMemberRemove(event) => {
+                proxy
+                    .guild_member_removal(ctx, event.guild_id, event.user)
+                    .await
+            }
+            Event::ReactionAdd(event) => proxy.reaction_add(ctx, event.reaction).await,
+            Event::ReactionRemove(event) => proxy.reaction_remove(ctx, event.reaction).await,
+            Event::InteractionCreate(event) => {
+                proxy.interaction_create(ctx, event.interaction).await
+            }
+            event => panic!("delivering {:?} isn't simulated", event.event_type()),
+        }
+
+        Outcome {
+            effects: self.discord.world().effects[start..].to_vec(),
+        }
+    }
+
+    /// Everything the bot has done so far.
+    pub fn effects(&self) -> Vec<Effect> {
+        self.discord.world().effects.clone()
+    }
+
+    /// Every request the bot has made so far, including during setup.
+    pub fn requests(&self) -> Vec<Request> {
+        self.discord.world().requests.clone()
+    }
+
+    /// The roles a member has now, after any changes the bot made.
+    pub fn roles(&self, guild_id: GuildId, user_id: u64) -> Vec<RoleId> {
+        self.discord
+            .world()
+            .guilds
+            .get(&guild_id)
+            .and_then(|guild| guild.get_member(UserId(user_id)))
+            .map(|member| member.roles().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// The messages the bot has sent to a channel, outside of interactions.
+    pub fn messages(&self, channel_id: ChannelId) -> Vec<Sent> {
+        self.effects()
+            .into_iter()
+            .filter_map(|effect| match effect {
+                Effect::Message {
+                    channel_id: channel,
+                    message,
+                } if channel == channel_id => Some(message),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for Harness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Harness")
+            .field("discord", &self.discord)
+            .field("data_dir", &self.data_dir)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        self.tasks.cancel();
+        let _ = std::fs::remove_dir_all(&self.data_dir);
+    }
+}
+
+impl<'a> Actor<'a> {
+    /// Interacts from the default channel of a guild instead.
+    pub fn in_guild(mut self, guild_id: GuildId) -> Self {
+        self.guild_id = Some(guild_id);
+        self.channel_id = ChannelId(guild_id.0);
+        self
+    }
+
+    /// Interacts from another channel of the same guild instead.
+    pub fn in_channel(mut self, id: u64) -> Self {
+        self.channel_id = ChannelId(id);
+        self
+    }
+
+    /// Sets the locale of the user's Discord client.
+    pub fn locale(mut self, locale: &str) -> Self {
+        self.locale = locale.to_string();
+        self
+    }
+
+    /// Uses a slash command, such as `/role add`, with options given as an
+    /// object of their values. Option types are looked up from the uploaded
+    /// command, and users, roles and channels are given by ID.
+    pub async fn command(&self, command: &str, options: Value) -> Outcome {
+        let data = self.command_data(command, options, None);
+        self.interact(2, data, None).await
+    }
+
+    /// Types into an option of a slash command, with the values of the other
+    /// options given as for `Actor::command`.
+    pub async fn autocomplete(&self, command: &str, options: Value, focused: &str) -> Outcome {
+        let data = self.command_data(command, options, Some(focused));
+        self.interact(4, data, None).await
+    }
+
+    /// Uses a context menu command on a user.
+    pub async fn user_command(&self, name: &str, target: u64) -> Outcome {
+        let user_id = UserId(target);
+        let (id, _) = self.find_command(2, name);
+        let mut resolved = Resolved::default();
+        resolved.user(self, user_id);
+
+        let data = json!({
+            "id": id,
+            "name": name,
+            "type": 2,
+            "target_id": user_id,
+            "resolved": resolved.into_json(),
+        });
+
+        self.interact(2, data, None).await
+    }
+
+    /// Uses a context menu command on a message the bot sent.
+    pub async fn message_command(&self, name: &str, target: &Sent) -> Outcome {
+        let (id, _) = self.find_command(3, name);
+        let message = self.message_json(target);
+        let message_id = message["id"].clone();
+
+        let data = json!({
+            "id": id,
+            "name": name,
+            "type": 3,
+            "target_id": message_id,
+            "resolved": { "messages": { message_id.as_str().unwrap_or_default(): message } },
+        });
+
+        self.interact(2, data, None).await
+    }
+
+    /// Clicks a button on a message the bot sent.
+    pub async fn click(&self, message: &Sent, custom_id: &str) -> Outcome {
+        let data = json!({ "custom_id": custom_id, "component_type": 2 });
+        self.interact(3, data, Some(self.message_json(message)))
+            .await
+    }
+
+    /// Picks values from a select menu on a message the bot sent.
+    pub async fn select(&self, message: &Sent, custom_id: &str, values: &[&str]) -> Outcome {
+        let data = json!({ "custom_id": custom_id, "component_type": 3, "values": values });
+        self.interact(3, data, Some(self.message_json(message)))
+            .await
+    }
+
+    /// Submits a modal, with the values of its text inputs by custom ID.
+    pub async fn submit(&self, custom_id: &str, inputs: &[(&str, &str)]) -> Outcome {
+        let rows = inputs
+            .iter()
+            .map(|(id, value)| {
+                json!({
+                    "type": 1,
+                    "components": [{ "type": 4, "custom_id": id, "value": value }],
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let data = json!({ "custom_id": custom_id, "components": rows });
+        self.interact(5, data, None).await
+    }
+
+    /// Sends an interaction of an `InteractionType` and waits for the bot to
+    /// finish handling it.
+    async fn interact(&self, kind: u8, data: Value, message: Option<Value>) -> Outcome {
+        let id = self.harness.discord.world().next_id();
+        let mut interaction = json!({
+            "id": id.to_string(),
+            "application_id": json::APPLICATION_ID.to_string(),
+            "type": kind,
+            "data": data,
+            "channel_id": self.channel_id,
+            "user": self.user_json(),
+            "token": format!("token-{id}"),
+            "version": 1,
+            "locale": self.locale,
+        });
+
+        if let Some(guild_id) = self.guild_id {
+            interaction["guild_id"] = json!(guild_id);
+            interaction["data"]["guild_id"] = json!(guild_id);
+            interaction["member"] = self
+                .member_json(guild_id)
+                .unwrap_or_else(|| panic!("user {} isn't in guild {guild_id}", self.user_id));
+        }
+
+        if let Some(message) = message {
+            interaction["message"] = message;
+        }
+
+        let event = serde_json::from_value(interaction).expect("simulated interactions are valid");
+        self.harness.deliver(Event::InteractionCreate(event)).await
+    }
+
+    /// Builds the data of a slash command interaction.
+    fn command_data(&self, command: &str, options: Value, focused: Option<&str>) -> Value {
+        let mut path = command.trim_start_matches('/').split_whitespace();
+        let name = path.next().expect("the command has a name");
+        let (id, schema) = self.find_command(1, name);
+
+        let mut schema = &schema["options"];
+        let mut resolved = Resolved::default();
+        let mut subcommands = Vec::new();
+
+        for subcommand in path {
+            let option = find_option(schema, subcommand)
+                .unwrap_or_else(|| panic!("`{command}` doesn't exist"));
+
+            subcommands.push((subcommand, option["type"].clone()));
+            schema = &option["options"];
+        }
+
+        let values = match options {
+            Value::Object(values) => values,
+            Value::Null => Map::new(),
+            other => panic!("options are given as an object, not {other}"),
+        };
+
+        let mut options = values
+            .into_iter()
+            .map(|(name, value)| {
+                let option = find_option(schema, &name)
+                    .unwrap_or_else(|| panic!("`{command}` has no option `{name}`"));
+
+                let kind = option["type"].as_u64().unwrap_or_default();
+                resolved.value(self, kind, &value);
+
+                let mut option = json!({ "name": name, "type": kind, "value": value });
+
+                if focused == Some(name.as_str()) {
+                    option["focused"] = true.into();
+                }
+
+                option
+            })
+            .collect::<Vec<_>>();
+
+        for (name, kind) in subcommands.into_iter().rev() {
+            options = vec![json!({ "name": name, "type": kind, "options": options })];
+        }
+
+        json!({
+            "id": id,
+            "name": name,
+            "type": 1,
+            "options": options,
+            "resolved": resolved.into_json(),
+        })
+    }
+
+    /// Finds an uploaded command of a `CommandType` that can be used here.
+    fn find_command(&self, kind: u8, name: &str) -> (String, Value) {
+        let world = self.harness.discord.world();
+        let (id, data) = world
+            .find_command(self.guild_id, kind, name)
+            .unwrap_or_else(|| panic!("no command `{name}` can be used here"));
+
+        (id.to_string(), data.clone())
+    }
+
+    fn user_json(&self) -> Value {
+        let world = self.harness.discord.world();
+        let member = world
+            .guilds
+            .values()
+            .find_map(|guild| guild.get_member(self.user_id));
+
+        match member {
+            Some(member) => member.user_json(),
+            None => json::user(self.user_id, &format!("user-{}", self.user_id)),
+        }
+    }
+
+    fn member_json(&self, guild_id: GuildId) -> Option<Value> {
+        let world = self.harness.discord.world();
+        let guild = world.guilds.get(&guild_id)?;
+        let member = guild.get_member(self.user_id)?;
+
+        Some(member.to_json(guild_id))
+    }
+
+    /// A message the bot sent where the user is, as Discord would include it in
+    /// an interaction.
+    fn message_json(&self, sent: &Sent) -> Value {
+        let id = MessageId(self.harness.discord.world().next_id());
+        let mut message = json::message(id, self.channel_id, json::bot(), &sent.data);
+
+        if let Some(guild_id) = self.guild_id {
+            message["guild_id"] = json!(guild_id);
+        }
+
+        message
+    }
+}
+
+impl Outcome {
+    /// The messages shown in reply to the interaction, in order: its response,
+    /// and any follow-ups or edits after it.
+    pub fn replies(&self) -> Vec<&Sent> {
+        let shown = [
+            InteractionResponseType::ChannelMessageWithSource as u8,
+            InteractionResponseType::UpdateMessage as u8,
+        ];
+
+        self.effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::Response { kind, message, .. } if shown.contains(kind) => Some(message),
+                Effect::FollowUp { message, .. } | Effect::Edit { message, .. } => Some(message),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The last message shown in reply to the interaction.
+    pub fn reply(&self) -> &Sent {
+        self.replies()
+            .pop()
+            .unwrap_or_else(|| panic!("the bot didn't reply, but did {:#?}", self.effects))
+    }
+
+    /// The names of the suggestions sent in response to autocomplete.
+    pub fn suggestions(&self) -> Vec<String> {
+        self.response(InteractionResponseType::Autocomplete)
+            .and_then(|sent| sent.data["choices"].as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|choice| choice["name"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// The modal shown in response to the interaction, if any.
+    pub fn modal(&self) -> Option<&Sent> {
+        self.response(InteractionResponseType::Modal)
+    }
+
+    fn response(&self, kind: InteractionResponseType) -> Option<&Sent> {
+        self.effects.iter().find_map(|effect| match effect {
+            Effect::Response {
+                kind: sent,
+                message,
+                ..
+            } if *sent == kind as u8 => Some(message),
+            _ => None,
+        })
+    }
+}
+
+/// The objects referred to by the options of a command, which Discord sends
+/// alongside them.
+#[derive(Default)]
+struct Resolved {
+    users: Map<String, Value>,
+    members: Map<String, Value>,
+    roles: Map<String, Value>,
+    channels: Map<String, Value>,
+}
+
+impl Resolved {
+    /// Resolves the value of an option of a `CommandOptionType`, if it refers
+    /// to something.
+    fn value(&mut self, actor: &Actor<'_>, kind: u64, value: &Value) {
+        let id = match value.as_str().and_then(|id| id.parse().ok()) {
+            Some(id) => id,
+            None => return,
+        };
+
+        match kind {
+            k if k == CommandOptionType::User as u64 => self.user(actor, UserId(id)),
+            k if k == CommandOptionType::Role as u64 => self.role(actor, RoleId(id)),
+            k if k == CommandOptionType::Channel as u64 => self.channel(actor, ChannelId(id)),
+            k if k == CommandOptionType::Mentionable as u64 => {
+                self.user(actor, UserId(id));
+                self.role(actor, RoleId(id));
+            }
+            _ => {}
+        }
+    }
+
+    fn user(&mut self, actor: &Actor<'_>, user_id: UserId) {
+        let world = actor.harness.discord.world();
+        let guild = actor.guild_id.and_then(|id| world.guilds.get(&id));
+        let member = guild.and_then(|guild| guild.get_member(user_id));
+
+        let user = match member {
+            Some(member) => member.user_json(),
+            None => json::user(user_id, &format!("user-{user_id}")),
+        };
+
+        if let (Some(guild), Some(member)) = (guild, member) {
+            let mut member = member.to_json(guild.id());
+            member.as_object_mut().map(|member| member.remove("user"));
+            self.members.insert(user_id.to_string(), member);
+        }
+
+        self.users.insert(user_id.to_string(), user);
+    }
+
+    fn role(&mut self, actor: &Actor<'_>, role_id: RoleId) {
+        let world = actor.harness.discord.world();
+        let role = actor
+            .guild_id
+            .and_then(|id| world.guilds.get(&id))
+            .and_then(|guild| guild.role_json(role_id));
+
+        if let Some(role) = role {
+            self.roles.insert(role_id.to_string(), role);
+        }
+    }
+
+    fn channel(&mut self, actor: &Actor<'_>, channel_id: ChannelId) {
+        let world = actor.harness.discord.world();
+        let guild = actor
+            .guild_id
+            .and_then(|id| world.guilds.get(&id))
+            .filter(|guild| guild.has_channel(channel_id));
+
+        if let Some(guild) = guild {
+            let channel = json::channel(guild.id(), channel_id);
+            self.channels.insert(channel_id.to_string(), channel);
+        }
+    }
+
+    fn into_json(self) -> Value {
+        json!({
+            "users": self.users,
+            "members": self.members,
+            "roles": self.roles,
+            "channels": self.channels,
+        })
+    }
+}
+
+/// Finds an option, subcommand or group by name among the options of a command.
+fn find_option<'a>(options: &'a Value, name: &str) -> Option<&'a Value> {
+    options
+        .as_array()?
+        .iter()
+        .find(|option| option["name"] == name)
+}
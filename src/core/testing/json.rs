@@ -0,0 +1,145 @@
+//! The JSON of the Discord objects the fakes respond with. Only the fields
+//! serenity needs to deserialize each object are filled in.
+
+use serde_json::{json, Value};
+use serenity::model::{
+    id::{ChannelId, CommandId, GuildId, MessageId, RoleId, UserId},
+    Permissions,
+};
+
+/// The ID of the bot's application, and of the bot user.
+pub const APPLICATION_ID: u64 = 1;
+
+/// When every fake member joined and every fake message was sent.
+const TIMESTAMP: &str = "2022-01-01T00:00:00Z";
+
+pub fn user(id: UserId, name: &str) -> Value {
+    json!({
+        "id": id,
+        "username": name,
+        "discriminator": "0000",
+        "avatar": null,
+        "bot": id.0 == APPLICATION_ID,
+    })
+}
+
+/// The bot user, which authors every message the bot sends.
+pub fn bot() -> Value {
+    user(UserId(APPLICATION_ID), "Porygon")
+}
+
+pub fn member(guild_id: GuildId, user: Value, roles: &[RoleId]) -> Value {
+    json!({
+        "guild_id": guild_id,
+        "user": user,
+        "nick": null,
+        "avatar": null,
+        "roles": roles,
+        "joined_at": TIMESTAMP,
+        "deaf": false,
+        "mute": false,
+        "permissions": "0",
+    })
+}
+
+pub fn role(guild_id: GuildId, id: RoleId, name: &str) -> Value {
+    json!({
+        "id": id,
+        "guild_id": guild_id,
+        "name": name,
+        "color": 0,
+        "hoist": false,
+        "managed": false,
+        "mentionable": false,
+        "permissions": "0",
+        "position": 0,
+    })
+}
+
+/// A text channel in a guild.
+pub fn channel(guild_id: GuildId, id: ChannelId) -> Value {
+    json!({
+        "id": id,
+        "guild_id": guild_id,
+        "type": 0,
+        "name": format!("channel-{id}"),
+        "position": 0,
+        "permission_overwrites": [],
+        "permissions": Permissions::all().bits().to_string(),
+    })
+}
+
+pub fn guild(id: GuildId, name: &str, roles: Vec<Value>) -> Value {
+    json!({
+        "id": id,
+        "name": name,
+        "owner_id": APPLICATION_ID.to_string(),
+        "afk_timeout": 0,
+        "default_message_notifications": 0,
+        "emojis": [],
+        "features": [],
+        "mfa_level": 0,
+        "roles": roles,
+        "stickers": [],
+        "system_channel_flags": 0,
+        "verification_level": 0,
+        "premium_subscription_count": 0,
+        "nsfw_level": 0,
+    })
+}
+
+/// The command Discord returns for uploaded data.
+pub fn command(id: CommandId, guild_id: Option<GuildId>, data: &Value) -> Value {
+    json!({
+        "id": id,
+        "type": data.get("type").cloned().unwrap_or_else(|| 1.into()),
+        "application_id": APPLICATION_ID.to_string(),
+        "guild_id": guild_id,
+        "name": data["name"],
+        "description": data.get("description").cloned().unwrap_or_else(|| "".into()),
+        "options": data.get("options").cloned().unwrap_or_else(|| json!([])),
+        "default_member_permissions": data.get("default_member_permissions"),
+        "version": "1",
+    })
+}
+
+/// The message Discord returns for sent data.
+pub fn message(id: MessageId, channel_id: ChannelId, author: Value, data: &Value) -> Value {
+    json!({
+        "id": id,
+        "channel_id": channel_id,
+        "author": author,
+        "content": data.get("content").cloned().unwrap_or_else(|| "".into()),
+        "embeds": data.get("embeds").cloned().unwrap_or_else(|| json!([])),
+        "components": data.get("components").cloned().unwrap_or_else(|| json!([])),
+        "flags": data.get("flags").cloned().unwrap_or_else(|| 0.into()),
+        "timestamp": TIMESTAMP,
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "reactions": [],
+        "pinned": false,
+        "type": 0,
+    })
+}
+
+/// The application the bot belongs to, owned by a single user.
+pub fn application(owner: Value) -> Value {
+    json!({
+        "id": APPLICATION_ID.to_string(),
+        "name": "Porygon",
+        "icon": null,
+        "description": "",
+        "rpc_origins": [],
+        "bot_public": false,
+        "bot_require_code_grant": false,
+        "owner": owner,
+        "team": null,
+        "summary": "",
+        "verify_key": "",
+        "flags": 0,
+    })
+}
@@ -0,0 +1,22 @@
+//! Offline end-to-end testing.
+//!
+//! A `Harness` runs the full setup of the bot against `FakeDiscord`, a stand-in
+//! for Discord's HTTP API that serenity is pointed at through its proxy setting,
+//! and that simulates a set of guilds and their members. Tests then act as users
+//! through `Harness::user`, or deliver gateway events with `Harness::deliver`.
+//! Both go through `EventProxy` like real events do, and return an `Outcome`
+//! holding the replies, messages and role changes the bot produced.
+//!
+//! Collectors, which wait on the gateway for further interactions, and anything
+//! sent through the shard manager, such as activities, aren't simulated.
+
+mod harness;
+pub mod json;
+mod server;
+mod world;
+
+pub use harness::*;
+pub use world::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,249 @@
+use super::world::{Response, Sent, World};
+use serde_json::Value;
+use serenity::http::StatusCode;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+/// The version of Discord's API serenity makes requests to.
+const API: &str = "/api/v10";
+
+/// A stand-in for Discord's HTTP API, listening on a local port. Serenity's
+/// `Http` sends its requests here when this is set as its proxy, and each one is
+/// handled against a `World` and recorded on it.
+///
+/// The server speaks just enough HTTP/1.1 for serenity, one thread per
+/// connection, and runs until the test process exits.
+#[derive(Debug, Clone)]
+pub struct FakeDiscord {
+    url: String,
+    world: Arc<Mutex<World>>,
+}
+
+/// A request read off a connection, before its body is parsed.
+struct RawRequest {
+    method: String,
+    path: String,
+    content_type: String,
+    body: Vec<u8>,
+}
+
+impl FakeDiscord {
+    pub(super) fn start(world: World) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("a local port is free");
+        let url = format!(
+            "http://{}",
+            listener.local_addr().expect("the listener is bound")
+        );
+
+        let world = Arc::new(Mutex::new(world));
+        let shared = world.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let world = shared.clone();
+                thread::spawn(move || serve(stream, &world));
+            }
+        });
+
+        Self { url, world }
+    }
+
+    /// The URL to give serenity as its proxy.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub(super) fn world(&self) -> MutexGuard<'_, World> {
+        lock(&self.world)
+    }
+}
+
+fn lock(world: &Mutex<World>) -> MutexGuard<'_, World> {
+    world.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Answers the requests on a connection until serenity closes it.
+fn serve(stream: TcpStream, world: &Mutex<World>) {
+    let mut reader = BufReader::new(match stream.try_clone() {
+        Ok(stream) => stream,
+        Err(_) => return,
+    });
+
+    let mut writer = stream;
+
+    while let Ok(Some(request)) = read_request(&mut reader) {
+        let path = request.path.strip_prefix(API).unwrap_or(&request.path);
+        let body = parse_body(&request.content_type, &request.body);
+        let response = lock(world).handle(&request.method, path, body);
+
+        if write_response(&mut writer, response).is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads the next request on a connection, or `None` once it's closed.
+fn read_request(reader: &mut impl BufRead) -> io::Result<Option<RawRequest>> {
+    let mut line = String::new();
+
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(io::ErrorKind::InvalidData.into()),
+    };
+
+    let (mut length, mut chunked, mut content_type) = (0, false, String::new());
+
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        let (name, value) = header.split_once(':').unwrap_or((header, ""));
+        let value = value.trim();
+
+        match name.to_ascii_lowercase().as_str() {
+            "content-length" => length = value.parse().unwrap_or_default(),
+            "transfer-encoding" => chunked = value.eq_ignore_ascii_case("chunked"),
+            "content-type" => content_type = value.to_string(),
+            _ => {}
+        }
+    }
+
+    let body = match chunked {
+        true => read_chunks(reader)?,
+        false => {
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body)?;
+            body
+        }
+    };
+
+    Ok(Some(RawRequest {
+        method,
+        path,
+        content_type,
+        body,
+    }))
+}
+
+/// Reads a body sent with chunked transfer encoding, as multipart bodies are.
+fn read_chunks(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+
+        let size = line.trim().split(';').next().unwrap_or_default();
+        let size = usize::from_str_radix(size, 16).map_err(|_| io::ErrorKind::InvalidData)?;
+
+        let mut chunk = vec![0; size + 2];
+        reader.read_exact(&mut chunk)?;
+
+        if size == 0 {
+            return Ok(body);
+        }
+
+        body.extend_from_slice(&chunk[..size]);
+    }
+}
+
+/// Parses a JSON body, or a multipart one with a `payload_json` part and files.
+fn parse_body(content_type: &str, body: &[u8]) -> Sent {
+    let boundary = match content_type.split_once("boundary=") {
+        Some((_, boundary)) => format!("--{}", boundary.trim_matches('"')),
+        None => {
+            return Sent {
+                data: serde_json::from_slice(body).unwrap_or(Value::Null),
+                files: Vec::new(),
+            }
+        }
+    };
+
+    let mut sent = Sent::default();
+
+    for part in split(body, boundary.as_bytes()) {
+        let (headers, content) = match find(part, b"\r\n\r\n") {
+            Some(end) => (&part[..end], &part[end + 4..]),
+            None => continue,
+        };
+
+        let content = content.strip_suffix(b"\r\n").unwrap_or(content);
+        let headers = String::from_utf8_lossy(headers);
+
+        match attribute(&headers, "filename") {
+            Some(filename) => sent.files.push((filename, content.to_vec())),
+            None if attribute(&headers, "name").as_deref() == Some("payload_json") => {
+                sent.data = serde_json::from_slice(content).unwrap_or(Value::Null);
+            }
+            None => {}
+        }
+    }
+
+    sent
+}
+
+/// Splits a multipart body into its parts.
+fn split<'a>(mut body: &'a [u8], boundary: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+
+    while let Some(start) = find(body, boundary) {
+        body = &body[start + boundary.len()..];
+
+        match find(body, boundary) {
+            Some(end) => parts.push(&body[..end]),
+            None => break,
+        }
+    }
+
+    parts
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Reads an attribute of a part's `Content-Disposition`, such as its `name`.
+fn attribute(headers: &str, name: &str) -> Option<String> {
+    let pattern = format!(" {name}=\"");
+    let start = headers.find(&pattern)? + pattern.len();
+    let end = headers[start..].find('"')?;
+
+    Some(headers[start..start + end].to_string())
+}
+
+fn write_response(writer: &mut impl Write, response: Response) -> io::Result<()> {
+    let body = response
+        .body
+        .map(|body| body.to_string())
+        .unwrap_or_default();
+
+    let reason = StatusCode::from_u16(response.status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or_default();
+
+    write!(
+        writer,
+        "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        response.status,
+        body.len(),
+    )?;
+
+    writer.flush()
+}
@@ -0,0 +1,454 @@
+use super::{Effect, FakeGuild, FakeMember, Harness};
+use crate::core::{
+    command::{
+        CommandContext, CommandResult, Handler, MessageCommand, Reply, TargetUser, UserCommand,
+    },
+    component::{Component, ComponentContext},
+    controller::POKECOM,
+    modal::{Modal, ModalContext, ModalHandler},
+    task::TaskArgs,
+};
+use porygon_macros::{task, Command};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serenity::model::{
+    channel::Message,
+    guild::Role,
+    id::{ChannelId, RoleId, UserId},
+    user::User,
+};
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use std::time::Duration;
+
+const STAFF: u64 = 10;
+const MEMBER: u64 = 20;
+const HELPER: u64 = 11;
+const MOD: u64 = 100;
+const USER: u64 = 200;
+
+const ANNOUNCEMENTS: ChannelId = ChannelId(30);
+
+/// Replies with pong.
+#[derive(Command)]
+struct Ping;
+
+/// Gives a member a role.
+#[derive(Command)]
+#[command(staff)]
+struct Promote {
+    /// The member to promote.
+    user: User,
+
+    /// The role to give them.
+    role: Role,
+}
+
+/// Posts an announcement.
+#[derive(Command)]
+#[command(channels(ANNOUNCEMENTS))]
+struct Announce {
+    /// What to announce.
+    text: String,
+}
+
+/// Counts up.
+#[derive(Command)]
+struct Count;
+
+/// A button counting how often it's been clicked.
+#[derive(Serialize, Deserialize)]
+struct Counter(u32);
+
+/// Picks colours.
+#[derive(Command)]
+struct Paint;
+
+/// A select menu of colours.
+#[derive(Serialize, Deserialize)]
+struct Colours;
+
+/// Sends feedback.
+#[derive(Command)]
+struct Feedback;
+
+/// Feedback
+#[derive(Modal)]
+struct FeedbackForm {
+    /// What do you think?
+    text: String,
+}
+
+struct Whois;
+
+struct Quote;
+
+#[async_trait]
+impl Handler for Ping {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.reply("Pong!").await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Promote {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let guild_id = ctx
+            .guild_id()
+            .expect("the command is only uploaded to a guild");
+
+        ctx.http()
+            .add_member_role(guild_id.0, self.user.id.0, self.role.id.0, None)
+            .await?;
+
+        ctx.reply(Reply::text(format!("Promoted {}.", self.user.name)).ephemeral())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Announce {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ANNOUNCEMENTS.say(ctx.http(), self.text).await?;
+        ctx.reply(Reply::text("Announced.").ephemeral()).await?;
+        Ok(())
+    }
+}
+
+/// Shows the count with a button counting up.
+fn counter(count: u32) -> Result<Reply, crate::core::component::CustomIdError> {
+    let custom_id = Counter(count).custom_id()?;
+
+    Ok(Reply::text(format!("Count: {count}")).components(|c| {
+        c.create_action_row(|row| row.create_button(|b| b.custom_id(custom_id).label("+1")))
+    }))
+}
+
+#[async_trait]
+impl Handler for Count {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.reply(counter(0)?).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Component for Counter {
+    const NAMESPACE: &'static str = "counter";
+
+    async fn handle(self, ctx: &ComponentContext) -> CommandResult {
+        ctx.update(counter(self.0 + 1)?).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Paint {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        let custom_id = Colours.custom_id()?;
+        let reply = Reply::text("Pick colours.").components(|c| {
+            c.create_action_row(|row| {
+                row.create_select_menu(|menu| {
+                    menu.custom_id(custom_id).max_values(2).options(|o| {
+                        o.create_option(|o| o.label("Red").value("red"))
+                            .create_option(|o| o.label("Blue").value("blue"))
+                    })
+                })
+            })
+        });
+
+        ctx.reply(reply).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Component for Colours {
+    const NAMESPACE: &'static str = "colours";
+
+    async fn handle(self, ctx: &ComponentContext) -> CommandResult {
+        ctx.reply(format!("Picked {}.", ctx.values().join(", ")))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler for Feedback {
+    async fn handle(self, ctx: &CommandContext) -> CommandResult {
+        ctx.show_modal::<FeedbackForm>().await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ModalHandler for FeedbackForm {
+    async fn handle(self, ctx: &ModalContext) -> CommandResult {
+        ctx.defer(false).await?;
+        ctx.reply(format!("Thanks for \"{}\".", self.text)).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserCommand for Whois {
+    const NAME: &'static str = "Whois";
+
+    async fn handle(ctx: &CommandContext, target: TargetUser) -> CommandResult {
+        let member = match target.member {
+            Some(_) => "a member here",
+            None => "not a member here",
+        };
+
+        ctx.reply(format!("That's {}, {member}.", target.user.name))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageCommand for Quote {
+    const NAME: &'static str = "Quote";
+
+    async fn handle(ctx: &CommandContext, target: Message) -> CommandResult {
+        ctx.reply(format!("> {}", target.content)).await?;
+        Ok(())
+    }
+}
+
+async fn harness() -> Harness {
+    let guild = FakeGuild::of(POKECOM)
+        .staff_role(STAFF)
+        .role(HELPER, "Helper")
+        .role(MEMBER, "Member")
+        .member(FakeMember::new(MOD, "mod").role(STAFF))
+        .channel(ANNOUNCEMENTS.0)
+        .member(FakeMember::new(USER, "user").role(MEMBER));
+
+    Harness::builder()
+        .guild(guild)
+        .start_with(|setup| {
+            setup
+                .add_command::<Ping>(POKECOM)
+                .add_command::<Promote>(POKECOM)
+                .add_command::<Announce>(POKECOM)
+                .add_command::<Count>(POKECOM)
+                .add_component::<Counter>(POKECOM)
+                .add_command::<Paint>(POKECOM)
+                .add_component::<Colours>(POKECOM)
+                .add_command::<Feedback>(POKECOM)
+                .add_modal::<FeedbackForm>(POKECOM)
+                .add_user_command::<Whois>(POKECOM)
+                .add_message_command::<Quote>(POKECOM)
+        })
+        .await
+}
+
+async fn harness_owned_by(owner: u64) -> Harness {
+    let guild = FakeGuild::of(POKECOM)
+        .member(FakeMember::new(MOD, "mod"))
+        .member(FakeMember::new(USER, "user"));
+
+    Harness::builder()
+        .guild(guild)
+        .owner(owner)
+        .start_with(|setup| setup.add_command::<Ping>(POKECOM))
+        .await
+}
+
+#[tokio::test]
+async fn replies_to_commands() {
+    let harness = harness().await;
+    let outcome = harness
+        .user(USER)
+        .command("/ping", Default::default())
+        .await;
+
+    assert_eq!(outcome.reply().content(), "Pong!");
+    assert!(!outcome.reply().is_ephemeral());
+}
+
+#[tokio::test]
+async fn records_role_changes() {
+    let harness = harness().await;
+    let guild_id = FakeGuild::of(POKECOM).id();
+    let options = serde_json::json!({ "user": USER.to_string(), "role": HELPER.to_string() });
+    let outcome = harness.user(MOD).command("/promote", options).await;
+
+    assert_eq!(outcome.reply().content(), "Promoted user.");
+    assert!(outcome.effects.contains(&Effect::RoleAdded {
+        guild_id,
+        user_id: UserId(USER),
+        role_id: RoleId(HELPER),
+    }));
+    assert_eq!(
+        harness.roles(guild_id, USER),
+        [RoleId(MEMBER), RoleId(HELPER)]
+    );
+}
+
+#[tokio::test]
+async fn checks_requirements_against_simulated_members() {
+    let harness = harness().await;
+    let options = serde_json::json!({ "user": MOD.to_string(), "role": STAFF.to_string() });
+    let outcome = harness.user(USER).command("/promote", options).await;
+
+    assert!(outcome.reply().is_ephemeral());
+    assert!(outcome.reply().description().contains("staff"));
+    assert!(!outcome
+        .effects
+        .iter()
+        .any(|effect| matches!(effect, Effect::RoleAdded { .. })));
+}
+
+#[tokio::test]
+async fn suggests_through_autocomplete() {
+    let harness = harness().await;
+    let options = serde_json::json!({ "command": "pro" });
+    let outcome = harness
+        .user(USER)
+        .autocomplete("/help", options, "command")
+        .await;
+
+    assert_eq!(outcome.suggestions(), ["promote"]);
+}
+
+#[tokio::test]
+async fn records_messages_sent_to_channels() {
+    let harness = harness().await;
+    let guild = FakeGuild::of(POKECOM);
+    let options = json!({ "text": "Hello!" });
+
+    let outcome = harness
+        .user(USER)
+        .in_channel(ANNOUNCEMENTS.0)
+        .command("/announce", options.clone())
+        .await;
+
+    assert_eq!(outcome.reply().content(), "Announced.");
+    assert_eq!(harness.messages(ANNOUNCEMENTS)[0].content(), "Hello!");
+
+    let english = harness
+        .user(USER)
+        .command("/announce", options.clone())
+        .await;
+    let spanish = harness
+        .user(USER)
+        .locale("es-ES")
+        .command("/announce", options)
+        .await;
+
+    assert_ne!(english.reply().description(), spanish.reply().description());
+    assert!(harness.messages(guild.default_channel()).is_empty());
+    assert_eq!(harness.messages(ANNOUNCEMENTS).len(), 1);
+}
+
+#[tokio::test]
+async fn clicks_buttons_and_picks_from_select_menus() {
+    let harness = harness().await;
+    let user = harness.user(USER);
+
+    let count = user.command("/count", Default::default()).await;
+    let message = count.reply();
+    assert_eq!(message.custom_ids(), ["counter:0"]);
+
+    let clicked = user.click(message, "counter:0").await;
+    assert_eq!(clicked.reply().content(), "Count: 1");
+    assert_eq!(clicked.reply().custom_ids(), ["counter:1"]);
+
+    let paint = user.command("/paint", Default::default()).await;
+    let picked = user
+        .select(paint.reply(), "colours", &["red", "blue"])
+        .await;
+    assert_eq!(picked.reply().content(), "Picked red, blue.");
+}
+
+#[tokio::test]
+async fn refuses_components_from_older_versions() {
+    let harness = harness().await;
+    let user = harness.user(USER);
+
+    let count = user.command("/count", Default::default()).await;
+    let clicked = user.click(count.reply(), "counter:\"zero\"").await;
+
+    assert!(clicked.reply().is_ephemeral());
+    assert_eq!(
+        clicked.reply().embeds()[0]["description"],
+        "This has expired. Use the command again to get a new one."
+    );
+}
+
+#[tokio::test]
+async fn shows_and_submits_modals() {
+    let harness = harness().await;
+    let user = harness.user(USER);
+
+    let outcome = user.command("/feedback", Default::default()).await;
+    let modal = outcome.modal().expect("a modal is shown");
+    assert_eq!(modal.data["custom_id"], "feedback-form");
+
+    let submitted = user.submit("feedback-form", &[("text", "Great")]).await;
+    assert_eq!(submitted.reply().content(), "Thanks for \"Great\".");
+}
+
+#[tokio::test]
+async fn uses_context_menu_commands() {
+    let harness = harness().await;
+    let user = harness.user(USER);
+
+    let whois = user.user_command("Whois", MOD).await;
+    assert_eq!(whois.reply().content(), "That's mod, a member here.");
+
+    let ping = user.command("/ping", Default::default()).await;
+    let quote = user.message_command("Quote", ping.reply()).await;
+    assert_eq!(quote.reply().content(), "> Pong!");
+
+    assert!(harness
+        .requests()
+        .iter()
+        .any(|request| request.method == "POST" && request.body.data["name"] == "Quote"));
+}
+
+#[tokio::test]
+async fn exports_files_to_the_owner() {
+    let harness = harness_owned_by(MOD).await;
+    let outcome = harness
+        .user(MOD)
+        .command("/help-export", Default::default())
+        .await;
+
+    let markdown = outcome.reply().file("commands.md").unwrap();
+    assert!(markdown.contains("ping"));
+    assert!(outcome.reply().content().starts_with("Documented "));
+
+    let denied = harness
+        .user(USER)
+        .command("/help-export", Default::default())
+        .await;
+    assert!(denied.reply().file("commands.md").is_none());
+}
+
+static TICKS: AtomicUsize = AtomicUsize::new(0);
+
+#[task(every = "1s")]
+async fn tick(_: &TaskArgs) {
+    TICKS.fetch_add(1, SeqCst);
+}
+
+#[tokio::test]
+async fn dropping_the_harness_stops_its_tasks() {
+    let harness = Harness::builder()
+        .start_with(|setup| setup.add_task(POKECOM, tick))
+        .await;
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    drop(harness);
+
+    let ticks = TICKS.load(SeqCst);
+    assert!(ticks > 0);
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(TICKS.load(SeqCst), ticks);
+}
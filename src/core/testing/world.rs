@@ -0,0 +1,579 @@
+use super::json;
+use crate::core::controller::Controller;
+use serde_json::{json, Value};
+use serenity::model::{
+    application::interaction::InteractionResponseType,
+    id::{ChannelId, CommandId, GuildId, InteractionId, MessageId, RoleId, UserId},
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// A guild the bot is in, with its roles, channels and members.
+#[derive(Debug, Clone)]
+pub struct FakeGuild {
+    pub(super) id: GuildId,
+    pub(super) controllers: Vec<Controller>,
+    pub(super) staff_role: Option<RoleId>,
+    pub(super) error_channel: Option<ChannelId>,
+    roles: BTreeMap<RoleId, String>,
+    channels: Vec<ChannelId>,
+    members: BTreeMap<UserId, FakeMember>,
+}
+
+/// A member of a `FakeGuild`.
+#[derive(Debug, Clone)]
+pub struct FakeMember {
+    id: UserId,
+    name: String,
+    roles: Vec<RoleId>,
+}
+
+/// Something the bot did on Discord, recorded so tests can check it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Effect {
+    /// The first response to an interaction, of an `InteractionResponseType`.
+    Response {
+        interaction_id: InteractionId,
+        kind: u8,
+        message: Sent,
+    },
+
+    FollowUp {
+        token: String,
+        message: Sent,
+    },
+
+    /// Holds the ID of the edited message, or `@original` for the response.
+    Edit {
+        token: String,
+        message_id: String,
+        message: Sent,
+    },
+
+    /// Holds the ID of the deleted message, or `@original` for the response.
+    Delete {
+        token: String,
+        message_id: String,
+    },
+
+    Message {
+        channel_id: ChannelId,
+        message: Sent,
+    },
+
+    RoleAdded {
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+    },
+
+    RoleRemoved {
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+    },
+}
+
+/// The JSON of a message the bot sent, along with any files attached to it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sent {
+    pub data: Value,
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+/// A request the bot made, as received by `FakeDiscord`.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: Sent,
+}
+
+/// What `FakeDiscord` responds to a request with.
+pub(super) struct Response {
+    pub status: u16,
+    pub body: Option<Value>,
+}
+
+/// The state of the fake Discord: its guilds, the commands uploaded to it, and
+/// everything the bot has done to it.
+#[derive(Debug)]
+pub(super) struct World {
+    pub guilds: BTreeMap<GuildId, FakeGuild>,
+    pub owner: UserId,
+    pub effects: Vec<Effect>,
+    pub requests: Vec<Request>,
+    commands: BTreeMap<CommandId, (Option<GuildId>, Value)>,
+
+    /// The responses to interactions, by token, for when they're fetched.
+    originals: HashMap<String, Value>,
+    next_id: u64,
+}
+
+impl FakeGuild {
+    /// The guild the commands registered under a controller are uploaded to.
+    /// Every controller shares the staging guild in `staging`, so guilds that
+    /// end up with the same ID are merged when a `Harness` is built.
+    pub fn of(controller: Controller) -> Self {
+        let id = controller
+            .upload_iface()
+            .guild_id()
+            .unwrap_or_else(|| panic!("{controller} isn't a guild"));
+
+        Self {
+            id,
+            controllers: vec![controller],
+            staff_role: None,
+            error_channel: None,
+            roles: BTreeMap::new(),
+            channels: vec![ChannelId(id.0)],
+            members: BTreeMap::new(),
+        }
+    }
+
+    pub fn id(&self) -> GuildId {
+        self.id
+    }
+
+    /// The channel interactions come from unless told otherwise, which has the
+    /// same ID as the guild.
+    pub fn default_channel(&self) -> ChannelId {
+        ChannelId(self.id.0)
+    }
+
+    pub fn role(mut self, id: u64, name: &str) -> Self {
+        self.roles.insert(RoleId(id), name.to_string());
+        self
+    }
+
+    /// Adds a role and makes it the staff role of the guild's controllers.
+    pub fn staff_role(mut self, id: u64) -> Self {
+        self.staff_role = Some(RoleId(id));
+        self.role(id, "Staff")
+    }
+
+    pub fn channel(mut self, id: u64) -> Self {
+        self.channels.push(ChannelId(id));
+        self
+    }
+
+    /// Adds a channel and makes it the error channel of the guild's controllers.
+    pub fn error_channel(mut self, id: u64) -> Self {
+        self.error_channel = Some(ChannelId(id));
+        self.channel(id)
+    }
+
+    pub fn member(mut self, member: FakeMember) -> Self {
+        self.members.insert(member.id, member);
+        self
+    }
+
+    /// Adds the roles, channels and members of another guild with the same ID.
+    pub(super) fn merge(&mut self, other: FakeGuild) {
+        self.controllers.extend(other.controllers);
+        self.staff_role = self.staff_role.or(other.staff_role);
+        self.error_channel = self.error_channel.or(other.error_channel);
+        self.roles.extend(other.roles);
+        self.channels.extend(other.channels);
+        self.members.extend(other.members);
+    }
+
+    pub(super) fn get_member(&self, user_id: UserId) -> Option<&FakeMember> {
+        self.members.get(&user_id)
+    }
+
+    pub(super) fn has_channel(&self, channel_id: ChannelId) -> bool {
+        self.channels.contains(&channel_id)
+    }
+
+    pub(super) fn to_json(&self) -> Value {
+        let roles = self
+            .roles
+            .iter()
+            .map(|(id, name)| json::role(self.id, *id, name))
+            .collect();
+
+        json::guild(self.id, &format!("Guild {}", self.id), roles)
+    }
+
+    pub(super) fn role_json(&self, id: RoleId) -> Option<Value> {
+        let name = self.roles.get(&id)?;
+        Some(json::role(self.id, id, name))
+    }
+}
+
+impl FakeMember {
+    /// A member with no roles.
+    pub fn new(id: u64, name: &str) -> Self {
+        Self {
+            id: UserId(id),
+            name: name.to_string(),
+            roles: Vec::new(),
+        }
+    }
+
+    pub fn role(mut self, id: u64) -> Self {
+        self.roles.push(RoleId(id));
+        self
+    }
+
+    pub fn roles(&self) -> &[RoleId] {
+        &self.roles
+    }
+
+    pub(super) fn user_json(&self) -> Value {
+        json::user(self.id, &self.name)
+    }
+
+    pub(super) fn to_json(&self, guild_id: GuildId) -> Value {
+        json::member(guild_id, self.user_json(), &self.roles)
+    }
+}
+
+impl Sent {
+    /// The text content, or an empty string if there is none.
+    pub fn content(&self) -> &str {
+        self.data["content"].as_str().unwrap_or_default()
+    }
+
+    pub fn embeds(&self) -> &[Value] {
+        self.data["embeds"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The description of the first embed, which is where errors are shown.
+    pub fn description(&self) -> &str {
+        self.embeds()
+            .first()
+            .and_then(|embed| embed["description"].as_str())
+            .unwrap_or_default()
+    }
+
+    /// Whether only the user of the interaction can see the message.
+    pub fn is_ephemeral(&self) -> bool {
+        self.data["flags"].as_u64().unwrap_or_default() & 64 != 0
+    }
+
+    /// The custom IDs of every button and select menu on the message, in order.
+    pub fn custom_ids(&self) -> Vec<&str> {
+        let rows = self.data["components"].as_array().into_iter().flatten();
+
+        rows.flat_map(|row| row["components"].as_array().into_iter().flatten())
+            .filter_map(|component| component["custom_id"].as_str())
+            .collect()
+    }
+
+    /// The text of an attached file.
+    pub fn file(&self, name: &str) -> Option<String> {
+        self.files
+            .iter()
+            .find(|(filename, _)| filename == name)
+            .map(|(_, data)| String::from_utf8_lossy(data).into_owned())
+    }
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            body: Some(body),
+        }
+    }
+
+    fn no_content() -> Self {
+        Self {
+            status: 204,
+            body: None,
+        }
+    }
+
+    fn not_found() -> Self {
+        Self {
+            status: 404,
+            body: Some(json!({ "code": 10000, "message": "Unknown" })),
+        }
+    }
+}
+
+impl World {
+    pub fn new(guilds: BTreeMap<GuildId, FakeGuild>, owner: UserId) -> Self {
+        Self {
+            guilds,
+            owner,
+            effects: Vec::new(),
+            requests: Vec::new(),
+            commands: BTreeMap::new(),
+            originals: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Handles a request to Discord's API, given its path below the API version.
+    pub fn handle(&mut self, method: &str, path: &str, body: Sent) -> Response {
+        self.requests.push(Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.clone(),
+        });
+
+        let path = path.split('?').next().unwrap_or_default();
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+        match (method, segments.as_slice()) {
+            ("GET", ["guilds", guild]) => match self.guild(guild) {
+                Some(guild) => Response::ok(guild.to_json()),
+                None => Response::not_found(),
+            },
+            ("GET", ["guilds", guild, "members", user]) => {
+                let member = self
+                    .guild(guild)
+                    .and_then(|guild| Some((guild.id, guild.get_member(UserId(id(user)?))?)));
+
+                match member {
+                    Some((guild_id, member)) => Response::ok(member.to_json(guild_id)),
+                    None => Response::not_found(),
+                }
+            }
+            ("PUT" | "DELETE", ["guilds", guild, "members", user, "roles", role]) => {
+                self.change_role(method == "PUT", guild, user, role)
+            }
+            (_, ["applications", _, "commands", rest @ ..]) => {
+                self.commands(method, None, rest, body)
+            }
+            (_, ["applications", _, "guilds", guild, "commands", rest @ ..]) => {
+                let guild_id = id(guild).map(GuildId);
+                self.commands(method, guild_id, rest, body)
+            }
+            ("GET", ["oauth2", "applications", "@me"]) => {
+                let owner = json::user(self.owner, "owner");
+                Response::ok(json::application(owner))
+            }
+            ("POST", ["channels", channel, "messages"]) => {
+                let channel_id = ChannelId(id(channel).unwrap_or_default());
+                let message = self.message(channel_id, &body.data);
+
+                self.effects.push(Effect::Message {
+                    channel_id,
+                    message: body,
+                });
+
+                Response::ok(message)
+            }
+            ("POST", ["interactions", interaction, token, "callback"]) => {
+                let kind = body.data["type"].as_u64().unwrap_or_default() as u8;
+                let data = body.data["data"].clone();
+
+                if kind == InteractionResponseType::ChannelMessageWithSource as u8 {
+                    self.originals.insert(token.to_string(), data.clone());
+                }
+
+                self.effects.push(Effect::Response {
+                    interaction_id: InteractionId(id(interaction).unwrap_or_default()),
+                    kind,
+                    message: Sent {
+                        data,
+                        files: body.files,
+                    },
+                });
+
+                Response::no_content()
+            }
+            ("POST", ["webhooks", _, token]) => {
+                let message = self.message(ChannelId(1), &body.data);
+                self.effects.push(Effect::FollowUp {
+                    token: token.to_string(),
+                    message: body,
+                });
+
+                Response::ok(message)
+            }
+            ("GET", ["webhooks", _, token, "messages", "@original"]) => {
+                match self.originals.get(*token).cloned() {
+                    Some(data) => Response::ok(self.message(ChannelId(1), &data)),
+                    None => Response::not_found(),
+                }
+            }
+            ("PATCH", ["webhooks", _, token, "messages", message_id]) => {
+                if *message_id == "@original" {
+                    self.originals.insert(token.to_string(), body.data.clone());
+                }
+
+                let message = self.message(ChannelId(1), &body.data);
+                self.effects.push(Effect::Edit {
+                    token: token.to_string(),
+                    message_id: message_id.to_string(),
+                    message: body,
+                });
+
+                Response::ok(message)
+            }
+            ("DELETE", ["webhooks", _, token, "messages", message_id]) => {
+                self.effects.push(Effect::Delete {
+                    token: token.to_string(),
+                    message_id: message_id.to_string(),
+                });
+
+                Response::no_content()
+            }
+            _ => {
+                warn!(%method, %path, "request isn't simulated");
+                Response::not_found()
+            }
+        }
+    }
+
+    /// Makes up a new ID for something created during a test.
+    pub fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        1_000_000 + self.next_id
+    }
+
+    /// Finds the uploaded command with a name that can be used in a guild,
+    /// preferring one uploaded to the guild over a global one.
+    pub fn find_command(
+        &self,
+        guild_id: Option<GuildId>,
+        kind: u8,
+        name: &str,
+    ) -> Option<(CommandId, &Value)> {
+        let mut matching = self.commands.iter().filter(|(_, (guild, data))| {
+            (guild.is_none() || *guild == guild_id)
+                && data["name"] == name
+                && data.get("type").and_then(Value::as_u64).unwrap_or(1) == kind as u64
+        });
+
+        let first = matching.next()?;
+        let (id, (_, data)) = matching
+            .find(|(_, (guild, _))| guild.is_some())
+            .unwrap_or(first);
+
+        Some((*id, data))
+    }
+
+    fn guild(&self, guild: &str) -> Option<&FakeGuild> {
+        self.guilds.get(&GuildId(id(guild)?))
+    }
+
+    fn change_role(&mut self, add: bool, guild: &str, user: &str, role: &str) -> Response {
+        let (guild_id, user_id, role_id) = match (id(guild), id(user), id(role)) {
+            (Some(guild), Some(user), Some(role)) => (GuildId(guild), UserId(user), RoleId(role)),
+            _ => return Response::not_found(),
+        };
+
+        let member = self
+            .guilds
+            .get_mut(&guild_id)
+            .filter(|guild| guild.roles.contains_key(&role_id))
+            .and_then(|guild| guild.members.get_mut(&user_id));
+
+        let member = match member {
+            Some(member) => member,
+            None => return Response::not_found(),
+        };
+
+        member.roles.retain(|id| *id != role_id);
+
+        let effect = match add {
+            true => {
+                member.roles.push(role_id);
+                Effect::RoleAdded {
+                    guild_id,
+                    user_id,
+                    role_id,
+                }
+            }
+            false => Effect::RoleRemoved {
+                guild_id,
+                user_id,
+                role_id,
+            },
+        };
+
+        self.effects.push(effect);
+        Response::no_content()
+    }
+
+    /// Handles a request about commands, given the path segments after
+    /// `commands`.
+    fn commands(
+        &mut self,
+        method: &str,
+        guild_id: Option<GuildId>,
+        rest: &[&str],
+        body: Sent,
+    ) -> Response {
+        let data = body.data;
+
+        match (method, rest) {
+            ("GET", []) => {
+                let commands = self
+                    .commands
+                    .iter()
+                    .filter(|(_, (guild, _))| *guild == guild_id)
+                    .map(|(id, (_, data))| json::command(*id, guild_id, data))
+                    .collect();
+
+                Response::ok(Value::Array(commands))
+            }
+            ("POST", []) => {
+                // Uploading a command with the name of an existing one overwrites it.
+                let existing = self
+                    .commands
+                    .iter()
+                    .find(|(_, (guild, stored))| {
+                        *guild == guild_id && stored["name"] == data["name"]
+                    })
+                    .map(|(id, _)| *id);
+
+                let id = existing.unwrap_or_else(|| CommandId(self.next_id()));
+                let command = json::command(id, guild_id, &data);
+
+                self.commands.insert(id, (guild_id, data));
+                Response::ok(command)
+            }
+            (method, [command]) => {
+                let command_id = match id(command).map(CommandId) {
+                    Some(id)
+                        if self
+                            .commands
+                            .get(&id)
+                            .is_some_and(|(guild, _)| *guild == guild_id) =>
+                    {
+                        id
+                    }
+                    _ => return Response::not_found(),
+                };
+
+                match method {
+                    "GET" => Response::ok(json::command(
+                        command_id,
+                        guild_id,
+                        &self.commands[&command_id].1,
+                    )),
+                    "PATCH" => {
+                        let command = json::command(command_id, guild_id, &data);
+                        self.commands.insert(command_id, (guild_id, data));
+                        Response::ok(command)
+                    }
+                    "DELETE" => {
+                        self.commands.remove(&command_id);
+                        Response::no_content()
+                    }
+                    _ => Response::not_found(),
+                }
+            }
+            _ => Response::not_found(),
+        }
+    }
+
+    /// The message Discord returns for data the bot sent.
+    fn message(&mut self, channel_id: ChannelId, data: &Value) -> Value {
+        let id = MessageId(self.next_id());
+        json::message(id, channel_id, json::bot(), data)
+    }
+}
+
+fn id(segment: &str) -> Option<u64> {
+    segment.parse().ok()
+}
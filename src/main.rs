@@ -13,28 +13,108 @@ extern crate serenity;
 extern crate tracing;
 
 mod app;
+mod cli;
 mod core;
 
-use crate::core::setup::Setup;
-use dotenv::dotenv;
-use serenity::Client;
-use std::env;
+use crate::cli::{Cli, CliCommand};
+use crate::core::{
+    config::{Config, LogFormat, LoggingConfig},
+    event::EventProxy,
+    setup::Setup,
+    task::TaskManager,
+};
+use clap::Parser;
+use serenity::client::{bridge::gateway::ShardManager, ClientBuilder};
+use serenity::http::Http;
+use serenity::prelude::{GatewayIntents, Mutex, RwLock, TypeMap};
+use std::io;
+use std::process::ExitCode;
+use std::sync::Arc;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry};
 use tracing_tree::HierarchicalLayer;
 
 #[tokio::main]
-#[instrument]
-async fn main() -> serenity::Result<()> {
-    dotenv().ok();
-    start_tracing();
-
-    let token = env("DISCORD_TOKEN");
-    let app_id = env("APPLICATION_ID")
-        .parse::<u64>()
-        .expect("Application ID is not a `u64`!");
-
-    let client = Client::builder(token)
-        .application_id(app_id)
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(CliCommand::Run);
+
+    // The plan only depends on what `app` installs, so it needs no configuration.
+    if command == CliCommand::Plan {
+        print!("{}", cli::plan(&Setup::new().add_from(app::installer)));
+        return ExitCode::SUCCESS;
+    }
+
+    let config = match Config::load(&cli.config) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if command == CliCommand::CheckConfig {
+        println!("{config:#?}");
+        return ExitCode::SUCCESS;
+    }
+
+    start_tracing(&config.logging);
+
+    match exec(command, config).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            error!(%e, "stopped");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs a subcommand that talks to Discord.
+#[instrument(skip(config))]
+async fn exec(command: CliCommand, config: Config) -> serenity::Result<()> {
+    info!(?config, "Loaded configuration!");
+
+    let http =
+        Http::new_with_application_id(config.discord.token.expose(), config.discord.application_id);
+
+    match command {
+        CliCommand::Run => run(http, config).await,
+        CliCommand::SyncCommands => sync_commands(http, config).await,
+        CliCommand::ListCommands { controller } => {
+            print!("{}", cli::list_commands(&http, controller).await?);
+            Ok(())
+        }
+        CliCommand::PurgeCommands { controller, yes } => {
+            let listed = cli::list_commands(&http, controller).await?;
+
+            if listed.is_empty() {
+                println!("No commands are uploaded under {controller}.");
+                return Ok(());
+            }
+
+            print!("{listed}");
+            let question = format!("Delete these commands under {controller}?");
+
+            if yes || cli::confirm(&question, io::stdin().lock(), io::stdout())? {
+                print!("{}", cli::purge_commands(&http, controller).await?);
+            }
+
+            Ok(())
+        }
+        CliCommand::Guilds => {
+            print!("{}", cli::guilds(&http).await);
+            Ok(())
+        }
+        CliCommand::Plan | CliCommand::CheckConfig => {
+            unreachable!("handled before connecting")
+        }
+    }
+}
+
+/// Connects to Discord and runs the bot until Ctrl+C.
+async fn run(http: Http, config: Config) -> serenity::Result<()> {
+    let mut client = ClientBuilder::new_with_http(http, GatewayIntents::non_privileged())
+        .type_map_insert::<Config>(Arc::new(config))
+        .event_handler(EventProxy)
         .await
         .unwrap_or_else(|e| panic!("Failed to create client: {e}"));
 
@@ -42,21 +122,58 @@ async fn main() -> serenity::Result<()> {
 
     info!("Setup complete!");
 
+    tokio::spawn(shutdown_on_ctrl_c(
+        client.data.clone(),
+        client.shard_manager.clone(),
+    ));
+
+    client.start().await
+}
+
+/// Uploads the commands `app` installs, without connecting to the gateway.
+async fn sync_commands(http: Http, config: Config) -> serenity::Result<()> {
+    let client = ClientBuilder::new_with_http(http, GatewayIntents::empty())
+        .type_map_insert::<Config>(Arc::new(config))
+        .await?;
+    let api = client.cache_and_http.http.clone();
+
+    Setup::new()
+        .add_from(app::installer)
+        .sync_commands(&client, api.as_ref())
+        .await?;
+
+    info!("Commands synced!");
     Ok(())
 }
 
-fn env(var: &str) -> String {
-    env::var(var).unwrap_or_else(|_| panic!("Mandatory environment variable `{var}` is missing!"))
+/// Waits for Ctrl+C, then stops all background tasks and disconnects every
+/// shard, which makes `Client::start` return.
+async fn shutdown_on_ctrl_c(data: Arc<RwLock<TypeMap>>, shard_manager: Arc<Mutex<ShardManager>>) {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        error!(%e, "failed to listen for Ctrl+C");
+        return;
+    }
+
+    info!("Shutting down!");
+
+    TaskManager::get(&data).await.shutdown().await;
+    shard_manager.lock().await.shutdown_all().await;
 }
 
-fn start_tracing() {
-    Registry::default()
-        .with(fmt::layer().compact().without_time())
-        .with(EnvFilter::from_default_env())
-        .with(
-            HierarchicalLayer::new(2)
-                .with_bracketed_fields(true)
-                .with_indent_lines(true),
-        )
-        .init();
+/// Starts logging as configured. The filter has already been validated by
+/// `Config::load`.
+fn start_tracing(logging: &LoggingConfig) {
+    let filter = EnvFilter::new(&logging.filter);
+    let registry = Registry::default().with(filter);
+
+    match logging.format {
+        LogFormat::Tree => registry
+            .with(
+                HierarchicalLayer::new(2)
+                    .with_bracketed_fields(true)
+                    .with_indent_lines(true),
+            )
+            .init(),
+        LogFormat::Compact => registry.with(fmt::layer().compact().without_time()).init(),
+    }
 }